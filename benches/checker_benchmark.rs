@@ -26,7 +26,8 @@ pub fn lexer_benchmark(c: &mut Criterion) {
     c.bench_function(format!("checker_{}", file_name).as_str(), |b| {
       b.iter(|| {
         let checker = &mut stella_checker::checker::Checker::new(file_name, source_code);
-        let check = checker.check(&stella_checker::parser::parser::Parser::new(source_code, file_name).parse_program());
+        let check =
+          checker.check(&stella_checker::parser::parser::Parser::new(source_code, file_name).parse_program().0);
         match check {
           Ok(t) => black_box(t),
           _ => black_box(stella_checker::types::Type::Unknown),
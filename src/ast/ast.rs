@@ -25,11 +25,15 @@ pub enum Statement {
   While(WhileStatement),
   Repeat(RepeatStatement),
   For(ForStatement),
+  ForIn(ForInStatement),
   Break(BreakStatement),
   Goto(GotoStatement),
+  Label(LabelStatement),
   Block(BlockStatement),
   Empty(EmptyStatement),
   TypeDeclaration(TypeDeclaration),
+  Enum(EnumDeclaration),
+  Class(ClassDeclaration),
   Continue(ContinueStatement),
   Local(LocalStatement),
   Expression(Expression),
@@ -44,11 +48,15 @@ impl Statement {
       Statement::While(while_) => while_.get_range(),
       Statement::Repeat(repeat) => repeat.get_range(),
       Statement::For(for_) => for_.get_range(),
+      Statement::ForIn(for_in) => for_in.get_range(),
       Statement::Break(break_) => break_.get_range(),
       Statement::Goto(goto) => goto.get_range(),
+      Statement::Label(label) => label.get_range(),
       Statement::Block(block) => block.get_range(),
       Statement::Empty(empty) => empty.get_range(),
       Statement::TypeDeclaration(declaration) => declaration.get_range(),
+      Statement::Enum(declaration) => declaration.get_range(),
+      Statement::Class(declaration) => declaration.get_range(),
       Statement::Expression(expression) => expression.get_range(),
       Statement::Continue(continue_) => continue_.get_range(),
       Statement::Local(local) => local.get_range(),
@@ -80,10 +88,14 @@ impl AssignExpression {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FunctionStatement {
   pub name: Token,
+  // `a.b` in `function a.b.c()` or `a` in `function a:c()`
+  pub path: Vec<Token>,
+  pub method: bool,
   pub local: bool,
   pub arguments: Vec<Variable>,
   pub return_type: Option<Type>,
-  pub generics: Vec<Type>,
+  // `T, U` in `function map<T, U>()`
+  pub generics: Vec<String>,
   pub body: Box<Statement>,
   pub range: Range,
   pub range_return_type: Option<Range>,
@@ -92,20 +104,28 @@ pub struct FunctionStatement {
 impl FunctionStatement {
   pub fn new(
     name: Token,
+    path: Vec<Token>,
+    method: bool,
     local: bool,
-    generics: Vec<Type>,
+    generics: Vec<String>,
     arguments: Vec<Variable>,
     return_type: Option<Type>,
     body: Statement,
     range: Range,
     range_return_type: Option<Range>,
   ) -> Self {
-    FunctionStatement { name, local, generics, arguments, return_type, body: Box::new(body), range, range_return_type }
+    let body = Box::new(body);
+    FunctionStatement { name, path, method, local, generics, arguments, return_type, body, range, range_return_type }
   }
 
   pub fn get_range(&self) -> Range {
     return self.range.clone();
   }
+
+  // the return type annotation, or the whole function without one
+  pub fn get_return_range(&self) -> Range {
+    self.range_return_type.clone().unwrap_or(self.range.clone())
+  }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -242,6 +262,24 @@ impl ForStatement {
   }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForInStatement {
+  pub variables: Vec<Variable>,
+  pub iterators: Vec<Expression>,
+  pub body: Box<Statement>,
+  pub range: Range,
+}
+
+impl ForInStatement {
+  pub fn new(variables: Vec<Variable>, iterators: Vec<Expression>, body: Statement, range: Range) -> Self {
+    ForInStatement { variables, iterators, body: Box::new(body), range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BreakStatement {
   pub range: Range,
@@ -272,6 +310,22 @@ impl GotoStatement {
   }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LabelStatement {
+  pub name: String,
+  pub range: Range,
+}
+
+impl LabelStatement {
+  pub fn new(name: String, range: Range) -> Self {
+    LabelStatement { name, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BlockStatement {
   pub statements: Vec<Statement>,
@@ -324,6 +378,84 @@ impl TypeDeclaration {
   }
 }
 
+// `enum Dir { Up = "up", Down = "down" }`, members without a value count up from the previous number
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnumDeclaration {
+  pub name: Identifier,
+  pub members: Vec<EnumMember>,
+  pub range: Range,
+}
+
+impl EnumDeclaration {
+  pub fn new(name: Identifier, members: Vec<EnumMember>, range: Range) -> Self {
+    EnumDeclaration { name, members, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnumMember {
+  pub name: Identifier,
+  pub value: LiteralExpression,
+}
+
+impl EnumMember {
+  pub fn new(name: Identifier, value: LiteralExpression) -> Self {
+    EnumMember { name, value }
+  }
+}
+
+// `class Dog extends Animal ... end`, typed fields, a `new` constructor and methods
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClassDeclaration {
+  pub name: Identifier,
+  pub base: Option<Identifier>,
+  pub fields: Vec<ClassField>,
+  pub methods: Vec<FunctionStatement>,
+  pub range: Range,
+}
+
+// the name the constructor body is emitted as, so a method named `init` doesn't replace it
+pub const CLASS_INIT_NAME: &str = "__stella_init";
+
+impl ClassDeclaration {
+  pub fn new(
+    name: Identifier,
+    base: Option<Identifier>,
+    fields: Vec<ClassField>,
+    methods: Vec<FunctionStatement>,
+    range: Range,
+  ) -> Self {
+    ClassDeclaration { name, base, fields, methods, range }
+  }
+
+  pub fn constructor(&self) -> Option<&FunctionStatement> {
+    self.methods.iter().find(|method| method.name.lexeme() == "new")
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+// `readonly name: string`, `nickname?: string`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClassField {
+  pub name: Identifier,
+  pub ty: Type,
+  pub optional: bool,
+  pub readonly: bool,
+}
+
+impl ClassField {
+  pub fn new(name: Identifier, ty: Type, optional: bool, readonly: bool) -> Self {
+    ClassField { name, ty, optional, readonly }
+  }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct TypeFunction {
   pub params: Vec<Type>,
@@ -345,11 +477,14 @@ impl TypeFunction {
 pub struct Variable {
   pub name: Token,
   pub ty: Option<Type>,
+  // `x: number = 1`, only for parameters
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub default: Option<Box<Expression>>,
 }
 
 impl Variable {
   pub fn new(name: Token, ty: Option<Type>) -> Self {
-    return Variable { name, ty };
+    return Variable { name, ty, default: None };
   }
   pub fn get_range(&self) -> Range {
     return self.name.range.clone();
@@ -402,7 +537,9 @@ pub enum Expression {
   Member(MemberExpression),
   Index(IndexExpression),
   Assign(AssignExpression),
+  CompoundAssign(CompoundAssignExpression),
   Variable(Variable),
+  Vararg(VarargExpression),
 }
 
 impl Expression {
@@ -426,12 +563,16 @@ impl Expression {
     Expression::Grouped(GroupedExpression::new(expressions, range))
   }
 
-  pub fn new_table(values: Vec<(Expression, Option<Expression>)>, range: Range) -> Self {
-    Expression::Table(TableExpression::new(values, range))
+  pub fn new_table(fields: Vec<TableField>, range: Range) -> Self {
+    Expression::Table(TableExpression::new(fields, range))
   }
 
   pub fn new_member(base: Expression, identifier: Identifier) -> Self {
-    Expression::Member(MemberExpression::new(Box::new(base), identifier))
+    Expression::Member(MemberExpression::new(Box::new(base), identifier, false))
+  }
+
+  pub fn new_method_member(base: Expression, identifier: Identifier) -> Self {
+    Expression::Member(MemberExpression::new(Box::new(base), identifier, true))
   }
 
   pub fn new_index(base: Expression, index: Expression, bracket_range: Range) -> Self {
@@ -442,6 +583,14 @@ impl Expression {
     Expression::Assign(AssignExpression::new(left, right, range))
   }
 
+  pub fn new_compound_assign(left: Expression, operator: BinaryOperator, right: Expression) -> Self {
+    Expression::CompoundAssign(CompoundAssignExpression::new(Box::new(left), operator, Box::new(right)))
+  }
+
+  pub fn new_vararg(range: Range) -> Self {
+    Expression::Vararg(VarargExpression::new(range))
+  }
+
   pub fn new_unary(operator: UnaryOperator, operand: Expression, range: Range) -> Self {
     Expression::Unary(UnaryExpression::new(operator, Box::new(operand), range))
   }
@@ -451,13 +600,14 @@ impl Expression {
   }
 
   pub fn new_function(
+    generics: Vec<String>,
     arguments: Vec<Variable>,
     return_type: Option<Type>,
     body: Statement,
     range: Range,
     return_range: Option<Range>,
   ) -> Self {
-    Expression::Function(FunctionExpression::new(arguments, return_type, body, range, return_range))
+    Expression::Function(FunctionExpression::new(generics, arguments, return_type, body, range, return_range))
   }
 
   pub fn get_range(&self) -> Range {
@@ -474,6 +624,8 @@ impl Expression {
       Expression::Member(member) => member.get_range(),
       Expression::Index(index) => index.get_range(),
       Expression::Assign(assign) => assign.get_range(),
+      Expression::CompoundAssign(assign) => assign.get_range(),
+      Expression::Vararg(vararg) => vararg.get_range(),
       Expression::Variable(var) => var.get_range(),
     }
   }
@@ -586,10 +738,12 @@ impl UnaryExpression {
 pub struct MemberExpression {
   pub base: Box<Expression>,
   pub identifier: Identifier,
+  // `base:identifier`, only valid as the left side of a call
+  pub method: bool,
 }
 impl MemberExpression {
-  pub fn new(base: Box<Expression>, identifier: Identifier) -> Self {
-    MemberExpression { base, identifier }
+  pub fn new(base: Box<Expression>, identifier: Identifier, method: bool) -> Self {
+    MemberExpression { base, identifier, method }
   }
 
   pub fn get_range(&self) -> Range {
@@ -616,15 +770,33 @@ impl IndexExpression {
     create_middle_range(&left_range, &right_range)
   }
 }
+#[derive(Debug, Serialize, Deserialize)]
+pub enum TableField {
+  // `value`, stored at the next array position
+  Positional(Expression),
+  // `name = value`
+  Named(Identifier, Expression),
+  // `[key] = value`
+  Computed(Expression, Expression),
+}
+
+impl TableField {
+  pub fn get_value(&self) -> &Expression {
+    match self {
+      TableField::Positional(value) | TableField::Named(_, value) | TableField::Computed(_, value) => value,
+    }
+  }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TableExpression {
-  pub values: Vec<(Expression, Option<Expression>)>,
+  pub fields: Vec<TableField>,
   pub range: Range,
 }
 
 impl TableExpression {
-  pub fn new(values: Vec<(Expression, Option<Expression>)>, range: Range) -> Self {
-    TableExpression { values, range }
+  pub fn new(fields: Vec<TableField>, range: Range) -> Self {
+    TableExpression { fields, range }
   }
 
   pub fn get_range(&self) -> Range {
@@ -634,6 +806,7 @@ impl TableExpression {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FunctionExpression {
+  pub generics: Vec<String>,
   pub arguments: Vec<Variable>,
   pub return_type: Option<Type>,
   pub body: Box<Statement>,
@@ -643,13 +816,14 @@ pub struct FunctionExpression {
 
 impl FunctionExpression {
   pub fn new(
+    generics: Vec<String>,
     arguments: Vec<Variable>,
     return_type: Option<Type>,
     body: Statement,
     range: Range,
     range_return_type: Option<Range>,
   ) -> Self {
-    FunctionExpression { arguments, return_type, body: Box::new(body), range, range_return_type }
+    FunctionExpression { generics, arguments, return_type, body: Box::new(body), range, range_return_type }
   }
 
   pub fn get_range(&self) -> Range {
@@ -677,6 +851,40 @@ impl BinaryExpression {
   }
 }
 
+// `left op= right`, with the left side evaluated only once
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompoundAssignExpression {
+  pub left: Box<Expression>,
+  pub operator: BinaryOperator,
+  pub right: Box<Expression>,
+}
+
+impl CompoundAssignExpression {
+  pub fn new(left: Box<Expression>, operator: BinaryOperator, right: Box<Expression>) -> Self {
+    CompoundAssignExpression { left, operator, right }
+  }
+
+  pub fn get_range(&self) -> Range {
+    create_middle_range(&self.left.get_range(), &self.right.get_range())
+  }
+}
+
+// `...` inside a vararg function
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VarargExpression {
+  pub range: Range,
+}
+
+impl VarargExpression {
+  pub fn new(range: Range) -> Self {
+    VarargExpression { range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum LiteralExpression {
   Number(NumberLiteral),
@@ -728,11 +936,14 @@ impl NumberLiteral {
 pub struct StringLiteral {
   pub value: String,
   pub range: Range,
+  // the literal as written when its bytes aren't UTF-8, `value` can't hold `"\xFF"`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub source: Option<String>,
 }
 
 impl StringLiteral {
   pub fn new(value: String, range: Range) -> Self {
-    StringLiteral { value, range }
+    StringLiteral { value, range, source: None }
   }
 }
 
@@ -761,9 +972,10 @@ impl NilLiteral {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum UnaryOperator {
-  Negate,
-  Not,
-  Hash,
+  Negate,     // -
+  Not,        // not
+  Hash,       // #
+  BitwiseNot, // ~
 }
 
 impl UnaryOperator {
@@ -772,22 +984,18 @@ impl UnaryOperator {
       UnaryOperator::Negate => "-",
       UnaryOperator::Not => "not",
       UnaryOperator::Hash => "#",
+      UnaryOperator::BitwiseNot => "~",
     }
   }
 
-  pub fn support_number(&self) -> bool {
-    matches!(self, UnaryOperator::Negate | UnaryOperator::Not | UnaryOperator::Hash)
-  }
-
-  pub fn support_string(&self) -> bool {
-    matches!(self, UnaryOperator::Hash)
-  }
-
-  pub fn support_boolean(&self) -> bool {
-    matches!(self, UnaryOperator::Not)
-  }
-  pub fn support_nil(&self) -> bool {
-    matches!(self, UnaryOperator::Not)
+  // the metatable field lua calls when the operand doesn't support the operator
+  pub fn metamethod(&self) -> Option<&str> {
+    match self {
+      UnaryOperator::Negate => Some("__unm"),
+      UnaryOperator::Hash => Some("__len"),
+      UnaryOperator::BitwiseNot => Some("__bnot"),
+      UnaryOperator::Not => None,
+    }
   }
 }
 
@@ -808,6 +1016,12 @@ pub enum BinaryOperator {
   GreaterThanOrEqual, // >=
   DoubleDot,          // ..
   DoubleSlash,        // //
+  Power,              // ^
+  BitwiseAnd,         // &
+  BitwiseOr,          // |
+  BitwiseXor,         // ~
+  ShiftLeft,          // <<
+  ShiftRight,         // >>
 }
 
 impl BinaryOperator {
@@ -815,21 +1029,27 @@ impl BinaryOperator {
     match self {
       BinaryOperator::Or => 1,
       BinaryOperator::And => 2,
-      BinaryOperator::Equal | BinaryOperator::NotEqual => 3,
-      BinaryOperator::LessThan
+      BinaryOperator::Equal
+      | BinaryOperator::NotEqual
+      | BinaryOperator::LessThan
       | BinaryOperator::GreaterThan
       | BinaryOperator::LessThanOrEqual
-      | BinaryOperator::GreaterThanOrEqual => 4,
-      BinaryOperator::Add | BinaryOperator::Subtract => 5,
-      BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Modulus => 6,
-      BinaryOperator::DoubleSlash => 7,
+      | BinaryOperator::GreaterThanOrEqual => 3,
+      BinaryOperator::BitwiseOr => 4,
+      BinaryOperator::BitwiseXor => 5,
+      BinaryOperator::BitwiseAnd => 6,
+      BinaryOperator::ShiftLeft | BinaryOperator::ShiftRight => 7,
       BinaryOperator::DoubleDot => 8,
+      BinaryOperator::Add | BinaryOperator::Subtract => 9,
+      BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::DoubleSlash | BinaryOperator::Modulus => 10,
+      // unary operators sit at 11
+      BinaryOperator::Power => 12,
     }
   }
 
   /// Returns `true` if the operator is right-associative.
   pub fn is_right_associative(&self) -> bool {
-    matches!(self, BinaryOperator::DoubleDot)
+    matches!(self, BinaryOperator::DoubleDot | BinaryOperator::Power)
   }
 
   /// Determines if the current operator has higher precedence than another operator.
@@ -837,6 +1057,29 @@ impl BinaryOperator {
     self.precedence() > other.precedence()
   }
 
+  /// Returns the metatable field Lua calls for the operator, `a > b` is `b < a`.
+  pub fn metamethod(&self) -> Option<&str> {
+    match self {
+      BinaryOperator::Add => Some("__add"),
+      BinaryOperator::Subtract => Some("__sub"),
+      BinaryOperator::Multiply => Some("__mul"),
+      BinaryOperator::Divide => Some("__div"),
+      BinaryOperator::Modulus => Some("__mod"),
+      BinaryOperator::Power => Some("__pow"),
+      BinaryOperator::DoubleSlash => Some("__idiv"),
+      BinaryOperator::BitwiseAnd => Some("__band"),
+      BinaryOperator::BitwiseOr => Some("__bor"),
+      BinaryOperator::BitwiseXor => Some("__bxor"),
+      BinaryOperator::ShiftLeft => Some("__shl"),
+      BinaryOperator::ShiftRight => Some("__shr"),
+      BinaryOperator::DoubleDot => Some("__concat"),
+      BinaryOperator::Equal | BinaryOperator::NotEqual => Some("__eq"),
+      BinaryOperator::LessThan | BinaryOperator::GreaterThan => Some("__lt"),
+      BinaryOperator::LessThanOrEqual | BinaryOperator::GreaterThanOrEqual => Some("__le"),
+      BinaryOperator::And | BinaryOperator::Or => None,
+    }
+  }
+
   /// Determines if the current operator has equal precedence to another operator.
   pub fn has_equal_precedence_to(&self, other: &BinaryOperator) -> bool {
     self.precedence() == other.precedence()
@@ -847,35 +1090,3 @@ pub struct TypeExpression {
   pub name: Type,
   pub range: Range,
 }
-
-impl BinaryOperator {
-  pub fn support_number(&self) -> bool {
-    matches!(
-      self,
-      BinaryOperator::Add
-        | BinaryOperator::Subtract
-        | BinaryOperator::Multiply
-        | BinaryOperator::Divide
-        | BinaryOperator::Modulus
-        | BinaryOperator::DoubleDot
-        | BinaryOperator::Equal
-        | BinaryOperator::NotEqual
-        | BinaryOperator::LessThan
-        | BinaryOperator::GreaterThan
-        | BinaryOperator::LessThanOrEqual
-        | BinaryOperator::GreaterThanOrEqual
-    )
-  }
-
-  pub fn support_string(&self) -> bool {
-    matches!(self, |BinaryOperator::Equal| BinaryOperator::NotEqual | BinaryOperator::DoubleDot)
-  }
-
-  pub fn support_boolean(&self) -> bool {
-    matches!(self, BinaryOperator::And | BinaryOperator::Or | BinaryOperator::Equal | BinaryOperator::NotEqual)
-  }
-
-  pub fn support_nil(&self) -> bool {
-    matches!(self, BinaryOperator::Equal | BinaryOperator::NotEqual)
-  }
-}
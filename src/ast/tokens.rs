@@ -63,6 +63,7 @@ impl Token {
       "until" => Token::new(TokenKind::Until, range),
       "return" => Token::new(TokenKind::Return, range),
       "break" => Token::new(TokenKind::Break, range),
+      "goto" => Token::new(TokenKind::Goto, range),
       "true" => Token::new(TokenKind::True, range),
       "false" => Token::new(TokenKind::False, range),
       "nil" => Token::new(TokenKind::Nil, range),
@@ -96,6 +97,7 @@ pub enum TokenKind {
   Until,
   Return,
   Break,
+  Goto,
   True,
   False,
   Nil,
@@ -105,41 +107,48 @@ pub enum TokenKind {
   Require,
 
   // Operators and Delimiters
-  Tilde,        // ~
-  Plus,         // +
-  Minus,        // -
-  Star,         // *
-  StarAssign,   // *=
-  Slash,        // /
-  DoubleSlash,  // //
-  SlashAssign,  // /=
-  Percent,      // %
-  Equal,        // ==
-  NotEqual,     // ~=
-  Less,         // <
-  Greater,      // >
-  LessEqual,    // <=
-  GreaterEqual, // >=
-  Assign,       // =
-  PlusAssign,   // +=
-  MinusAssign,  // -=
-  And,          // and
-  Or,           // or
-  Not,          // not
-  Hash,         // #
-  Comma,        // ,
-  Semicolon,    // ;
-  Colon,        // :
-  DoubleColon,  // ::
-  Dot,          // .
-  DoubleDot,    // ..
-  TripleDot,    // ...
-  LeftParen,    // (
-  RightParen,   // )
-  LeftBrace,    // {
-  RightBrace,   // }
-  LeftBracket,  // [
-  RightBracket, // ]
+  Tilde,           // ~
+  Plus,            // +
+  Minus,           // -
+  Star,            // *
+  StarAssign,      // *=
+  Slash,           // /
+  DoubleSlash,     // //
+  SlashAssign,     // /=
+  DoubleDotAssign, // ..=
+  Percent,         // %
+  Caret,           // ^
+  Ampersand,       // &
+  Pipe,            // |
+  ShiftLeft,       // <<
+  ShiftRight,      // >>
+  Equal,           // ==
+  NotEqual,        // ~=
+  Less,            // <
+  Greater,         // >
+  LessEqual,       // <=
+  GreaterEqual,    // >=
+  Assign,          // =
+  PlusAssign,      // +=
+  MinusAssign,     // -=
+  And,             // and
+  Or,              // or
+  Not,             // not
+  Hash,            // #
+  Comma,           // ,
+  Semicolon,       // ;
+  Colon,           // :
+  DoubleColon,     // ::
+  Dot,             // .
+  DoubleDot,       // ..
+  TripleDot,       // ...
+  LeftParen,       // (
+  RightParen,      // )
+  LeftBrace,       // {
+  RightBrace,      // }
+  LeftBracket,     // [
+  RightBracket,    // ]
+  Question,        // ?
 
   // Literals, inclue types
   Identifier(String),
@@ -1,30 +1,40 @@
 use super::{type_utils::CheckResult, Checker};
-use crate::{ast::ast, diagnostics::TypeError, types::Type};
+use crate::{ast::ast, diagnostics::TypeError, types::Type, utils::range::Range};
+use std::collections::BTreeMap;
 
 impl<'a> Checker<'a> {
   // expression = expression
   //
-  pub fn assign_variables(&mut self, left: &ast::Expression, right: Option<&ast::Expression>) -> CheckResult<()> {
+  pub fn assign_variables(&mut self, left: &ast::Expression, right_type: Type) -> CheckResult<()> {
     match left {
-      ast::Expression::Identifier(identifier) => self.assign_identifier(identifier, right),
-      ast::Expression::Variable(variable) => self.assign_variable(variable, right),
-      ast::Expression::Index(index) => self.assign_index(index, right),
-      ast::Expression::Member(member) => self.assign_member(member, right),
-      _ => todo!("Implement more assign expressions"),
+      ast::Expression::Identifier(identifier) => self.assign_identifier(identifier, right_type),
+      ast::Expression::Variable(variable) => self.assign_variable(variable, right_type),
+      ast::Expression::Index(index) => self.assign_index(index, right_type),
+      ast::Expression::Member(member) => self.assign_member(member, right_type),
+      // the parser only gives names, fields and indexes
+      _ => Err(self.create_diagnostic(TypeError::InvalidAssignTarget(Some(left.get_range())))),
     }
   }
 
   // age = 20
   //
-  pub fn assign_identifier(&mut self, left: &ast::Identifier, right: Option<&ast::Expression>) -> CheckResult<()> {
+  pub fn assign_identifier(&mut self, left: &ast::Identifier, right_type: Type) -> CheckResult<()> {
     let lexeme = left.name.as_str();
-
-    let right_type = match right {
-      Some(right) => self.check_expression(right)?.unwrap_or(Type::Nil),
-      None => Type::Nil,
-    };
+    self.ctx.forget_narrowing(lexeme);
 
     let range = left.range.clone();
+    // a declared variable keeps its type, the assigned value only narrows it
+    let (defined, scope_pointer) = self.ctx.defined_in_any_scope(lexeme);
+    let declared_type = self.ctx.get_variable(lexeme, Some(scope_pointer)).cloned();
+    if let Some(declared_type) = declared_type.filter(|declared_type| defined && !declared_type.is_nil()) {
+      let declared_type = self.check_type(&declared_type)?;
+      self.check_record_fields(&declared_type, &right_type, &range)?;
+      if !declared_type.check_match(&right_type) {
+        return Err(self.create_type_mismatch(declared_type, right_type, range));
+      }
+      return self.narrow_assignment(lexeme, &right_type);
+    }
+
     let left_hand_side = &(lexeme, None);
     self.declare_global_variable(&left_hand_side, right_type, range)?;
     Ok(())
@@ -32,14 +42,9 @@ impl<'a> Checker<'a> {
 
   // age:number = 20
   //
-  pub fn assign_variable(&mut self, left: &ast::Variable, right: Option<&ast::Expression>) -> CheckResult<()> {
+  pub fn assign_variable(&mut self, left: &ast::Variable, right_type: Type) -> CheckResult<()> {
     let lexeme = left.name.lexeme();
 
-    let right_type = match right {
-      Some(right) => self.check_expression(right)?.unwrap_or(Type::Nil),
-      None => Type::Nil,
-    };
-
     let range = left.get_range();
     let left_hand_side = &(lexeme, left.ty.clone());
     self.declare_global_variable(&left_hand_side, right_type, range)?;
@@ -47,12 +52,11 @@ impl<'a> Checker<'a> {
   }
 
   // a[1] = b[2]
-  pub fn assign_index(&mut self, index: &ast::IndexExpression, right: Option<&ast::Expression>) -> CheckResult<()> {
-    let index_type = self.check_index_expression(index)?.unwrap_or(Type::Nil);
-    let right_type = match right {
-      Some(right) => self.check_expression(right)?.unwrap_or(Type::Nil),
-      None => Type::Nil,
-    };
+  pub fn assign_index(&mut self, index: &ast::IndexExpression, right_type: Type) -> CheckResult<()> {
+    if let ast::Expression::Literal(ast::LiteralExpression::String(key)) = &*index.index {
+      self.check_readonly_field(&index.base, &key.value, index.get_range())?;
+    }
+    let index_type = self.check_index_assign_target(index)?.unwrap_or(Type::Nil);
     let range = index.get_range();
 
     if !index_type.check_match(&right_type) {
@@ -63,13 +67,13 @@ impl<'a> Checker<'a> {
   }
 
   // a.b = b.c
-  pub fn assign_member(&mut self, member: &ast::MemberExpression, right: Option<&ast::Expression>) -> CheckResult<()> {
-    let member_type = self.check_member_expression(member)?.unwrap_or(Type::Nil);
+  pub fn assign_member(&mut self, member: &ast::MemberExpression, right_type: Type) -> CheckResult<()> {
+    if self.declare_metamethod_field(member, &right_type)? {
+      return Ok(());
+    }
+    self.check_readonly_field(&member.base, &member.identifier.name, member.identifier.range.clone())?;
+    let member_type = self.check_member_assign_target(member)?.unwrap_or(Type::Nil);
 
-    let right_type = match right {
-      Some(right) => self.check_expression(right)?.unwrap_or(Type::Nil),
-      None => Type::Nil,
-    };
     let range = member.get_range();
 
     if !member_type.check_match(&right_type) {
@@ -78,4 +82,54 @@ impl<'a> Checker<'a> {
     }
     Ok(())
   }
+
+  // Vec.__index = Vec, a table can take metamethods after it was declared
+  fn declare_metamethod_field(&mut self, member: &ast::MemberExpression, right_type: &Type) -> CheckResult<bool> {
+    let name = &member.identifier.name;
+    let ast::Expression::Identifier(root) = &*member.base else {
+      return Ok(false);
+    };
+    let (defined, scope_pointer) = self.ctx.defined_in_any_scope(&root.name);
+    if !name.starts_with("__") || !defined {
+      return Ok(false);
+    }
+    let root_type = self.ctx.get_variable(&root.name, Some(scope_pointer)).cloned().unwrap_or(Type::Any);
+    let Type::Table(mut table) = self.check_type(&root_type)? else {
+      return Ok(false);
+    };
+    if table.get_type(name).is_some() {
+      return Ok(false);
+    }
+    table.map.get_or_insert_with(BTreeMap::new).insert(name.to_string(), right_type.clone());
+    self.ctx.use_variable(&root.name, Some(scope_pointer));
+    self.ctx.redeclare_variable(&root.name, Type::Table(table), Some(scope_pointer));
+    Ok(true)
+  }
+
+  // user.id += 1 writes the field as much as user.id = 2 does
+  pub fn check_readonly_target(&mut self, target: &ast::Expression) -> CheckResult<()> {
+    match target {
+      ast::Expression::Member(member) => {
+        self.check_readonly_field(&member.base, &member.identifier.name, member.identifier.range.clone())
+      }
+      ast::Expression::Index(index) => match &*index.index {
+        ast::Expression::Literal(ast::LiteralExpression::String(key)) => {
+          self.check_readonly_field(&index.base, &key.value, index.get_range())
+        }
+        _ => Ok(()),
+      },
+      _ => Ok(()),
+    }
+  }
+
+  // user.id = 2, where `id` is a readonly field
+  fn check_readonly_field(&mut self, base: &ast::Expression, key: &str, range: Range) -> CheckResult<()> {
+    let base_type = self.check_expression(base)?.unwrap_or(Type::Nil);
+    if let Type::Table(table) = self.check_type(&base_type)? {
+      if table.is_readonly(key) {
+        return Err(self.create_diagnostic(TypeError::ReadonlyField(key.to_string(), Some(range))));
+      }
+    }
+    Ok(())
+  }
 }
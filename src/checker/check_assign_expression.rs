@@ -1,16 +1,22 @@
 use super::{type_utils::CheckResult, Checker};
-use crate::{ast::ast::AssignExpression, types::Type};
+use crate::{
+  ast::ast::{AssignExpression, Expression},
+  types::Type,
+};
 
 impl<'a> Checker<'a> {
   pub fn check_assign_expression(&mut self, assign: &AssignExpression) -> CheckResult<Option<Type>> {
-    let result = assign.left.iter().enumerate().map::<CheckResult<()>, _>(|(position, variable)| {
-      let left_expression = variable;
-      let right_expression = assign.right.get(position);
-      self.assign_variables(left_expression, right_expression)?;
-      return Ok(());
-    });
+    let right_types = self.check_expression_list(&assign.right)?;
+    let right_types = self.adjust_values(&assign.right, right_types, assign.left.len());
+    for (position, (left_expression, right_type)) in assign.left.iter().zip(right_types).enumerate() {
+      if let (Expression::Variable(variable), Some(value)) = (left_expression, assign.right.get(position)) {
+        if let Some(declared_type) = &variable.ty {
+          self.check_unknown_fields(declared_type, value, &variable.get_range())?;
+        }
+      }
+      self.assign_variables(left_expression, right_type)?;
+    }
     // assign don't return a type
-    let _ = result.collect::<CheckResult<Vec<()>>>()?;
-    return Ok(None);
+    Ok(None)
   }
 }
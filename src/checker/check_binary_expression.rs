@@ -1,22 +1,108 @@
-use super::{type_utils::CheckResult, Checker};
-use crate::{ast::ast, diagnostics::TypeError, types::Type};
+use super::{
+  narrowing::{is_falsy_member, union_members},
+  type_utils::CheckResult,
+  Checker,
+};
+use crate::{
+  ast::ast,
+  diagnostics::TypeError,
+  types::{LiteralType, Type},
+  utils::range::Range,
+};
 
 impl<'a> Checker<'a> {
   pub fn check_binary_expression(&mut self, binary_expr: &ast::BinaryExpression) -> CheckResult<Option<Type>> {
     let left_type = self.check_expression(&binary_expr.left)?.unwrap();
-    let right_type = self.check_expression(&binary_expr.right)?.unwrap();
-    if left_type.supports_operator(&binary_expr.operator) && right_type.supports_operator(&binary_expr.operator) {
-      let result_type = left_type.get_operator_result_type(&right_type, &binary_expr.operator);
-      return Ok(Some(result_type));
+    let right_type = self.check_right_operand(binary_expr)?.unwrap();
+    let range = binary_expr.get_range();
+    let result_type = match binary_expr.operator {
+      ast::BinaryOperator::And | ast::BinaryOperator::Or => {
+        self.check_logical_operator(&left_type, &binary_expr.operator, &right_type)?
+      }
+      _ => self.check_binary_operator(&left_type, &binary_expr.operator, &right_type, range)?,
+    };
+    Ok(Some(result_type))
+  }
+
+  // `x ~= nil and x > 0`, the right side only runs when the left side is true (or false for `or`)
+  fn check_right_operand(&mut self, binary_expr: &ast::BinaryExpression) -> CheckResult<Option<Type>> {
+    let narrowings = match binary_expr.operator {
+      ast::BinaryOperator::And => self.narrow_condition(&binary_expr.left)?.truthy,
+      ast::BinaryOperator::Or => self.narrow_condition(&binary_expr.left)?.falsy,
+      _ => return self.check_expression(&binary_expr.right),
+    };
+    self.with_narrowings(&narrowings, |checker| checker.check_expression(&binary_expr.right))
+  }
+
+  // `a and b` is `a` when `a` is falsy and `b` otherwise
+  // `a or b` is `a` when `a` is truthy and `b` otherwise
+  fn check_logical_operator(
+    &mut self,
+    left_type: &Type,
+    operator: &ast::BinaryOperator,
+    right_type: &Type,
+  ) -> CheckResult<Type> {
+    let mut left_members = vec![];
+    for member in union_members(&self.check_type(left_type)?) {
+      left_members.extend(union_members(&self.check_type(&member)?));
+    }
+    if left_members.iter().any(|member| matches!(member, Type::Any)) {
+      return Ok(Type::Any);
+    }
+    // an unknown value may be falsy or not, so either side can come out
+    if left_members.iter().any(|member| matches!(member, Type::Unknown)) {
+      return Ok(Type::Unknown);
     }
 
-    let range = binary_expr.get_range();
-    let diagnostic = TypeError::UnsupportedOperator(
-      left_type.to_string(),
-      binary_expr.operator.to_owned(),
-      right_type.to_string(),
-      Some(range),
-    );
+    let is_and = matches!(operator, ast::BinaryOperator::And);
+    // a `boolean` is both, `false` on the `and` side and `true` on the `or` side
+    let left_members: Vec<Type> = left_members
+      .into_iter()
+      .flat_map(|member| match member {
+        Type::Boolean => vec![Type::Literal(LiteralType::Boolean(false)), Type::Literal(LiteralType::Boolean(true))],
+        member => vec![member],
+      })
+      .collect();
+    let mut members: Vec<Type> =
+      left_members.iter().filter(|member| is_falsy_member(member) == is_and).cloned().collect();
+    let right_runs = left_members.iter().any(|member| is_falsy_member(member) != is_and);
+    if right_runs {
+      members.extend(union_members(right_type));
+    }
+
+    // `option<number> or 0` is a `number`, the literal adds nothing
+    let literals_of_known: Vec<Type> = members
+      .iter()
+      .filter(|member| matches!(member, Type::Literal(_)) && members.contains(&member.widen()))
+      .cloned()
+      .collect();
+    members.retain(|member| !literals_of_known.contains(member));
+    // `b and true or false` is a `boolean` again
+    let both = [LiteralType::Boolean(true), LiteralType::Boolean(false)].map(Type::Literal);
+    if both.iter().all(|literal| members.contains(literal)) {
+      members.retain(|member| !both.contains(member));
+      members.push(Type::Boolean);
+    }
+    Ok(self.create_union(members).unwrap_or(Type::Nil))
+  }
+
+  pub fn check_binary_operator(
+    &mut self,
+    left_type: &Type,
+    operator: &ast::BinaryOperator,
+    right_type: &Type,
+    range: Range,
+  ) -> CheckResult<Type> {
+    let (left_type, right_type) = (&left_type.widen(), &right_type.widen());
+    if let Some(result_type) = self.check_binary_metamethod(left_type, operator, right_type, &range)? {
+      return Ok(result_type);
+    }
+    if let Some(result_type) = left_type.get_operator_result_type(right_type, operator) {
+      return Ok(result_type);
+    }
+
+    let diagnostic =
+      TypeError::UnsupportedOperator(left_type.to_string(), operator.to_owned(), right_type.to_string(), Some(range));
 
     Err(self.create_diagnostic(diagnostic))
   }
@@ -2,13 +2,27 @@ use super::{type_utils::CheckResult, Checker};
 use crate::{
   ast::ast,
   diagnostics::TypeError,
-  types::{Type, VariadicType},
+  stdlib::{metatable, select, string},
+  types::{FunctionType, LiteralType, Type, VariadicType},
   utils::range::Range,
 };
 
 impl<'a> Checker<'a> {
   pub fn check_call_expression(&mut self, call_expr: &ast::CallExpression) -> CheckResult<Option<Type>> {
+    if let ast::Expression::Member(member) = &*call_expr.left {
+      if member.method {
+        return self.check_method_call_expression(member, call_expr);
+      }
+    }
     let call_type = self.check_expression(&call_expr.left)?.unwrap();
+    if let ast::Expression::Identifier(identifier) = &*call_expr.left {
+      if identifier.name == "select" && call_type == select::create_select_type() {
+        return self.check_select_call(&call_expr.args);
+      }
+      if identifier.name == "setmetatable" && call_type == metatable::create_setmetatable_type() {
+        return self.check_setmetatable_call(&call_expr.args);
+      }
+    }
     // let (defined, scope_pointer) = self.ctx.defined_in_any_scope(name);
 
     // if !defined {
@@ -36,78 +50,201 @@ impl<'a> Checker<'a> {
     }
   }
 
+  // receiver:method(args) is method(receiver, args)
+  fn check_method_call_expression(
+    &mut self,
+    member: &ast::MemberExpression,
+    call_expr: &ast::CallExpression,
+  ) -> CheckResult<Option<Type>> {
+    let receiver_type = self.check_expression(&member.base)?.unwrap_or(Type::Nil);
+    // strings share the `string` library as their metatable, so `s:upper()` is `string.upper(s)`
+    let method_owner = match self.check_type(&receiver_type)? {
+      Type::String | Type::Literal(LiteralType::String(_)) => string::create_string_type(),
+      _ => receiver_type.clone(),
+    };
+    let method_type = self.check_member_access(&method_owner, member)?.unwrap_or(Type::Nil);
+    let method_type = self.check_type(&method_type)?;
+
+    match method_type {
+      Type::Function(func_type) => self.check_method_signature(&func_type, &receiver_type, member, call_expr),
+      Type::Overload(overload) => self.check_overload_method_call(&overload, &receiver_type, member, call_expr),
+      Type::Any => Ok(Some(Type::Any)),
+      Type::Never => Ok(Some(Type::Never)),
+      Type::Unknown => Err(self.create_diagnostic(TypeError::UnnarrowedUnknown(Some(call_expr.get_range())))),
+      _ => {
+        let range = call_expr.get_range();
+        Err(self.create_diagnostic(TypeError::ExpectedFunction(method_type.to_string(), Some(range))))
+      }
+    }
+  }
+
+  pub fn check_method_signature(
+    &mut self,
+    func_type: &FunctionType,
+    receiver_type: &Type,
+    member: &ast::MemberExpression,
+    call_expr: &ast::CallExpression,
+  ) -> CheckResult<Option<Type>> {
+    let Some((self_type, params)) = func_type.params.split_first() else {
+      let found = self.count_call_arguments(&call_expr.args) + 1;
+      return Err(self.create_function_arity_mismatch(0, found, call_expr.args.get_range()));
+    };
+
+    let self_type = self.check_type(self_type)?;
+    if !self_type.check_match(receiver_type) {
+      let range = member.base.get_range();
+      return Err(self.create_type_mismatch(self_type, receiver_type.clone(), range));
+    }
+
+    self.check_call_arguments(&call_expr.args, params)?;
+    match *func_type.return_type.clone() {
+      Type::Nil => Ok(None),
+      return_type => Ok(Some(return_type)),
+    }
+  }
+
+  // select('#', ...) is the number of values, select(n, ...) the values from the n-th on
+  fn check_select_call(&mut self, args: &ast::Expression) -> CheckResult<Option<Type>> {
+    let expressions = call_arguments(args);
+    let Some((index, values)) = expressions.split_first() else {
+      return Err(self.create_function_arity_mismatch(2, 0, args.get_range()));
+    };
+
+    let value_types = self.check_expression_list(values)?;
+    if let ast::Expression::Literal(ast::LiteralExpression::String(string)) = index {
+      if string.value == "#" {
+        return Ok(Some(Type::Number));
+      }
+    }
+
+    let index_type = self.check_expression(index)?.unwrap_or(Type::Nil);
+    if !index_type.check_match(&Type::Number) {
+      return Err(self.create_type_mismatch(Type::Number, index_type, index.get_range()));
+    }
+
+    match value_types.as_slice() {
+      [Type::Variadic(_)] => Ok(value_types.into_iter().next()),
+      _ => Ok(Some(Type::new_variadic(Type::Any))),
+    }
+  }
+
+  fn count_call_arguments(&self, args: &ast::Expression) -> usize {
+    match args {
+      ast::Expression::Grouped(grouped) => grouped.expressions.len(),
+      _ => 1,
+    }
+  }
+
   pub fn check_call_type(&mut self, call: &Type, args: &ast::Expression, range: Range) -> CheckResult<Option<Type>> {
     match call {
+      Type::Function(func_type) if !func_type.generics.is_empty() => self.check_generic_function_call(func_type, args),
       Type::Function(func_type) => {
         self.check_call_arguments(args, &func_type.params)?;
         Ok(Some(*func_type.return_type.clone()))
       }
-      Type::Unknown => Ok(Some(Type::Unknown)),
+      Type::Overload(overload) => self.check_overload_call(overload, args, range),
+      Type::Table(table) if table.get_metamethod("__call").is_some() => self.check_call_metamethod(table, args, range),
+      Type::Any => Ok(Some(Type::Any)),
+      Type::Never => Ok(Some(Type::Never)),
+      Type::Unknown => Err(self.create_diagnostic(TypeError::UnnarrowedUnknown(Some(range)))),
       _ => Err(self.create_diagnostic(TypeError::ExpectedFunction(call.to_string(), Some(range)))),
     }
   }
 
   pub fn check_call_arguments(&mut self, args: &ast::Expression, params: &[Type]) -> CheckResult<()> {
-    if let ast::Expression::Grouped(ast::GroupedExpression { expressions, range }) = args {
-      let required_params = params.iter().filter(|p| !p.is_variadic()).count();
-      let variadic_param = params.iter().find(|p| p.is_variadic());
+    let arg_types = self.check_expression_list(call_arguments(args))?;
+    self.check_call_argument_types(args, arg_types, params)
+  }
 
-      if expressions.len() < required_params || (variadic_param.is_none() && expressions.len() != params.len()) {
-        let diagnostic = TypeError::FunctionArityMismatch(params.len(), expressions.len(), Some(range.clone()));
-        return Err(self.create_diagnostic(diagnostic));
-      }
+  pub fn check_call_argument_types(
+    &mut self,
+    args: &ast::Expression,
+    mut arg_types: Vec<Type>,
+    params: &[Type],
+  ) -> CheckResult<()> {
+    let expressions = call_arguments(args);
+    let fixed_params = params.iter().filter(|p| !p.is_variadic()).count();
+    let variadic_param = params.iter().find(|p| p.is_variadic());
+    let required_params = self.count_required_params(&params[..fixed_params])?;
 
-      for (arg_expr, param_type) in expressions.iter().zip(params.iter()) {
-        if param_type.is_variadic() {
-          self.check_variadic_arguments(arg_expr, param_type)?;
-        } else {
-          self.check_single_argument(arg_expr, param_type)?;
-        }
+    // a trailing `...` can fill any number of parameters
+    if let Some(Type::Variadic(variadic)) = arg_types.last() {
+      let inner_type = *variadic.inner_type.clone();
+      arg_types.pop();
+      if arg_types.len() < required_params {
+        arg_types.resize(required_params, inner_type);
       }
+    }
 
-      if expressions.len() > required_params {
-        if let Some(variadic_type) = variadic_param {
-          for arg_expr in &expressions[required_params..] {
-            self.check_variadic_arguments(arg_expr, variadic_type)?;
+    if arg_types.len() < required_params || (variadic_param.is_none() && arg_types.len() > fixed_params) {
+      let diagnostic = if required_params == fixed_params {
+        TypeError::FunctionArityMismatch(params.len(), arg_types.len(), Some(args.get_range()))
+      } else {
+        TypeError::FunctionArityRangeMismatch(required_params, fixed_params, arg_types.len(), Some(args.get_range()))
+      };
+      return Err(self.create_diagnostic(diagnostic));
+    }
+
+    for (position, arg_type) in arg_types.iter().enumerate() {
+      // values expanded from a trailing call share its range
+      let range = expressions.get(position).or(expressions.last()).unwrap().get_range();
+      match variadic_param {
+        Some(variadic_type) if position >= fixed_params => {
+          self.check_variadic_argument(arg_type, variadic_type, range)?
+        }
+        _ => {
+          if let Some(expression) = expressions.get(position) {
+            self.check_unknown_fields(&params[position], expression, &range)?;
           }
+          self.check_single_argument(arg_type, &params[position], range)?
         }
       }
-      return Ok(());
     }
-    if params.len() != 1 {
-      return Err(self.create_diagnostic(TypeError::FunctionArityMismatch(params.len(), 1, Some(args.get_range()))));
+    Ok(())
+  }
+
+  // trailing parameters that take nil can be left out, `f(a, b?)` takes one or two arguments
+  fn count_required_params(&mut self, params: &[Type]) -> CheckResult<usize> {
+    let mut required = params.len();
+    while required > 0 && self.check_type(&params[required - 1])?.accepts_nil() {
+      required -= 1;
     }
-    self.check_single_argument(args, params.first().unwrap())
+    Ok(required)
   }
 
-  fn check_single_argument(&mut self, arg: &ast::Expression, param_type: &Type) -> CheckResult<()> {
+  fn check_single_argument(&mut self, arg_type: &Type, param_type: &Type, range: Range) -> CheckResult<()> {
     let param_type_checked = self.check_type(param_type)?;
-    let arg_type = self.check_expression(arg)?.unwrap();
-    if !arg_type.check_match(&param_type_checked) {
+    self.check_record_fields(&param_type_checked, arg_type, &range)?;
+    if !param_type_checked.check_match(arg_type) {
       return Err(self.create_diagnostic(TypeError::MismatchedTypes(
         param_type_checked.to_string(),
         arg_type.to_string(),
-        Some(arg.get_range()),
+        Some(range),
       )));
     }
 
     Ok(())
   }
 
-  fn check_variadic_arguments(&mut self, arg: &ast::Expression, param_type: &Type) -> CheckResult<()> {
+  fn check_variadic_argument(&mut self, arg_type: &Type, param_type: &Type, range: Range) -> CheckResult<()> {
     if let Type::Variadic(VariadicType { inner_type }) = param_type {
-      let arg_type = self.check_expression(arg)?.unwrap();
-
-      let inner_type = self.check_type(&*inner_type)?;
-      if !arg_type.check_match(&inner_type) {
-        let diagnostic =
-          TypeError::MismatchedTypes(inner_type.to_string(), arg_type.to_string(), Some(arg.get_range()));
+      let inner_type = self.check_type(inner_type)?;
+      if !inner_type.check_match(arg_type) {
+        let diagnostic = TypeError::MismatchedTypes(inner_type.to_string(), arg_type.to_string(), Some(range));
         return Err(self.create_diagnostic(diagnostic));
       }
       Ok(())
     } else {
-      let diagnostic = TypeError::ExpectedVariadic(param_type.to_string(), Some(arg.get_range()));
+      let diagnostic = TypeError::ExpectedVariadic(param_type.to_string(), Some(range));
       Err(self.create_diagnostic(diagnostic))
     }
   }
 }
+
+// `f(a, b)` has a list of arguments, `f{...}` and `f"..."` a single one
+pub fn call_arguments(args: &ast::Expression) -> &[ast::Expression] {
+  match args {
+    ast::Expression::Grouped(grouped) => grouped.expressions.as_slice(),
+    _ => std::slice::from_ref(args),
+  }
+}
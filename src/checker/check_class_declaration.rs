@@ -0,0 +1,310 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use super::{type_utils::CheckResult, Checker};
+use crate::{
+  ast::ast,
+  diagnostics::TypeError,
+  types::{FunctionType, TableType, Type},
+  utils::range::Range,
+};
+
+// the instances of a base class and the table with its methods
+struct BaseClass {
+  name: String,
+  instance: TableType,
+  class: TableType,
+}
+
+impl<'a> Checker<'a> {
+  // `class Dog extends Animal ... end` declares the type `Dog` of the instances and the table `Dog`,
+  // with `new`, the constructor body and the methods
+  pub fn check_class_declaration(&mut self, class: &ast::ClassDeclaration) -> CheckResult<Option<Type>> {
+    let base = match &class.base {
+      Some(base) => Some(self.check_base_class(base)?),
+      None => None,
+    };
+    self.check_class_members(class, base.as_ref())?;
+
+    // the signatures come first, so fields and methods can refer to the class
+    let class_table = self.declare_class(class, base.as_ref(), &BTreeMap::new())?;
+    let left_hand_side = &(class.name.name.as_str(), Some(class_table.clone()));
+    self.declare_local_variable(left_hand_side, class_table, class.name.range.clone())?;
+    for field in &class.fields {
+      self.check_type(&field.ty)?;
+    }
+    if let Some(base) = &base {
+      self.check_overrides(class, base)?;
+    }
+
+    let mut return_types = BTreeMap::new();
+    for method in &class.methods {
+      if let Some(return_type) = self.check_class_method(class, base.as_ref(), method)? {
+        return_types.insert(method.name.lexeme().to_string(), return_type);
+      }
+    }
+
+    // methods without a return type return what their body does
+    let class_table = self.declare_class(class, base.as_ref(), &return_types)?;
+    self.ctx.redeclare_variable(&class.name.name, class_table, Some(self.ctx.scope_pointer));
+    self.check_constructor_fields(class, base.as_ref())?;
+    Ok(None)
+  }
+
+  fn check_base_class(&mut self, base: &ast::Identifier) -> CheckResult<BaseClass> {
+    let Some(base_type) = self.ctx.get_type(&base.name).cloned() else {
+      return Err(self.create_diagnostic(TypeError::UndeclaredType(base.name.clone(), Some(base.range.clone()))));
+    };
+    if let Type::Table(instance) = self.check_type(&base_type)? {
+      if let Some(class) = instance.get_index_table().cloned() {
+        // the lowered class reads the table of the base
+        self.ctx.use_variable(&base.name, None);
+        return Ok(BaseClass { name: base.name.clone(), instance, class });
+      }
+    }
+    Err(self.create_diagnostic(TypeError::ExpectedClass(base.name.clone(), Some(base.range.clone()))))
+  }
+
+  // a name is declared once, in the class or in one of its bases
+  fn check_class_members(&mut self, class: &ast::ClassDeclaration, base: Option<&BaseClass>) -> CheckResult<()> {
+    let fields = class.fields.iter().map(|field| (field.name.name.as_str(), field.name.range.clone()));
+    let methods = class.methods.iter().map(|method| (method.name.lexeme(), method.name.range.clone()));
+    let mut names = HashSet::new();
+    for (name, range) in fields.chain(methods) {
+      if !names.insert(name) {
+        return Err(self.create_redeclaration(name, range));
+      }
+      let Some(base) = base else {
+        continue;
+      };
+      let is_base_field = base.instance.get_type(name).is_some();
+      // a method can override a method of the base, a field can't
+      let is_base_method = find_class_member(&base.class, name).is_some();
+      let is_method = class.methods.iter().any(|method| method.name.lexeme() == name);
+      if is_base_field || (is_base_method && !is_method) {
+        let diagnostic = TypeError::DeclaredInBaseClass(name.to_string(), base.name.clone(), Some(range));
+        return Err(self.create_diagnostic(diagnostic));
+      }
+    }
+    Ok(())
+  }
+
+  // declares the type of the instances and gives the type of the class table
+  fn declare_class(
+    &mut self,
+    class: &ast::ClassDeclaration,
+    base: Option<&BaseClass>,
+    return_types: &BTreeMap<String, Type>,
+  ) -> CheckResult<Type> {
+    let name = class.name.name.as_str();
+    let self_type = Type::new(name, class.name.range.clone());
+
+    let mut fields = base.and_then(|base| base.instance.map.clone()).unwrap_or_default();
+    let mut optional = base.map(|base| base.instance.optional.clone()).unwrap_or_default();
+    let mut readonly = base.map(|base| base.instance.readonly.clone()).unwrap_or_default();
+    for field in &class.fields {
+      let field_name = field.name.name.clone();
+      if field.optional {
+        optional.insert(field_name.clone());
+      }
+      if field.readonly {
+        readonly.insert(field_name.clone());
+      }
+      fields.insert(field_name, field.ty.clone());
+    }
+
+    let mut methods = BTreeMap::new();
+    for method in class.methods.iter().filter(|method| method.name.lexeme() != "new") {
+      let return_type = return_types.get(method.name.lexeme()).cloned();
+      let method_type = self.create_method_type(&self_type, method, return_type);
+      methods.insert(method.name.lexeme().to_string(), Type::Function(method_type));
+    }
+
+    // without a constructor, the one of the base takes the same arguments
+    let init_params = match (class.constructor(), base) {
+      (Some(constructor), _) => self.signature_params(&constructor.arguments),
+      (None, Some(base)) => base_init_params(&base.class),
+      (None, None) => vec![],
+    };
+    let mut init_with_self = vec![self_type.clone()];
+    init_with_self.extend(init_params.clone());
+    methods.insert(ast::CLASS_INIT_NAME.to_string(), Type::new_function(init_with_self, Type::Nil));
+    methods.insert("new".to_string(), Type::new_function(init_params, self_type));
+
+    let class_table = TableType {
+      map: Some(methods),
+      metatable: base.map(|base| Box::new(create_index_table(base.class.clone()))),
+      ..Default::default()
+    };
+    let instance = TableType {
+      map: if fields.is_empty() { None } else { Some(fields) },
+      optional,
+      readonly,
+      metatable: Some(Box::new(create_index_table(class_table.clone()))),
+      ..Default::default()
+    };
+    self.ctx.declare_type(name, Type::Table(instance));
+    Ok(Type::Table(class_table))
+  }
+
+  fn create_method_type(
+    &mut self,
+    self_type: &Type,
+    method: &ast::FunctionStatement,
+    body_return_type: Option<Type>,
+  ) -> FunctionType {
+    let mut params = vec![self_type.clone()];
+    params.extend(self.signature_params(&method.arguments));
+    let return_type = method.return_type.clone().or(body_return_type).unwrap_or(Type::Any);
+    FunctionType { generics: method.generics.clone(), params, return_type: Box::new(return_type) }
+  }
+
+  fn signature_params(&mut self, arguments: &[ast::Variable]) -> Vec<Type> {
+    let mut params = vec![];
+    for argument in arguments {
+      let ty = self.check_variadic_type(&argument.name, argument.ty.clone().unwrap_or(Type::Any));
+      params.push(if argument.default.is_some() { Type::new_option(ty) } else { ty });
+    }
+    params
+  }
+
+  // an override takes the arguments of the method it replaces and returns what it does
+  fn check_overrides(&mut self, class: &ast::ClassDeclaration, base: &BaseClass) -> CheckResult<()> {
+    let self_type = Type::new(&class.name.name, class.name.range.clone());
+    for method in class.methods.iter().filter(|method| method.name.lexeme() != "new") {
+      let method_name = method.name.lexeme();
+      let Some(Type::Function(base_method)) = find_class_member(&base.class, method_name) else {
+        continue;
+      };
+      let method_type = self.create_method_type(&self_type, method, None);
+      let expected = self.resolve_method(&base_method, &method.range)?;
+      let found = self.resolve_method(&method_type, &method.range)?;
+      let params_match = expected.params.len() == found.params.len()
+        && found.params.iter().zip(&expected.params).all(|(found, expected)| found.check_match(expected));
+      if !params_match || !expected.return_type.check_match(&found.return_type) {
+        let range = Some(method.name.range.clone());
+        let (expected, found) = (Type::Function(expected).to_string(), Type::Function(found).to_string());
+        let diagnostic =
+          TypeError::IncompatibleOverride(method_name.to_string(), base.name.clone(), expected, found, range);
+        return Err(self.create_diagnostic(diagnostic));
+      }
+    }
+    Ok(())
+  }
+
+  // the method as it's called, without `self`
+  fn resolve_method(&mut self, method: &FunctionType, range: &Range) -> CheckResult<FunctionType> {
+    self.ctx.enter_scope();
+    self.declare_generic_params(&method.generics, range);
+    let params = method.params.iter().skip(1).map(|param| self.check_type(param)).collect::<CheckResult<Vec<_>>>();
+    let return_type = self.check_type(&method.return_type);
+    self.ctx.leave_scope();
+    Ok(FunctionType { generics: method.generics.clone(), params: params?, return_type: Box::new(return_type?) })
+  }
+
+  // gives the return type of the body, for methods without one
+  fn check_class_method(
+    &mut self,
+    class: &ast::ClassDeclaration,
+    base: Option<&BaseClass>,
+    method: &ast::FunctionStatement,
+  ) -> CheckResult<Option<Type>> {
+    let is_constructor = method.name.lexeme() == "new";
+    self.enter_scope();
+    self.declare_generic_params(&method.generics, &method.range);
+    let return_type = self.check_option_type(&method.return_type, is_constructor)?;
+
+    let mut self_type = self.check_type(&Type::new(&class.name.name, class.name.range.clone()))?;
+    if is_constructor {
+      // the constructor sets the fields, readonly ones too
+      if let Type::Table(instance) = &mut self_type {
+        instance.readonly = BTreeSet::new();
+      }
+      if let Some(base) = base {
+        self.ctx.declare_variable("super", Type::new_function(base_init_params(&base.class), Type::Nil), None);
+      }
+    }
+    // `self` and `super` have no range, so they're never reported as unused
+    self.ctx.declare_variable("self", self_type, None);
+    self.declare_function_params(&method.arguments)?;
+    self.ctx.declare_return_param_type(return_type.clone());
+
+    let last_type = self.check_function_body(&method.body, &return_type, method.get_return_range())?;
+    self.leave_scope();
+    Ok(last_type.filter(|_| method.return_type.is_none() && !is_constructor))
+  }
+
+  // every field that can't be nil is set by the constructor, the ones of the base by `super(...)`
+  fn check_constructor_fields(&mut self, class: &ast::ClassDeclaration, base: Option<&BaseClass>) -> CheckResult<()> {
+    let mut assigned = HashSet::new();
+    // without a constructor, the one of the base runs
+    let mut calls_super = class.constructor().is_none();
+    let statements = match class.constructor().map(|constructor| &*constructor.body) {
+      Some(ast::Statement::Block(block)) => block.statements.as_slice(),
+      _ => &[],
+    };
+    for statement in statements {
+      match statement {
+        ast::Statement::Expression(ast::Expression::Assign(assign)) => {
+          assigned.extend(assign.left.iter().filter_map(self_field_name));
+        }
+        ast::Statement::Expression(ast::Expression::Call(call)) => {
+          calls_super |= matches!(&*call.left, ast::Expression::Identifier(identifier) if identifier.name == "super");
+        }
+        _ => {}
+      }
+    }
+
+    let class_name = class.name.name.clone();
+    for field in class.fields.iter().filter(|field| !field.optional && !matches!(field.ty, Type::Option(_))) {
+      if !assigned.contains(field.name.name.as_str()) {
+        let diagnostic =
+          TypeError::UninitializedField(field.name.name.clone(), class_name, Some(field.name.range.clone()));
+        return Err(self.create_diagnostic(diagnostic));
+      }
+    }
+
+    let Some(base) = base.filter(|_| !calls_super) else {
+      return Ok(());
+    };
+    let base_fields = base.instance.map.iter().flatten();
+    let required = base_fields.filter(|(name, ty)| !base.instance.is_optional(name) && !matches!(ty, Type::Option(_)));
+    if let Some((name, _)) = required.into_iter().find(|(name, _)| !assigned.contains(name.as_str())) {
+      let range = class.constructor().map(|constructor| constructor.name.range.clone());
+      return Err(self.create_diagnostic(TypeError::UninitializedField(name.clone(), class_name, range)));
+    }
+    Ok(())
+  }
+}
+
+// `{ __index = class }`, the metatable of the instances
+fn create_index_table(class: TableType) -> TableType {
+  let map = BTreeMap::from([("__index".to_string(), Type::Table(class))]);
+  TableType { map: Some(map), ..Default::default() }
+}
+
+// a member of the class or of one of its bases
+fn find_class_member(class: &TableType, name: &str) -> Option<Type> {
+  match class.get_type(name) {
+    Some(member) => Some(member.clone()),
+    None => find_class_member(class.get_index_table()?, name),
+  }
+}
+
+// the arguments of the constructor body, after `self`
+fn base_init_params(class: &TableType) -> Vec<Type> {
+  match class.get_type(ast::CLASS_INIT_NAME) {
+    Some(Type::Function(init)) => init.params.iter().skip(1).cloned().collect(),
+    _ => vec![],
+  }
+}
+
+// `self.name` in `self.name = name`
+fn self_field_name(target: &ast::Expression) -> Option<&str> {
+  match target {
+    ast::Expression::Member(member) => match &*member.base {
+      ast::Expression::Identifier(identifier) if identifier.name == "self" => Some(member.identifier.name.as_str()),
+      _ => None,
+    },
+    _ => None,
+  }
+}
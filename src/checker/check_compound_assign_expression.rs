@@ -0,0 +1,29 @@
+use super::{type_utils::CheckResult, Checker};
+use crate::{ast::ast, diagnostics::TypeError, types::Type};
+
+impl<'a> Checker<'a> {
+  // `a += b` is checked like `a = a + b`
+  pub fn check_compound_assign_expression(
+    &mut self,
+    assign: &ast::CompoundAssignExpression,
+  ) -> CheckResult<Option<Type>> {
+    self.check_readonly_target(&assign.left)?;
+    // `t[k]` and `t.k` read through an index signature may be absent, like any other read
+    let left_type = self.check_expression(&assign.left)?.unwrap_or(Type::Nil);
+    let right_type = self.check_expression(&assign.right)?.unwrap_or(Type::Nil);
+    let range = assign.get_range();
+    let result_type = self.check_binary_operator(&left_type, &assign.operator, &right_type, range.clone())?;
+
+    // a name keeps its declared type, the result only narrows it
+    if let ast::Expression::Identifier(identifier) = assign.left.as_ref() {
+      self.assign_identifier(identifier, result_type)?;
+      return Ok(None);
+    }
+    if !left_type.check_match(&result_type) {
+      let diagnostic = TypeError::TypeMismatchAssignment(left_type.to_string(), result_type.to_string(), Some(range));
+      return Err(self.create_diagnostic(diagnostic));
+    }
+    // assign don't return a type
+    Ok(None)
+  }
+}
@@ -0,0 +1,33 @@
+use std::collections::BTreeMap;
+
+use super::{type_utils::CheckResult, Checker};
+use crate::{
+  ast::ast,
+  types::{TableType, Type},
+};
+
+impl<'a> Checker<'a> {
+  // `enum Dir { Up = "up" }` declares the type `Dir`, a union of the values, and the table `Dir`
+  pub fn check_enum_declaration(&mut self, declaration: &ast::EnumDeclaration) -> CheckResult<Option<Type>> {
+    let mut members = BTreeMap::new();
+    let mut values = vec![];
+    for member in &declaration.members {
+      let value_type = self.check_literal_expression(&member.value)?.unwrap_or(Type::Nil);
+      if members.insert(member.name.name.clone(), value_type.clone()).is_some() {
+        return Err(self.create_redeclaration(&member.name.name, member.name.range.clone()));
+      }
+      if !values.contains(&value_type) {
+        values.push(value_type);
+      }
+    }
+
+    let name = declaration.name.name.as_str();
+    let enum_type = self.create_type_based_array(values).unwrap_or(Type::Nil);
+    self.ctx.declare_type(name, enum_type);
+
+    let table_type = Type::Table(TableType { map: Some(members), ..Default::default() });
+    let left_hand_side = &(name, Some(table_type.clone()));
+    self.declare_local_variable(left_hand_side, table_type, declaration.name.range.clone())?;
+    Ok(None)
+  }
+}
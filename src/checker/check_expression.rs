@@ -18,6 +18,8 @@ impl<'a> Checker<'a> {
       ast::Expression::Member(member) => self.check_member_expression(member),
       ast::Expression::Index(index) => self.check_index_expression(index),
       ast::Expression::Assign(assign) => self.check_assign_expression(assign),
+      ast::Expression::CompoundAssign(assign) => self.check_compound_assign_expression(assign),
+      ast::Expression::Vararg(vararg) => self.check_vararg_expression(vararg),
       _ => unreachable!(),
     }
   }
@@ -0,0 +1,78 @@
+use super::{type_utils::CheckResult, Checker};
+use crate::{
+  ast::ast,
+  diagnostics::TypeWarning,
+  types::Type,
+  utils::range::{create_middle_range, Range},
+};
+
+impl<'a> Checker<'a> {
+  // every expression in a list gives exactly one value, except a call or `...` in the last
+  // position, which gives all of its values
+  //
+  // f(), g() -> first value of f, all values of g
+  pub fn check_expression_list(&mut self, expressions: &[ast::Expression]) -> CheckResult<Vec<Type>> {
+    let mut types = Vec::with_capacity(expressions.len());
+    for (position, expression) in expressions.iter().enumerate() {
+      let value_type = self.check_expression(expression)?;
+      let is_last = position + 1 == expressions.len();
+      if is_last && matches!(expression, ast::Expression::Call(_) | ast::Expression::Vararg(_)) {
+        types.extend(expand_values(value_type));
+      } else {
+        types.push(first_value(value_type));
+      }
+    }
+    Ok(types)
+  }
+
+  // local a, b, c = 1, 2
+  //
+  // extra values are dropped and missing ones are nil
+  pub fn adjust_values(&mut self, expressions: &[ast::Expression], mut types: Vec<Type>, count: usize) -> Vec<Type> {
+    if expressions.len() > count {
+      let range = list_range(&expressions[count..]);
+      self.diagnostics.add(TypeWarning::ExtraValues(count, expressions.len(), Some(range)).into());
+    }
+
+    // a variadic or unknown tail can fill any number of variables
+    let fill_type = match types.last() {
+      Some(Type::Variadic(variadic)) => Some(*variadic.inner_type.clone()),
+      Some(Type::Any) => Some(Type::Any),
+      _ => None,
+    };
+
+    if let Some(fill_type) = fill_type {
+      types.pop();
+      types.resize(count.max(types.len()), fill_type);
+    } else if !expressions.is_empty() && types.len() < count {
+      let range = list_range(expressions);
+      self.diagnostics.add(TypeWarning::MissingValues(count, types.len(), Some(range)).into());
+    }
+
+    types.resize(count, Type::Nil);
+    types
+  }
+}
+
+fn first_value(value_type: Option<Type>) -> Type {
+  match value_type {
+    Some(Type::Group(group)) => first_value(group.types.into_iter().next()),
+    Some(Type::Variadic(variadic)) => *variadic.inner_type,
+    Some(value_type) => value_type,
+    None => Type::Nil,
+  }
+}
+
+fn expand_values(value_type: Option<Type>) -> Vec<Type> {
+  match value_type {
+    Some(Type::Group(group)) => group.types,
+    Some(value_type) => vec![value_type],
+    None => vec![],
+  }
+}
+
+fn list_range(expressions: &[ast::Expression]) -> Range {
+  let first = expressions.first().unwrap().get_range();
+  let last = expressions.last().unwrap().get_range();
+  create_middle_range(&first, &last)
+}
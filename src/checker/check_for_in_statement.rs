@@ -0,0 +1,146 @@
+use super::{type_utils::CheckResult, Checker};
+use crate::{
+  ast::ast,
+  diagnostics::TypeError,
+  types::{TableType, Type},
+};
+
+impl<'a> Checker<'a> {
+  pub fn check_for_in_statement(&mut self, for_in: &ast::ForInStatement) -> CheckResult<Option<Type>> {
+    let (loop_types, rest_type) = self.check_for_in_iterators(&for_in.iterators)?;
+
+    self.enter_scope();
+    for (position, variable) in for_in.variables.iter().enumerate() {
+      let lexeme = variable.name.lexeme();
+      let assign_type = loop_types.get(position).cloned().unwrap_or(rest_type.clone());
+      let left_hand_side = &(lexeme, variable.ty.clone());
+      self.declare_local_variable(left_hand_side, assign_type, variable.get_range())?;
+    }
+
+    self.forget_loop_narrowings(&for_in.body);
+    let body_type = self.check_statement(&for_in.body)?;
+    self.leave_scope();
+
+    Ok(body_type)
+  }
+
+  // returns the types bound to the loop variables, and the type of any extra variable
+  fn check_for_in_iterators(&mut self, iterators: &[ast::Expression]) -> CheckResult<(Vec<Type>, Type)> {
+    if let Some(loop_types) = self.check_stdlib_iterator(iterators)? {
+      return Ok((loop_types, Type::Nil));
+    }
+
+    let mut iterator_types = vec![];
+    for iterator in iterators {
+      iterator_types.push(self.check_expression(iterator)?.unwrap_or(Type::Nil));
+    }
+
+    // `for x in f()` where `f` returns the `(iterator, state, init)` triple
+    let iterator_type = match iterator_types.remove(0) {
+      Type::Group(group) => group.types.first().cloned().unwrap_or(Type::Nil),
+      iterator_type => iterator_type,
+    };
+
+    match self.check_type(&iterator_type)? {
+      Type::Function(function) => Ok((self.create_iterator_loop_types(&function.return_type), Type::Nil)),
+      Type::Any => Ok((vec![], Type::Any)),
+      // the loop is never reached
+      Type::Never => Ok((vec![], Type::Never)),
+      iterator_type => {
+        let range = iterators.first().unwrap().get_range();
+        Err(self.create_diagnostic(TypeError::ExpectedFunction(iterator_type.to_string(), Some(range))))
+      }
+    }
+  }
+
+  // pairs(t), ipairs(t) and next, t
+  fn check_stdlib_iterator(&mut self, iterators: &[ast::Expression]) -> CheckResult<Option<Vec<Type>>> {
+    let (name, table_expression) = match iterators {
+      [ast::Expression::Call(call)] => match (&*call.left, &*call.args) {
+        (ast::Expression::Identifier(identifier), ast::Expression::Grouped(args)) if args.expressions.len() == 1 => {
+          (identifier.name.as_str(), args.expressions.first().unwrap())
+        }
+        _ => return Ok(None),
+      },
+      [ast::Expression::Identifier(identifier), table_expression, ..] => (identifier.name.as_str(), table_expression),
+      _ => return Ok(None),
+    };
+
+    let is_pairs = matches!((name, iterators.len()), ("pairs", 1) | ("next", _));
+    let is_ipairs = name == "ipairs" && iterators.len() == 1;
+
+    // a local `pairs` shadows the stdlib one
+    if !(is_pairs || is_ipairs) || self.ctx.defined_in_any_scope(name) != (true, 0) {
+      return Ok(None);
+    }
+
+    let table_type = self.check_expression(table_expression)?.unwrap_or(Type::Nil);
+    match self.check_type(&table_type)? {
+      Type::Table(table) if is_ipairs => Ok(Some(vec![Type::Number, self.create_table_array_type(&table)])),
+      Type::Table(table) => Ok(Some(self.create_table_pairs_types(&table))),
+      Type::Any => Ok(Some(vec![Type::Any, Type::Any])),
+      table_type => {
+        let range = table_expression.get_range();
+        Err(self.create_diagnostic(TypeError::ExpectedTable(table_type.to_string(), Some(range))))
+      }
+    }
+  }
+
+  fn create_iterator_loop_types(&self, return_type: &Type) -> Vec<Type> {
+    let mut loop_types = match return_type {
+      Type::Group(group) => group.types.clone(),
+      return_type => vec![return_type.clone()],
+    };
+    // the loop stops when the first value is nil, so inside the body it never is
+    if let Some(first_type) = loop_types.first() {
+      loop_types[0] = self.remove_nil_type(first_type);
+    }
+    loop_types
+  }
+
+  fn remove_nil_type(&self, ty: &Type) -> Type {
+    match ty {
+      Type::Option(option) => self.remove_nil_type(&option.inner_type),
+      Type::Group(group) if group.types.len() == 1 => self.remove_nil_type(&group.types[0]),
+      Type::Union(union) => {
+        let mut types = vec![];
+        for inner_type in union.types.iter().filter(|inner_type| !inner_type.is_nil()) {
+          let inner_type = self.remove_nil_type(inner_type);
+          if !types.contains(&inner_type) {
+            types.push(inner_type);
+          }
+        }
+        self.create_type_based_array(types).unwrap_or(Type::Nil)
+      }
+      ty => ty.clone(),
+    }
+  }
+
+  fn create_table_array_type(&self, table: &TableType) -> Type {
+    self.create_type_based_array(table.element_types()).unwrap_or(Type::Nil)
+  }
+
+  fn create_table_pairs_types(&self, table: &TableType) -> Vec<Type> {
+    let mut key_types = vec![];
+    let mut value_types = vec![];
+    if table.array.is_some() || table.tuple.is_some() {
+      key_types.push(Type::Number);
+      value_types.extend(table.element_types());
+    }
+    if let Some(map) = &table.map {
+      key_types.push(Type::String);
+      value_types.extend(map.values().filter(|value| !value_types.contains(value)).cloned().collect::<Vec<_>>());
+    }
+    if let Some(index) = &table.index {
+      if !key_types.contains(&index.key) {
+        key_types.push(*index.key.clone());
+      }
+      if !value_types.contains(&index.value) {
+        value_types.push(*index.value.clone());
+      }
+    }
+    let key_type = self.create_type_based_array(key_types).unwrap_or(Type::Any);
+    let value_type = self.create_type_based_array(value_types).unwrap_or(Type::Any);
+    vec![key_type, value_type]
+  }
+}
@@ -3,26 +3,21 @@ use crate::{ast::ast, types::Type};
 
 impl<'a> Checker<'a> {
   pub fn check_for_statement(&mut self, for_: &ast::ForStatement) -> CheckResult<Option<Type>> {
-    let init_value = for_.init.initializer.first().unwrap();
-    let init_variable = for_.init.variables.first().unwrap();
-    if for_.init.initializer.len() != 1 || for_.init.variables.len() != 1 {
-      // todo: implement this
-      panic!("Invalid for statement");
-      // let diagnostic =
-      //   TypeError::MismatchedTypes(init_variable.to_string(), init_value.to_string(), Some(init_value.get_range()));
-      // return Err(diagnostic);
-    }
+    // the parser only builds `name = value` for the initializer
+    let (Some(init_variable), Some(init_value)) = (for_.init.variables.first(), for_.init.initializer.first()) else {
+      return Ok(None);
+    };
 
-    let init_type = self.check_expression(&init_value)?.unwrap_or(Type::Nil);
+    let init_type = self.check_expression(init_value)?.unwrap_or(Type::Nil);
     if !init_type.check_match(&Type::Number) {
       let init_range = for_.init.get_range();
       let diagnostic = self.create_type_mismatch(Type::Number, init_type.to_owned(), init_range);
       return Err(diagnostic);
     }
 
-    self.assign_variables(init_variable, Some(init_value))?;
+    self.assign_variables(init_variable, init_type.clone())?;
 
-    let init_type = self.check_expression(&init_value)?.unwrap_or(Type::Nil);
+    let init_type = self.check_expression(init_value)?.unwrap_or(Type::Nil);
     let limit_type = self.check_expression(&for_.limit)?.unwrap_or(Type::Nil);
 
     let step_type = match &for_.step {
@@ -30,6 +25,7 @@ impl<'a> Checker<'a> {
       None => None,
     };
 
+    self.forget_loop_narrowings(&for_.body);
     let body_type = self.check_statement(&for_.body)?;
 
     if !init_type.check_match(&Type::Number) {
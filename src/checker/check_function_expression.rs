@@ -1,15 +1,17 @@
-use super::{type_utils::CheckResult, Checker};
+use super::{check_function_statement::visible_return_type, type_utils::CheckResult, Checker};
 use crate::{ast::ast, types::Type};
 
 impl<'a> Checker<'a> {
   pub fn check_function_expression(&mut self, function: &ast::FunctionExpression) -> CheckResult<Option<Type>> {
-    let mut return_type = self.check_option_type(&function.return_type, false)?;
     self.enter_scope();
+    self.declare_generic_params(&function.generics, &function.range);
+    let return_type = self.check_option_type(&function.return_type, false)?;
     let params = self.declare_function_params(&function.arguments)?;
 
     self.ctx.declare_return_param_type(return_type.clone());
 
-    let last_type = self.check_statement(&function.body)?.unwrap_or(Type::Nil);
+    let range = function.range_return_type.clone().unwrap_or(function.range.clone());
+    let last_type = self.check_function_body(&function.body, &return_type, range)?.unwrap_or(Type::Nil);
 
     if !return_type.check_match(&last_type) {
       let range = function.range_return_type.clone().unwrap_or(function.range.clone());
@@ -17,14 +19,11 @@ impl<'a> Checker<'a> {
       return Err(diagnostic);
     }
 
-    if return_type.can_replace(&last_type) {
-      return_type = last_type
-      // return_type = replace_type(&return_type.to_owned(), &last_type);
-    }
+    let return_type = visible_return_type(&function.return_type, return_type, Some(last_type));
 
     self.leave_scope();
 
-    let function_type = Type::new_function(params, return_type.to_owned());
+    let function_type = Type::new_generic_function(function.generics.clone(), params, return_type.to_owned());
     return Ok(Some(function_type));
   }
 }
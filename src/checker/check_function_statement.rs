@@ -1,36 +1,39 @@
+use std::collections::BTreeMap;
+
 use super::{type_utils::CheckResult, Checker};
 use crate::{
   ast::{ast, tokens::Token},
+  diagnostics::TypeError,
   types::Type,
+  utils::range::Range,
 };
 
 impl<'a> Checker<'a> {
   pub fn check_function_statement(&mut self, function: &ast::FunctionStatement) -> CheckResult<Option<Type>> {
+    if !function.path.is_empty() {
+      return self.check_table_function_statement(function);
+    }
     let function_name = function.name.lexeme();
-    let mut return_type = self.check_option_type(&function.return_type, false)?;
 
     // declare function placeholder
     let anonymous_function = self.ctx.create_anonymous_function();
     let scope_pointer = self.ctx.declare_variable(function_name, anonymous_function, None);
 
     self.enter_scope();
+    self.declare_generic_params(&function.generics, &function.range);
+    let return_type = self.check_option_type(&function.return_type, false)?;
     let params = self.declare_function_params(&function.arguments)?;
 
     self.ctx.declare_return_param_type(return_type.clone());
 
-    let function_type = Type::new_function(params.clone(), return_type.clone());
+    let function_type = Type::new_generic_function(function.generics.clone(), params.clone(), return_type.clone());
 
     self.ctx.redeclare_variable(function_name, function_type, Some(scope_pointer));
 
-    let last_type = self.check_statement(&function.body)?;
-
-    if let Some(last_type) = last_type {
-      if return_type.can_replace(&last_type) {
-        return_type = last_type;
-      }
-    }
+    let last_type = self.check_function_body(&function.body, &return_type, function.get_return_range())?;
+    let return_type = visible_return_type(&function.return_type, return_type, last_type);
 
-    let function_type = Type::new_function(params, return_type.to_owned());
+    let function_type = Type::new_generic_function(function.generics.clone(), params, return_type.to_owned());
 
     self.ctx.redeclare_variable(function_name, function_type.clone(), Some(scope_pointer));
 
@@ -39,25 +42,179 @@ impl<'a> Checker<'a> {
     Ok(None)
   }
 
+  // function a.b.c() ... end, function a:c() ... end
+  fn check_table_function_statement(&mut self, function: &ast::FunctionStatement) -> CheckResult<Option<Type>> {
+    let (root, path) = function.path.split_first().unwrap();
+    let root_name = root.lexeme();
+    let (defined, scope_pointer) = self.ctx.defined_in_any_scope(root_name);
+    if !defined {
+      let diagnostic = TypeError::UndeclaredVariable(root_name.to_string(), Some(root.range.clone()));
+      return Err(self.create_diagnostic(diagnostic));
+    }
+    self.ctx.use_variable(root_name, Some(scope_pointer));
+
+    let root_type = self.ctx.get_variable(root_name, Some(scope_pointer)).cloned().unwrap_or(Type::Any);
+    let owner_type = self.get_table_path_type(&root_type, root, path)?;
+
+    self.enter_scope();
+    self.declare_generic_params(&function.generics, &function.range);
+    let return_type = self.check_option_type(&function.return_type, false)?;
+    let mut params = self.declare_function_params(&function.arguments)?;
+    if function.method {
+      // the receiver is the table as it is here, tables with more fields fit it too
+      params.insert(0, owner_type);
+    }
+
+    // declare function placeholder, so the body can call it
+    let function_type = Type::new_generic_function(function.generics.clone(), params.clone(), return_type.clone());
+    let root_type = self.set_table_path_member(&root_type, root, path, &function.name, function_type)?;
+    self.ctx.redeclare_variable(root_name, root_type.clone(), Some(scope_pointer));
+
+    if function.method {
+      // `self` has no range, so it's never reported as unused
+      let self_type = self.get_table_path_type(&root_type, root, path)?;
+      self.ctx.declare_variable("self", self_type, None);
+    }
+
+    self.ctx.declare_return_param_type(return_type.clone());
+
+    let last_type = self.check_function_body(&function.body, &return_type, function.get_return_range())?;
+    let return_type = visible_return_type(&function.return_type, return_type, last_type);
+    self.leave_scope();
+
+    let function_type = Type::new_generic_function(function.generics.clone(), params, return_type);
+    let root_type = self.ctx.get_variable(root_name, Some(scope_pointer)).cloned().unwrap_or(Type::Any);
+    let root_type = self.set_table_path_member(&root_type, root, path, &function.name, function_type)?;
+    self.ctx.redeclare_variable(root_name, root_type, Some(scope_pointer));
+    Ok(None)
+  }
+
+  fn get_table_path_type(&mut self, table_type: &Type, owner: &Token, path: &[Token]) -> CheckResult<Type> {
+    let table_type = self.check_type(table_type)?;
+    let Some((segment, rest)) = path.split_first() else {
+      return Ok(table_type);
+    };
+    match table_type {
+      Type::Table(table) => match table.get_type(segment.lexeme()) {
+        Some(member_type) => self.get_table_path_type(&member_type.clone(), segment, rest),
+        None => {
+          let diagnostic =
+            TypeError::KeyNotFoundInTable(segment.lexeme().to_string(), table.to_string(), Some(segment.range.clone()));
+          Err(self.create_diagnostic(diagnostic))
+        }
+      },
+      Type::Any => Ok(Type::Any),
+      _ => Err(self.create_diagnostic(TypeError::ExpectedTable(table_type.to_string(), Some(owner.range.clone())))),
+    }
+  }
+
+  fn set_table_path_member(
+    &mut self,
+    table_type: &Type,
+    owner: &Token,
+    path: &[Token],
+    name: &Token,
+    member_type: Type,
+  ) -> CheckResult<Type> {
+    let table_type = self.check_type(table_type)?;
+    let mut table = match table_type {
+      Type::Table(table) => table,
+      Type::Any => return Ok(Type::Any),
+      _ => {
+        let diagnostic = TypeError::ExpectedTable(table_type.to_string(), Some(owner.range.clone()));
+        return Err(self.create_diagnostic(diagnostic));
+      }
+    };
+
+    let (key, member_type) = match path.split_first() {
+      Some((segment, rest)) => {
+        let inner_type = self.get_table_path_type(&Type::Table(table.clone()), owner, &path[..1])?;
+        (segment.lexeme(), self.set_table_path_member(&inner_type, segment, rest, name, member_type)?)
+      }
+      None => (name.lexeme(), member_type),
+    };
+
+    table.map.get_or_insert_with(BTreeMap::new).insert(key.to_string(), member_type);
+    Ok(Type::Table(table))
+  }
+
   pub fn declare_function_params(&mut self, arguments: &Vec<ast::Variable>) -> CheckResult<Vec<Type>> {
-    let params = arguments.iter().map::<CheckResult<Type>, _>(|arg| {
-      let arg_type = if let Some(ty) = &arg.ty { ty } else { &Type::Unknown };
+    // `...` of an outer function isn't visible here, `nil` marks it as unavailable
+    if !arguments.iter().any(|arg| arg.name.is_triple_dot()) {
+      self.ctx.declare_variable("...", Type::Nil, None);
+    }
+
+    let mut params = vec![];
+    for arg in arguments {
+      let arg_type = if let Some(ty) = &arg.ty { ty } else { &Type::Any };
       let lexeme = arg.name.lexeme();
 
       let arg_type = self.check_type(arg_type)?;
+      let arg_type = self.check_variadic_type(&arg.name, arg_type);
+      let arg_type = match &arg.default {
+        Some(default) => self.check_parameter_default(arg, arg_type, default)?,
+        None => arg_type,
+      };
 
       self.ctx.declare_variable(lexeme, arg_type.clone(), None);
       self.ctx.declare_variable_range(lexeme, arg.name.range.clone(), None);
 
-      Ok(arg_type.to_owned())
-    });
-
-    let params = params.collect::<CheckResult<Vec<Type>>>()?;
+      // callers can leave out a parameter with a default
+      params.push(if arg.default.is_some() { Type::new_option(arg_type) } else { arg_type });
+    }
 
     return Ok(params);
   }
 
+  // a body that can't finish, like one ending with `error()`, gives `never`
+  pub fn check_function_body(
+    &mut self,
+    body: &ast::Statement,
+    return_type: &Type,
+    range: Range,
+  ) -> CheckResult<Option<Type>> {
+    let last_type = self.check_statement(body)?;
+    let never_returns = matches!(last_type, None | Some(Type::Never)) && self.always_exits(body);
+    if matches!(return_type, Type::Never) && !never_returns {
+      return Err(self.create_diagnostic(TypeError::NeverFunctionReturns(Some(range))));
+    }
+    Ok(if never_returns { Some(Type::Never) } else { last_type })
+  }
+
+  // `x: number = 1`, the body always sees a `number`
+  fn check_parameter_default(
+    &mut self,
+    arg: &ast::Variable,
+    arg_type: Type,
+    default: &ast::Expression,
+  ) -> CheckResult<Type> {
+    let default_type = self.check_expression(default)?.unwrap_or(Type::Nil);
+    // without an annotation the parameter takes the type of its default
+    if arg.ty.is_none() {
+      return Ok(default_type.widen());
+    }
+    if !arg_type.check_match(&default_type) {
+      return Err(self.create_type_mismatch(arg_type, default_type, default.get_range()));
+    }
+    Ok(arg_type)
+  }
+
+  // type parameters are opaque inside the body, `T` only matches `T`
+  pub fn declare_generic_params(&mut self, generics: &[String], range: &Range) {
+    for name in generics {
+      self.ctx.declare_type(name, Type::new(name, range.clone()));
+    }
+  }
+
   pub fn check_variadic_type(&mut self, token: &Token, inner_type: Type) -> Type {
     return if token.is_triple_dot() { Type::new_variadic(inner_type) } else { inner_type };
   }
 }
+
+// an annotated return type is what callers see, the body only has to fit it
+pub fn visible_return_type(annotation: &Option<Type>, return_type: Type, body_type: Option<Type>) -> Type {
+  match body_type {
+    Some(body_type) if annotation.is_none() => body_type,
+    _ => return_type,
+  }
+}
@@ -1,8 +1,12 @@
+use super::check_stdlib::STDLIB_TYPES;
 use super::type_utils::CheckResult;
 use super::Checker;
 use crate::diagnostics::{Diagnostic, TypeError};
-use crate::types::{FunctionType, GenericCallType, GenericType, GroupType, OptionType, TableType, Type, UnionType};
-use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::types::{
+  FunctionType, GenericCallType, GenericType, GroupType, IndexType, IntersectionType, OptionType, OverloadType,
+  TableType, Type, UnionType,
+};
+use std::collections::{BTreeMap, HashMap};
 
 type GenericBinds = HashMap<String, Type>;
 
@@ -27,7 +31,12 @@ impl<'a> Checker<'a> {
 
     if let Type::Generic(generic) = ty {
       let binds = self.create_generic_table(&generic_call.types, &generic.variables);
-      self.apply_generic_binds(&generic.value, &binds)
+      let bound_type = self.apply_generic_binds(&generic.value, &binds)?;
+      // type operators and intersections can only be resolved once the variables are bound
+      match bound_type {
+        Type::GenericCall(_) | Type::Intersection(_) => self.check_type(&bound_type),
+        _ => Ok(bound_type),
+      }
     } else {
       let diagnostic = TypeError::UndeclaredType(generic_call.name.to_string(), Some(generic_call.range.clone()));
       Err(self.create_diagnostic(diagnostic))
@@ -116,8 +125,10 @@ impl<'a> Checker<'a> {
         }
       }
       Type::Function(function) => self.apply_generic_bind_function(function, binds),
+      Type::Overload(overload) => self.apply_generic_bind_overload(overload, binds),
       Type::Table(table) => self.apply_generic_bind_table(table, binds),
       Type::Union(union) => self.apply_generic_bind_union(union, binds),
+      Type::Intersection(intersection) => self.apply_generic_bind_intersection(intersection, binds),
       Type::Option(option) => self.apply_generic_bind_option(option, binds),
       Type::Group(group) => self.apply_generic_bind_group(group, binds),
       Type::GenericCall(generic_call) => self.apply_generic_bind_call(generic_call, binds),
@@ -133,10 +144,24 @@ impl<'a> Checker<'a> {
     Ok(Type::new_function(params, return_type))
   }
 
+  pub fn apply_generic_bind_overload(&self, overload: &OverloadType, binds: &GenericBinds) -> CheckResult<Type> {
+    let mut functions = vec![];
+    for function in &overload.functions {
+      if let Type::Function(function) = self.apply_generic_bind_function(function, binds)? {
+        functions.push(function);
+      }
+    }
+    Ok(Type::new_overload(functions))
+  }
+
   pub fn apply_generic_bind_table(&self, table: &TableType, binds: &GenericBinds) -> CheckResult<Type> {
-    let array = if let Some(array) = &table.array {
-      let array_bind = array.iter().map(|ty| self.apply_generic_binds(ty, binds)).collect::<Result<HashSet<_>, _>>()?;
-      Some(array_bind)
+    let array = match &table.array {
+      Some(array) => Some(Box::new(self.apply_generic_binds(array, binds)?)),
+      None => None,
+    };
+
+    let tuple = if let Some(tuple) = &table.tuple {
+      Some(tuple.iter().map(|ty| self.apply_generic_binds(ty, binds)).collect::<Result<Vec<_>, _>>()?)
     } else {
       None
     };
@@ -152,7 +177,18 @@ impl<'a> Checker<'a> {
       None
     };
 
-    Ok(Type::new_table(array, map))
+    let index = match &table.index {
+      Some(index) => {
+        let key = self.apply_generic_binds(&index.key, binds)?;
+        Some(IndexType::new(key, self.apply_generic_binds(&index.value, binds)?))
+      }
+      None => None,
+    };
+
+    let optional = table.optional.clone();
+    let readonly = table.readonly.clone();
+    let metatable = table.metatable.clone();
+    Ok(Type::Table(TableType { array, tuple, map, index, optional, readonly, metatable }))
   }
 
   pub fn apply_generic_bind_union(&self, union: &UnionType, binds: &GenericBinds) -> CheckResult<Type> {
@@ -160,6 +196,16 @@ impl<'a> Checker<'a> {
     Ok(Type::new_union(types))
   }
 
+  pub fn apply_generic_bind_intersection(
+    &self,
+    intersection: &IntersectionType,
+    binds: &GenericBinds,
+  ) -> CheckResult<Type> {
+    let types =
+      intersection.types.iter().map(|ty| self.apply_generic_binds(ty, binds)).collect::<Result<Vec<_>, _>>()?;
+    Ok(Type::new_intersection(types, intersection.range.clone()))
+  }
+
   pub fn apply_generic_bind_option(&self, option: &OptionType, binds: &GenericBinds) -> CheckResult<Type> {
     let inner_type = self.apply_generic_binds(&option.inner_type, binds)?;
     Ok(Type::new_option(inner_type))
@@ -173,6 +219,10 @@ impl<'a> Checker<'a> {
   pub fn apply_generic_bind_call(&self, call: &GenericCallType, binds: &GenericBinds) -> CheckResult<Type> {
     let types = call.types.iter().map(|ty| self.apply_generic_binds(ty, binds)).collect::<Result<Vec<_>, _>>()?;
 
+    if STDLIB_TYPES.contains(&call.name.as_str()) {
+      return Ok(Type::new_generic_call(call.name.clone(), types, call.range.clone()));
+    }
+
     let base_type = self
       .ctx
       .get_type(&call.name)
@@ -14,12 +14,13 @@ impl<'a> Checker<'a> {
       // I think we should return unknown and save the error in diagnostics... is it ok? :(
       // return Err(self.create_diagnostic(diagnostic));
       self.diagnostics.add(self.create_diagnostic(diagnostic));
-      return Ok(Some(Type::Unknown));
+      return Ok(Some(Type::Any));
     }
 
     self.ctx.use_variable(&ident.name, Some(scope_pointer));
 
-    let tyy = self.ctx.get_variable(&ident.name, Some(scope_pointer));
+    // the innermost scope is the declaring one or one that narrows it
+    let tyy = self.ctx.get_variable(&ident.name, None);
     match tyy {
       Some(tyy) => Ok(Some(self.check_type(&tyy.to_owned())?)),
       None => Ok(None),
@@ -38,6 +39,17 @@ impl<'a> Checker<'a> {
     let tty = self.ctx.get_type(ident.name.as_str()).cloned().ok_or_else(|| {
       self.create_diagnostic(TypeError::UndeclaredType(ident.name.to_string(), Some(ident.range.clone())))
     })?;
-    return Ok(self.check_type(&tty)?);
+    // type parameters of generic functions resolve to themselves
+    if matches!(&tty, types::Type::Alias(alias) if alias.name == ident.name) {
+      return Ok(tty);
+    }
+    // a name inside its own definition stays a name
+    if self.resolving_aliases.contains(&ident.name) {
+      return Ok(types::Type::Alias(ident.clone()));
+    }
+    self.resolving_aliases.push(ident.name.clone());
+    let resolved = self.check_type(&tty);
+    self.resolving_aliases.pop();
+    resolved
   }
 }
@@ -2,66 +2,46 @@ use super::{type_utils::CheckResult, Checker};
 use crate::{ast::ast, types::Type};
 impl<'a> Checker<'a> {
   pub fn check_if_statement(&mut self, if_stmt: &ast::IfStatement) -> CheckResult<Option<Type>> {
-    let condition_type = self.check_expression(&if_stmt.condition)?.unwrap_or(Type::Nil);
-    if !condition_type.check_match(&Type::Boolean) {
-      let condition_range = if_stmt.condition.get_range();
-      return Err(self.create_type_mismatch(Type::new_boolean(), condition_type, condition_range));
-    }
+    let narrowing = self.check_condition(&if_stmt.condition)?;
+    let mut result_types = vec![];
 
     self.enter_scope();
-    let narrowing = self.is_condition_narrowing(&if_stmt.condition);
-    if narrowing {
-      let condition_narrowing = self.get_specified_type(&if_stmt.condition);
-      match condition_narrowing {
-        Ok((Some(name), Some(narrowing_type))) => {
-          self.ctx.declare_variable(name.as_str(), narrowing_type, None);
-        }
-        _ => {}
-      }
-    }
-
+    self.apply_narrowings(&narrowing.truthy);
     let then_type = self.check_statement(&if_stmt.then_body)?;
-
     self.leave_scope();
 
-    let mut result_types = vec![];
-
     if let Some(then_type) = then_type {
       result_types.push(then_type);
     }
 
+    // every later branch runs only when the conditions before it were false
+    let mut remaining = narrowing.falsy;
+
     for else_if_branch in &if_stmt.else_if_branches {
-      let else_if_condition_type = self.check_expression(&else_if_branch.condition)?.unwrap_or(Type::Nil);
-      if !else_if_condition_type.check_match(&Type::Boolean) {
-        let condition_range = else_if_branch.condition.get_range();
-        return Err(self.create_type_mismatch(Type::new_boolean(), else_if_condition_type, condition_range));
-      }
       self.enter_scope();
+      self.apply_narrowings(&remaining);
 
-      if self.is_condition_narrowing(&else_if_branch.condition) {
-        let condition_narrowing = self.get_specified_type(&else_if_branch.condition);
-        match condition_narrowing {
-          Ok((Some(name), Some(narrowing_type))) => {
-            self.ctx.declare_variable(name.as_str(), narrowing_type, None);
-          }
-          _ => {}
-        }
-      }
+      let else_if_narrowing = self.check_condition(&else_if_branch.condition)?;
+      self.apply_narrowings(&else_if_narrowing.truthy);
 
       if let Some(else_if_type) = self.check_statement(&else_if_branch.then_branch)? {
         result_types.push(else_if_type);
       }
-
       self.leave_scope();
+      remaining.extend(else_if_narrowing.falsy);
     }
 
     if let Some(else_body) = &if_stmt.else_body {
       self.enter_scope();
+      self.apply_narrowings(&remaining);
       if let Some(else_type) = self.check_statement(else_body)? {
         result_types.push(else_type);
       }
       self.leave_scope();
     }
+
+    self.narrow_after_if(if_stmt, narrowing.truthy, remaining);
+
     if result_types.len() > 1 {
       return Ok(Some(Type::new_union(result_types)));
     }
@@ -72,4 +52,24 @@ impl<'a> Checker<'a> {
 
     Ok(None)
   }
+
+  // if not user then return end
+  //
+  // when only one branch reaches the code after the if, its narrowing holds there
+  fn narrow_after_if(
+    &mut self,
+    if_stmt: &ast::IfStatement,
+    truthy: Vec<(String, Type)>,
+    remaining: Vec<(String, Type)>,
+  ) {
+    let then_exits = self.always_exits(&if_stmt.then_body);
+    let else_if_exit = if_stmt.else_if_branches.iter().all(|branch| self.always_exits(&branch.then_branch));
+    let else_exits = if_stmt.else_body.as_ref().is_some_and(|else_body| self.always_exits(else_body));
+
+    if then_exits && else_if_exit && !else_exits {
+      self.apply_narrowings(&remaining);
+    } else if !then_exits && else_if_exit && else_exits {
+      self.apply_narrowings(&truthy);
+    }
+  }
 }
@@ -2,58 +2,124 @@ use super::{type_utils::CheckResult, Checker};
 use crate::{
   ast::ast,
   diagnostics::{Diagnostic, TypeError},
-  types::{TableType, Type},
+  types::{IndexType, TableType, Type},
   utils::range::Range,
 };
 
 pub enum Accessor {
   String(String),
   Number(usize),
+  // a key only known at runtime
+  Dynamic(Type),
 }
 
 impl<'a> Checker<'a> {
-  // todo: improve this... :(
   pub fn check_index_expression(&mut self, table_expr: &ast::IndexExpression) -> CheckResult<Option<Type>> {
+    self.check_index_target(table_expr, !self.unchecked_index)
+  }
+
+  // `t[k] = nil` removes the entry, so writing through an index signature always accepts nil
+  pub fn check_index_assign_target(&mut self, table_expr: &ast::IndexExpression) -> CheckResult<Option<Type>> {
+    self.check_index_target(table_expr, true)
+  }
+
+  // todo: improve this... :(
+  fn check_index_target(
+    &mut self,
+    table_expr: &ast::IndexExpression,
+    may_be_absent: bool,
+  ) -> CheckResult<Option<Type>> {
     let base_type = self.check_expression(&table_expr.base)?.unwrap_or(Type::Nil);
     let base_type = self.check_type(&base_type)?;
     let base_range = table_expr.base.get_range();
     match base_type {
       Type::Table(ref table_type) => {
         let acc = self.extract_accessor(&table_expr.index)?;
-        let tty = self.check_index_access(table_type, acc, table_expr.index.get_range())?;
+        let range = table_expr.index.get_range();
+        let tty = self.check_index_access(table_type, acc, may_be_absent, range)?;
         Ok(Some(tty))
       }
-      Type::Unknown => Ok(Some(Type::Unknown)),
+      Type::Any => Ok(Some(Type::Any)),
+      Type::Never => Ok(Some(Type::Never)),
+      Type::Unknown => Err(self.create_diagnostic(TypeError::UnnarrowedUnknown(Some(base_range)))),
       _ => Err(self.create_diagnostic(TypeError::ExpectedTable(base_type.to_string(), Some(base_range)))),
     }
   }
 
-  fn check_index_access(&self, table: &TableType, acc: Option<Accessor>, range: Range) -> CheckResult<Type> {
-    match acc {
-      Some(Accessor::String(name)) => self.check_index_access_string(table, &name, range),
-      Some(Accessor::Number(index)) => self.check_index_access_number(table),
-      // todo: return union type based on table values
-      None => Ok(Type::Unknown),
+  fn check_index_access(
+    &self,
+    table: &TableType,
+    acc: Option<Accessor>,
+    may_be_absent: bool,
+    range: Range,
+  ) -> CheckResult<Type> {
+    match (acc, &table.index) {
+      (Some(Accessor::String(name)), _) => self.check_index_access_string(table, &name, may_be_absent, range),
+      (Some(Accessor::Number(_)), Some(index)) if table.array.is_none() && table.tuple.is_none() => {
+        self.check_index_signature(index, &Type::Number, may_be_absent, range)
+      }
+      (Some(Accessor::Number(position)), _) => self.check_index_access_number(table, position, range),
+      (Some(Accessor::Dynamic(key_type)), Some(index)) => {
+        self.check_index_signature(index, &key_type, may_be_absent, range)
+      }
+      (Some(Accessor::Dynamic(key_type)), None) => {
+        self.check_accessor_type(&key_type, range)?;
+        match key_type {
+          Type::Number => Ok(self.create_type_based_array(table.element_types()).unwrap_or(Type::Any)),
+          // todo: return union type based on table values
+          _ => Ok(Type::Any),
+        }
+      }
+      (None, _) => Ok(Type::Any),
     }
   }
 
-  fn check_index_access_string(&self, table: &TableType, name: &str, range: Range) -> CheckResult<Type> {
-    if let Some(value_type) = table.get_type(name) {
-      Ok(value_type.clone())
+  fn check_index_access_string(
+    &self,
+    table: &TableType,
+    name: &str,
+    may_be_absent: bool,
+    range: Range,
+  ) -> CheckResult<Type> {
+    if let Some(value_type) = table.get_field_type(name) {
+      Ok(value_type)
+    } else if let Some(index) = table.index.as_ref().filter(|index| index.key.check_match(&Type::String)) {
+      self.check_index_signature(index, &Type::String, may_be_absent, range)
     } else {
       Err(self.create_diagnostic(TypeError::KeyNotFoundInTable(name.to_string(), table.to_string(), Some(range))))
     }
   }
 
-  fn check_index_access_number(&self, table: &TableType) -> CheckResult<Type> {
-    let types = table.array.as_ref().map(|array| array.iter().cloned().collect::<Vec<_>>()).unwrap_or(vec![]);
-    if types.is_empty() {
-      return Ok(Type::Nil);
+  // `{[string]: number}` may not have the key, so reading gives `option<number>`
+  pub fn check_index_signature(
+    &self,
+    index: &IndexType,
+    key_type: &Type,
+    may_be_absent: bool,
+    range: Range,
+  ) -> CheckResult<Type> {
+    if !index.key.check_match(key_type) {
+      return Err(self.create_type_mismatch(*index.key.clone(), key_type.clone(), range));
+    }
+    match *index.value.clone() {
+      value_type @ (Type::Option(_) | Type::Any) => Ok(value_type),
+      value_type if may_be_absent => Ok(Type::new_option(value_type)),
+      value_type => Ok(value_type),
     }
-    if types.len() == 1 {
-      return Ok(types.first().unwrap().clone());
+  }
+
+  // `t[2]` of a tuple is the type at that position
+  fn check_index_access_number(&self, table: &TableType, position: usize, range: Range) -> CheckResult<Type> {
+    if let Some(tuple) = &table.tuple {
+      return match position.checked_sub(1).and_then(|index| tuple.get(index)) {
+        Some(element) => Ok(element.clone()),
+        None => {
+          let diagnostic = TypeError::TupleIndexOutOfRange(position, tuple.len(), Some(range));
+          Err(self.create_diagnostic(diagnostic))
+        }
+      };
     }
-    return Ok(Type::new_union(types));
+    Ok(table.array.as_deref().cloned().unwrap_or(Type::Nil))
   }
 
   fn extract_accessor(&mut self, index_expr: &ast::Expression) -> Result<Option<Accessor>, Diagnostic> {
@@ -72,17 +138,14 @@ impl<'a> Checker<'a> {
   }
 
   fn handle_non_literal_index(&mut self, index_expr: &ast::Expression) -> Result<Option<Accessor>, Diagnostic> {
-    self.check_expression_index(index_expr)?;
-    Ok(None)
+    let key_type = self.check_expression(index_expr)?.unwrap_or(Type::Nil).widen();
+    Ok(Some(Accessor::Dynamic(key_type)))
   }
 
-  fn check_expression_index(&mut self, key_expr: &ast::Expression) -> Result<(), Diagnostic> {
-    let expr_type = self.check_expression(key_expr)?.unwrap_or(Type::Nil);
-    match expr_type {
+  fn check_accessor_type(&self, key_type: &Type, range: Range) -> Result<(), Diagnostic> {
+    match key_type {
       Type::String | Type::Number => Ok(()),
-      _ => Err(
-        self.create_diagnostic(TypeError::MismatchedAccessorType(expr_type.to_string(), Some(key_expr.get_range()))),
-      ),
+      _ => Err(self.create_diagnostic(TypeError::MismatchedAccessorType(key_type.to_string(), Some(range)))),
     }
   }
 }
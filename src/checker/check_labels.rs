@@ -0,0 +1,201 @@
+use super::Checker;
+use crate::{ast::ast, diagnostics::TypeError, utils::range::Range};
+use std::slice;
+
+// labels and pending gotos of one function, resolved the same way lua's parser does
+#[derive(Default)]
+struct LabelContext {
+  labels: Vec<Label>,
+  gotos: Vec<Label>,
+  // locals currently in scope, in declaration order
+  locals: Vec<String>,
+  // loops enclosing the current statement, for `break` and `continue`
+  loops: usize,
+}
+
+struct Label {
+  name: String,
+  range: Range,
+  // number of locals in scope at this point
+  active_locals: usize,
+}
+
+impl<'a> Checker<'a> {
+  pub fn check_labels(&mut self, statements: &[ast::Statement]) {
+    let mut context = LabelContext::default();
+    self.check_labels_in_block(&mut context, statements, false);
+    for goto in context.gotos {
+      self.diagnostics.add(self.create_diagnostic(TypeError::UndefinedLabel(goto.name, Some(goto.range))));
+    }
+  }
+
+  fn check_labels_in_block(&mut self, context: &mut LabelContext, statements: &[ast::Statement], repeat_body: bool) {
+    let first_label = context.labels.len();
+    let first_goto = context.gotos.len();
+    let block_locals = context.locals.len();
+
+    for (position, statement) in statements.iter().enumerate() {
+      match statement {
+        ast::Statement::Label(label) => {
+          // labels followed only by other labels are at the end of the block, outside the scope of its locals,
+          // except in `repeat`, where `until` still sees them
+          let rest = &statements[position + 1..];
+          let last = !repeat_body
+            && rest.iter().all(|statement| matches!(statement, ast::Statement::Label(_) | ast::Statement::Empty(_)));
+          let active_locals = if last { block_locals } else { context.locals.len() };
+          self.declare_label(context, label, active_locals, first_goto);
+        }
+        ast::Statement::Goto(goto) => {
+          let name = goto.label.clone().unwrap_or_default();
+          // backward jumps never enter the scope of a local
+          if !context.labels.iter().any(|label| label.name == name) {
+            let active_locals = context.locals.len();
+            context.gotos.push(Label { name, range: goto.get_range(), active_locals });
+          }
+        }
+        ast::Statement::Local(local) => {
+          local.initializer.iter().for_each(|expression| self.check_labels_in_expression(expression));
+          context.locals.extend(local.variables.iter().map(|variable| variable.name.lexeme().to_string()));
+        }
+        ast::Statement::Function(function) => {
+          if function.local {
+            context.locals.push(function.name.lexeme().to_string());
+          }
+          self.check_labels(block_statements(&function.body));
+        }
+        ast::Statement::If(if_) => {
+          self.check_labels_in_expression(&if_.condition);
+          self.check_labels_in_block(context, block_statements(&if_.then_body), false);
+          for else_if in &if_.else_if_branches {
+            self.check_labels_in_expression(&else_if.condition);
+            self.check_labels_in_block(context, block_statements(&else_if.then_branch), false);
+          }
+          if let Some(else_body) = &if_.else_body {
+            self.check_labels_in_block(context, block_statements(else_body), false);
+          }
+        }
+        ast::Statement::While(while_) => {
+          self.check_labels_in_expression(&while_.condition);
+          self.check_labels_in_loop(context, &while_.body, false);
+        }
+        ast::Statement::Repeat(repeat) => {
+          self.check_labels_in_loop(context, &repeat.body, true);
+          self.check_labels_in_expression(&repeat.condition);
+        }
+        ast::Statement::For(for_) => {
+          for_.init.initializer.iter().for_each(|expression| self.check_labels_in_expression(expression));
+          self.check_labels_in_expression(&for_.limit);
+          for_.step.iter().for_each(|expression| self.check_labels_in_expression(expression));
+          self.check_labels_in_loop(context, &for_.body, false);
+        }
+        ast::Statement::ForIn(for_in) => {
+          for_in.iterators.iter().for_each(|expression| self.check_labels_in_expression(expression));
+          self.check_labels_in_loop(context, &for_in.body, false);
+        }
+        ast::Statement::Break(break_) if context.loops == 0 => {
+          let diagnostic = TypeError::OutsideLoop("break".to_string(), Some(break_.get_range()));
+          self.diagnostics.add(self.create_diagnostic(diagnostic));
+        }
+        ast::Statement::Continue(continue_) if context.loops == 0 => {
+          let diagnostic = TypeError::OutsideLoop("continue".to_string(), Some(continue_.get_range()));
+          self.diagnostics.add(self.create_diagnostic(diagnostic));
+        }
+        ast::Statement::Block(block) => self.check_labels_in_block(context, &block.statements, false),
+        ast::Statement::Return(return_) => {
+          return_.values.iter().for_each(|expression| self.check_labels_in_expression(expression));
+        }
+        ast::Statement::Expression(expression) => self.check_labels_in_expression(expression),
+        _ => {}
+      }
+    }
+
+    // leaving the block: its labels are no longer visible and its pending gotos leave the scope of its locals
+    context.labels.truncate(first_label);
+    for goto in context.gotos[first_goto..].iter_mut() {
+      goto.active_locals = goto.active_locals.min(block_locals);
+    }
+    context.locals.truncate(block_locals);
+  }
+
+  fn check_labels_in_loop(&mut self, context: &mut LabelContext, body: &ast::Statement, repeat_body: bool) {
+    context.loops += 1;
+    self.check_labels_in_block(context, block_statements(body), repeat_body);
+    context.loops -= 1;
+  }
+
+  fn declare_label(
+    &mut self,
+    context: &mut LabelContext,
+    label: &ast::LabelStatement,
+    active_locals: usize,
+    first_goto: usize,
+  ) {
+    if context.labels.iter().any(|visible| visible.name == label.name) {
+      let diagnostic = TypeError::DuplicateLabel(label.name.clone(), Some(label.get_range()));
+      self.diagnostics.add(self.create_diagnostic(diagnostic));
+      return;
+    }
+
+    // resolve the pending gotos of this block (including the ones coming from nested blocks)
+    let mut position = first_goto;
+    while position < context.gotos.len() {
+      if context.gotos[position].name != label.name {
+        position += 1;
+        continue;
+      }
+      let goto = context.gotos.remove(position);
+      if goto.active_locals < active_locals {
+        let local = context.locals[goto.active_locals].clone();
+        let diagnostic = TypeError::JumpIntoLocalScope(goto.name, local, Some(goto.range));
+        self.diagnostics.add(self.create_diagnostic(diagnostic));
+      }
+    }
+
+    let range = label.get_range();
+    context.labels.push(Label { name: label.name.clone(), range, active_locals });
+  }
+
+  // gotos can't cross function boundaries, so each function expression gets its own labels
+  fn check_labels_in_expression(&mut self, expression: &ast::Expression) {
+    match expression {
+      ast::Expression::Function(function) => self.check_labels(block_statements(&function.body)),
+      ast::Expression::Call(call) => {
+        self.check_labels_in_expression(&call.left);
+        self.check_labels_in_expression(&call.args);
+      }
+      ast::Expression::Unary(unary) => self.check_labels_in_expression(&unary.operand),
+      ast::Expression::Binary(binary) => {
+        self.check_labels_in_expression(&binary.left);
+        self.check_labels_in_expression(&binary.right);
+      }
+      ast::Expression::Grouped(grouped) => {
+        grouped.expressions.iter().for_each(|expression| self.check_labels_in_expression(expression));
+      }
+      ast::Expression::Table(table) => {
+        for field in &table.fields {
+          if let ast::TableField::Computed(key, _) = field {
+            self.check_labels_in_expression(key);
+          }
+          self.check_labels_in_expression(field.get_value());
+        }
+      }
+      ast::Expression::Member(member) => self.check_labels_in_expression(&member.base),
+      ast::Expression::Index(index) => {
+        self.check_labels_in_expression(&index.base);
+        self.check_labels_in_expression(&index.index);
+      }
+      ast::Expression::Assign(assign) => {
+        assign.left.iter().for_each(|expression| self.check_labels_in_expression(expression));
+        assign.right.iter().for_each(|expression| self.check_labels_in_expression(expression));
+      }
+      _ => {}
+    }
+  }
+}
+
+fn block_statements(statement: &ast::Statement) -> &[ast::Statement] {
+  match statement {
+    ast::Statement::Block(block) => &block.statements,
+    statement => slice::from_ref(statement),
+  }
+}
@@ -8,21 +8,19 @@ impl<'a> Checker<'a> {
     let variables = &declaration.variables;
     let initializer = &declaration.initializer;
 
-    let result = variables.iter().enumerate().map::<CheckResult<Option<Type>>, _>(|(position, variable)| {
+    let assign_types = self.check_expression_list(initializer)?;
+    let assign_types = self.adjust_values(initializer, assign_types, variables.len());
+
+    for (position, (variable, assign_type)) in variables.iter().zip(assign_types).enumerate() {
       let lexeme = variable.name.lexeme();
       let range = variable.name.range.clone();
+      if let (Some(declared_type), Some(value)) = (&variable.ty, initializer.get(position)) {
+        self.check_unknown_fields(declared_type, value, &range)?;
+      }
       let left_hand_side = &(lexeme, variable.ty.clone());
-      let init_expression = initializer.get(position);
-      let assign_type = match init_expression {
-        Some(init_expression) => self.check_expression(init_expression)?.unwrap_or(Type::Nil),
-        None => Type::Nil,
-      };
       // declare is not return type ...
       self.declare_local_variable(left_hand_side, assign_type, range)?;
-
-      return Ok(None);
-    });
-    let _ = result.collect::<CheckResult<Vec<_>>>()?;
-    return Ok(None);
+    }
+    Ok(None)
   }
 }
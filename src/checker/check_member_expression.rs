@@ -9,23 +9,64 @@ use crate::{
 impl<'a> Checker<'a> {
   // todo: improve this... :(
   pub fn check_member_expression(&mut self, member: &ast::MemberExpression) -> CheckResult<Option<Type>> {
-    let base_type = self.check_expression(&member.base)?.unwrap();
-    let base_type = self.check_type(&base_type)?;
+    let base_type = self.check_expression(&member.base)?.unwrap_or(Type::Nil);
+    self.check_member_access(&base_type, member)
+  }
+
+  // `t.name = nil` removes the entry, so writing through an index signature always accepts nil
+  pub fn check_member_assign_target(&mut self, member: &ast::MemberExpression) -> CheckResult<Option<Type>> {
+    let base_type = self.check_expression(&member.base)?.unwrap_or(Type::Nil);
+    self.check_member_target(&base_type, member, true)
+  }
+
+  pub fn check_member_access(&mut self, base_type: &Type, member: &ast::MemberExpression) -> CheckResult<Option<Type>> {
+    self.check_member_target(base_type, member, !self.unchecked_index)
+  }
+
+  fn check_member_target(
+    &mut self,
+    base_type: &Type,
+    member: &ast::MemberExpression,
+    may_be_absent: bool,
+  ) -> CheckResult<Option<Type>> {
+    let base_type = self.check_type(base_type)?;
     let base_range = member.base.get_range();
     match base_type {
-      Type::Table(ref table_type) => self.check_identifier_member(table_type, &member.identifier),
-      Type::Unknown => Ok(Some(Type::Unknown)),
+      Type::Table(ref table_type) => self.check_identifier_member(table_type, &member.identifier, may_be_absent),
+      Type::Any => Ok(Some(Type::Any)),
+      Type::Never => Ok(Some(Type::Never)),
+      Type::Unknown => Err(self.create_diagnostic(TypeError::UnnarrowedUnknown(Some(base_range)))),
       _ => Err(self.create_diagnostic(TypeError::ExpectedTable(base_type.to_string(), Some(base_range)))),
     }
   }
 
-  fn check_identifier_member(&mut self, table: &TableType, identifier: &ast::Identifier) -> CheckResult<Option<Type>> {
-    let name = identifier.name.as_str();
-    if let Some(member_type) = table.get_type(name) {
-      Ok(Some(member_type.clone()))
-    } else {
-      Err(self.create_not_found_key_error(name, table, identifier.range.clone()))
+  fn check_identifier_member(
+    &mut self,
+    table: &TableType,
+    identifier: &ast::Identifier,
+    may_be_absent: bool,
+  ) -> CheckResult<Option<Type>> {
+    match self.find_member(table, identifier, may_be_absent)? {
+      Some(member_type) => Ok(Some(member_type)),
+      None => Err(self.create_not_found_key_error(&identifier.name, table, identifier.range.clone())),
+    }
+  }
+
+  // a field, an entry of the index signature or a member through `__index`
+  pub fn find_member(
+    &mut self,
+    table: &TableType,
+    identifier: &ast::Identifier,
+    may_be_absent: bool,
+  ) -> CheckResult<Option<Type>> {
+    if let Some(member_type) = table.get_field_type(&identifier.name) {
+      return Ok(Some(member_type));
+    }
+    if let Some(index) = table.index.as_ref().filter(|index| index.key.check_match(&Type::String)) {
+      let range = identifier.range.clone();
+      return Ok(Some(self.check_index_signature(index, &Type::String, may_be_absent, range)?));
     }
+    self.check_metatable_index(table, identifier, may_be_absent)
   }
 
   // fn create_member_error(&mut self, member: &ast::Expression) -> Diagnostic {
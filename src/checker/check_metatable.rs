@@ -0,0 +1,145 @@
+use super::{check_call_expression::call_arguments, type_utils::CheckResult, Checker};
+use crate::{
+  ast::ast,
+  diagnostics::TypeError,
+  types::{TableType, Type},
+  utils::range::Range,
+};
+
+impl<'a> Checker<'a> {
+  // setmetatable(t, mt) is `t` with `mt` as its metatable, `setmetatable(t, nil)` removes it
+  pub fn check_setmetatable_call(&mut self, args: &ast::Expression) -> CheckResult<Option<Type>> {
+    let expressions = call_arguments(args);
+    let [table, metatable] = expressions else {
+      return Err(self.create_function_arity_mismatch(2, expressions.len(), args.get_range()));
+    };
+
+    let table_type = self.check_expression(table)?.unwrap_or(Type::Nil);
+    let Type::Table(mut table_type) = self.check_type(&table_type)? else {
+      return Err(self.create_diagnostic(TypeError::ExpectedTable(table_type.to_string(), Some(table.get_range()))));
+    };
+
+    let metatable_type = self.check_expression(metatable)?.unwrap_or(Type::Nil);
+    table_type.metatable = match self.check_type(&metatable_type)? {
+      Type::Table(metatable_type) => Some(Box::new(metatable_type)),
+      Type::Nil => None,
+      _ => {
+        let diagnostic = TypeError::ExpectedTable(metatable_type.to_string(), Some(metatable.get_range()));
+        return Err(self.create_diagnostic(diagnostic));
+      }
+    };
+    Ok(Some(Type::Table(table_type)))
+  }
+
+  // `t.name` missing from `t` is looked up in `__index`, a table or a function
+  pub fn check_metatable_index(
+    &mut self,
+    table: &TableType,
+    identifier: &ast::Identifier,
+    may_be_absent: bool,
+  ) -> CheckResult<Option<Type>> {
+    let Some(index) = table.get_metamethod("__index").cloned() else {
+      return Ok(None);
+    };
+    match self.check_type(&index)? {
+      Type::Table(index_table) => self.find_member(&index_table, identifier, may_be_absent),
+      Type::Function(function) => Ok(Some(*function.return_type)),
+      Type::Any => Ok(Some(Type::Any)),
+      _ => Ok(None),
+    }
+  }
+
+  // `a + b` with `__add` in the metatable of `a` (or else of `b`) is `__add(a, b)`
+  pub fn check_binary_metamethod(
+    &mut self,
+    left: &Type,
+    operator: &ast::BinaryOperator,
+    right: &Type,
+    range: &Range,
+  ) -> CheckResult<Option<Type>> {
+    let Some(name) = operator.metamethod() else {
+      return Ok(None);
+    };
+    let Some(metamethod) = find_metamethod(left, name).or_else(|| find_metamethod(right, name)) else {
+      return Ok(None);
+    };
+
+    use ast::BinaryOperator::*;
+    let operands = match operator {
+      GreaterThan | GreaterThanOrEqual => [right, left],
+      _ => [left, right],
+    };
+    let result_type = self.check_metamethod_call(&metamethod, &operands, range)?;
+    // lua turns the result of a comparison into a boolean
+    match operator {
+      Equal | NotEqual | LessThan | GreaterThan | LessThanOrEqual | GreaterThanOrEqual => Ok(Some(Type::Boolean)),
+      _ => Ok(Some(result_type)),
+    }
+  }
+
+  // `-v` is `__unm(v)`, `#v` is `__len(v)`
+  pub fn check_unary_metamethod(
+    &mut self,
+    operand: &Type,
+    operator: &ast::UnaryOperator,
+    range: &Range,
+  ) -> CheckResult<Option<Type>> {
+    match operator.metamethod().and_then(|name| find_metamethod(operand, name)) {
+      Some(metamethod) => Ok(Some(self.check_metamethod_call(&metamethod, &[operand], range)?)),
+      None => Ok(None),
+    }
+  }
+
+  // `t(args)` with `__call` in the metatable of `t` is `__call(t, args)`
+  pub fn check_call_metamethod(
+    &mut self,
+    table: &TableType,
+    args: &ast::Expression,
+    range: Range,
+  ) -> CheckResult<Option<Type>> {
+    let metamethod = table.get_metamethod("__call").cloned().unwrap_or(Type::Any);
+    let function = match self.check_type(&metamethod)? {
+      Type::Function(function) => function,
+      Type::Any => return Ok(Some(Type::Any)),
+      found => return Err(self.create_diagnostic(TypeError::ExpectedFunction(found.to_string(), Some(range)))),
+    };
+
+    let table_type = Type::Table(table.clone());
+    let params = match function.params.split_first() {
+      Some((self_type, params)) => {
+        self.check_metamethod_operand(self_type, &table_type, &range)?;
+        params
+      }
+      None => &[],
+    };
+    self.check_call_arguments(args, params)?;
+    Ok(Some(*function.return_type))
+  }
+
+  fn check_metamethod_call(&mut self, metamethod: &Type, operands: &[&Type], range: &Range) -> CheckResult<Type> {
+    let function = match self.check_type(metamethod)? {
+      Type::Function(function) => function,
+      Type::Any => return Ok(Type::Any),
+      found => return Err(self.create_diagnostic(TypeError::ExpectedFunction(found.to_string(), Some(range.clone())))),
+    };
+    for (param, operand) in function.params.iter().zip(operands) {
+      self.check_metamethod_operand(param, operand, range)?;
+    }
+    Ok(*function.return_type)
+  }
+
+  fn check_metamethod_operand(&mut self, param: &Type, operand: &Type, range: &Range) -> CheckResult<()> {
+    let param = self.check_type(param)?;
+    if !param.check_match(operand) {
+      return Err(self.create_type_mismatch(param, operand.clone(), range.clone()));
+    }
+    Ok(())
+  }
+}
+
+fn find_metamethod(operand: &Type, name: &str) -> Option<Type> {
+  match operand {
+    Type::Table(table) => table.get_metamethod(name).cloned(),
+    _ => None,
+  }
+}
@@ -0,0 +1,45 @@
+use super::{type_utils::CheckResult, Checker};
+use crate::{
+  ast::ast,
+  diagnostics::TypeError,
+  types::{OverloadType, Type},
+  utils::range::Range,
+};
+
+impl<'a> Checker<'a> {
+  // the first signature that takes the arguments gives the result, every candidate is reported otherwise
+  pub fn check_overload_call(
+    &mut self,
+    overload: &OverloadType,
+    args: &ast::Expression,
+    range: Range,
+  ) -> CheckResult<Option<Type>> {
+    let mut rejected = vec![];
+    for function in &overload.functions {
+      let function_type = Type::Function(function.clone());
+      match self.check_call_type(&function_type, args, range.clone()) {
+        Ok(return_type) => return Ok(return_type),
+        Err(diagnostic) => rejected.push((function_type.to_string(), diagnostic.message)),
+      }
+    }
+    Err(self.create_diagnostic(TypeError::NoMatchingOverload(rejected, Some(range))))
+  }
+
+  // receiver:method(args) with an overloaded method
+  pub fn check_overload_method_call(
+    &mut self,
+    overload: &OverloadType,
+    receiver_type: &Type,
+    member: &ast::MemberExpression,
+    call_expr: &ast::CallExpression,
+  ) -> CheckResult<Option<Type>> {
+    let mut rejected = vec![];
+    for function in &overload.functions {
+      match self.check_method_signature(function, receiver_type, member, call_expr) {
+        Ok(return_type) => return Ok(return_type),
+        Err(diagnostic) => rejected.push((Type::Function(function.clone()).to_string(), diagnostic.message)),
+      }
+    }
+    Err(self.create_diagnostic(TypeError::NoMatchingOverload(rejected, Some(call_expr.get_range()))))
+  }
+}
@@ -0,0 +1,82 @@
+use super::{type_utils::CheckResult, Checker};
+use crate::{
+  ast::ast,
+  diagnostics::TypeError,
+  types::{TableType, Type},
+  utils::range::Range,
+};
+
+impl<'a> Checker<'a> {
+  // local user: User = { name = "ana" }
+  //
+  // names the missing field instead of a mismatch of the whole table
+  pub fn check_record_fields(&mut self, expected: &Type, found: &Type, range: &Range) -> CheckResult<()> {
+    let (Type::Table(expected), Type::Table(found)) = (expected, found) else {
+      return Ok(());
+    };
+    let Some(expected_map) = expected.map.as_ref().filter(|_| is_record(expected)) else {
+      return Ok(());
+    };
+    if !is_record(found) || found.inherits(expected) {
+      return Ok(());
+    }
+
+    for (key, expected_value) in expected_map {
+      match found.get_type(key) {
+        Some(found_value) => {
+          let expected_value = self.check_type(expected_value)?;
+          let found_value = &self.check_type(found_value)?;
+          self.check_record_fields(&expected_value, found_value, range)?;
+          let is_absent = expected.is_optional(key) && found_value.is_nil();
+          if !is_absent && !expected_value.check_match(found_value) {
+            return Err(self.create_type_mismatch(expected_value, found_value.clone(), range.clone()));
+          }
+        }
+        None if !expected.is_optional(key) => {
+          let diagnostic = TypeError::MissingField(key.clone(), expected.to_string(), Some(range.clone()));
+          return Err(self.create_diagnostic(diagnostic));
+        }
+        None => {}
+      }
+    }
+    Ok(())
+  }
+
+  // local user: User = { name = "ana", nmae = "bob" }
+  //
+  // a table written in place can't have fields the type doesn't declare, a typo would be lost,
+  // any other table can, it's still a `User`
+  pub fn check_unknown_fields(&mut self, expected: &Type, value: &ast::Expression, range: &Range) -> CheckResult<()> {
+    let ast::Expression::Table(table_expr) = value else {
+      return Ok(());
+    };
+    let Type::Table(expected) = self.check_type(expected)? else {
+      return Ok(());
+    };
+    let Some(expected_map) = expected.map.as_ref().filter(|_| is_record(&expected)) else {
+      return Ok(());
+    };
+    for field in &table_expr.fields {
+      let (key, value) = match field {
+        ast::TableField::Named(name, value) => (&name.name, value),
+        ast::TableField::Computed(ast::Expression::Literal(ast::LiteralExpression::String(key)), value) => {
+          (&key.value, value)
+        }
+        _ => continue,
+      };
+      match expected_map.get(key) {
+        Some(expected_value) => self.check_unknown_fields(expected_value, value, range)?,
+        None => {
+          let diagnostic = TypeError::UnknownField(key.clone(), expected.to_string(), Some(range.clone()));
+          return Err(self.create_diagnostic(diagnostic));
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+// only named fields, `{}` is a record without fields
+fn is_record(table: &TableType) -> bool {
+  table.array.is_none() && table.tuple.is_none() && table.index.is_none()
+}
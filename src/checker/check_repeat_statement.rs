@@ -3,6 +3,15 @@ use crate::{ast::ast, types::Type};
 
 impl<'a> Checker<'a> {
   pub fn check_repeat_statement(&mut self, repeat: &ast::RepeatStatement) -> CheckResult<Option<Type>> {
-    todo!()
+    // the condition is part of the body scope, so it can use the body locals
+    self.enter_scope();
+    self.forget_loop_narrowings(&repeat.body);
+    let last_t = self.check_statement(&repeat.body)?;
+
+    self.check_condition(&repeat.condition)?;
+
+    self.leave_scope();
+
+    Ok(last_t)
   }
 }
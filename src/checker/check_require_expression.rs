@@ -35,9 +35,13 @@ impl<'a> Checker<'a> {
 
     let mut parser = Parser::new(content, path_name);
 
-    let program = parser.parse_program();
+    let (program, diagnostics) = parser.parse_program();
+    if let Some(diagnostic) = diagnostics.into_iter().next() {
+      return Err(diagnostic);
+    }
 
     let mut checker = Checker::new(path_name, content);
+    checker.unchecked_index = self.unchecked_index;
 
     let _ = checker.check(&program)?;
 
@@ -25,7 +25,7 @@ impl<'a> Checker<'a> {
 
   fn check_global_redeclaration(&mut self, lexeme: &str, rg: &Range) -> CheckResult<()> {
     if let Some(previous_type) = self.ctx.get_variable(lexeme, Some(0)) {
-      let current_type = self.ctx.get_variable(lexeme, None).unwrap_or_else(|| &Type::Unknown);
+      let current_type = self.ctx.get_variable(lexeme, None).unwrap_or(&Type::Any);
       if !previous_type.check_match(&current_type) {
         return Err(self.create_type_mismatch(previous_type.to_owned(), current_type.to_owned(), rg.clone()));
       }
@@ -15,10 +15,15 @@ impl<'a> Checker<'a> {
       ast::Statement::While(while_) => self.check_while_statement(while_),
       ast::Statement::Repeat(repeat) => self.check_repeat_statement(repeat),
       ast::Statement::For(for_) => self.check_for_statement(for_),
+      ast::Statement::ForIn(for_in) => self.check_for_in_statement(for_in),
       ast::Statement::Expression(expression) => self.check_expression(&expression),
       ast::Statement::TypeDeclaration(declaration) => self.check_type_declaration(declaration),
+      ast::Statement::Enum(declaration) => self.check_enum_declaration(declaration),
+      ast::Statement::Class(declaration) => self.check_class_declaration(declaration),
       ast::Statement::Local(local) => self.check_local_statement(local),
-      _ => todo!("Implement more statement checks: {:#?}", statement),
+      // validated up front by `check_labels`
+      ast::Statement::Goto(_) | ast::Statement::Label(_) => Ok(None),
+      ast::Statement::Break(_) | ast::Statement::Continue(_) => Ok(None),
     }
   }
 }
@@ -1,7 +1,14 @@
+use std::collections::BTreeSet;
+
+use super::narrowing::union_members;
 use super::type_utils::CheckResult;
 use super::Checker;
 use crate::diagnostics::TypeError;
-use crate::types::{GenericCallType, Type};
+use crate::types::{GenericCallType, LiteralType, TableType, Type};
+
+// types built into the checker, they are resolved once their arguments are known
+pub const STDLIB_TYPES: [&str; 8] =
+  ["option", "union", "keyof", "Partial", "Readonly", "Pick", "ReturnType", "Parameters"];
 
 impl<'a> Checker<'a> {
   pub fn check_stdlib_type<'t>(&mut self, generic_call: &'t GenericCallType) -> CheckResult<Option<Type>> {
@@ -13,7 +20,16 @@ impl<'a> Checker<'a> {
       let union_type = self.check_union_stdlib_type(&generic_call)?;
       return Ok(union_type);
     }
-    Ok(None)
+    let operator_type = match generic_call.name.as_str() {
+      "keyof" => self.check_keyof_stdlib_type(generic_call)?,
+      "Partial" => self.check_partial_stdlib_type(generic_call)?,
+      "Readonly" => self.check_readonly_stdlib_type(generic_call)?,
+      "Pick" => self.check_pick_stdlib_type(generic_call)?,
+      "ReturnType" => self.check_return_type_stdlib_type(generic_call)?,
+      "Parameters" => self.check_parameters_stdlib_type(generic_call)?,
+      _ => return Ok(None),
+    };
+    Ok(Some(operator_type))
   }
   pub fn check_option_stdlib_type<'t>(&mut self, call: &'t GenericCallType) -> CheckResult<Option<Type>> {
     if call.types.len() != 1 {
@@ -36,4 +52,94 @@ impl<'a> Checker<'a> {
 
     Ok(Some(union_type))
   }
+
+  // `keyof { id: number, name: string }` is `"id" | "name"`
+  fn check_keyof_stdlib_type(&mut self, call: &GenericCallType) -> CheckResult<Type> {
+    let table = self.check_table_operand(call)?;
+    let keys = table.map.iter().flatten().map(|(key, _)| Type::Literal(LiteralType::String(key.clone())));
+    Ok(self.create_type_based_array(keys.collect()).unwrap_or(Type::Never))
+  }
+
+  // `Partial<T>`, every field of `T` may be absent
+  fn check_partial_stdlib_type(&mut self, call: &GenericCallType) -> CheckResult<Type> {
+    let mut table = self.check_table_operand(call)?;
+    table.optional = table.map.iter().flatten().map(|(key, _)| key.clone()).collect();
+    Ok(Type::Table(table))
+  }
+
+  // `Readonly<T>`, no field of `T` can be assigned
+  fn check_readonly_stdlib_type(&mut self, call: &GenericCallType) -> CheckResult<Type> {
+    let mut table = self.check_table_operand(call)?;
+    table.readonly = table.map.iter().flatten().map(|(key, _)| key.clone()).collect();
+    Ok(Type::Table(table))
+  }
+
+  // `Pick<T, "id" | "name">`, only the given fields of `T`
+  fn check_pick_stdlib_type(&mut self, call: &GenericCallType) -> CheckResult<Type> {
+    self.check_type_operator_arity(call, 2)?;
+    let range = Some(call.range.clone());
+    let table = match self.check_type(&call.types[0])? {
+      Type::Table(table) => table,
+      found => return Err(self.create_diagnostic(TypeError::ExpectedTable(found.to_string(), range))),
+    };
+    let mut keys = BTreeSet::new();
+    for key in union_members(&self.check_type(&call.types[1])?) {
+      match key {
+        Type::Literal(LiteralType::String(key)) if table.get_type(&key).is_some() => keys.insert(key),
+        Type::Literal(LiteralType::String(key)) => {
+          return Err(self.create_diagnostic(TypeError::KeyNotFoundInTable(key, table.to_string(), range)));
+        }
+        found => return Err(self.create_diagnostic(TypeError::ExpectedFieldName(found.to_string(), range))),
+      };
+    }
+    let map = table.map.iter().flatten().filter(|(key, _)| keys.contains(*key));
+    let picked = TableType {
+      map: Some(map.map(|(key, value)| (key.clone(), value.clone())).collect()),
+      optional: table.optional.intersection(&keys).cloned().collect(),
+      readonly: table.readonly.intersection(&keys).cloned().collect(),
+      ..Default::default()
+    };
+    Ok(Type::Table(picked))
+  }
+
+  // `ReturnType<F>`, what calling `F` gives
+  fn check_return_type_stdlib_type(&mut self, call: &GenericCallType) -> CheckResult<Type> {
+    self.check_type_operator_arity(call, 1)?;
+    match self.check_type(&call.types[0])? {
+      Type::Function(function) => self.check_type(&function.return_type),
+      Type::Overload(overload) => {
+        let return_types = overload.functions.into_iter().map(|function| *function.return_type).collect();
+        Ok(self.create_type_based_array(return_types).unwrap_or(Type::Nil))
+      }
+      found => Err(self.create_diagnostic(TypeError::ExpectedFunction(found.to_string(), Some(call.range.clone())))),
+    }
+  }
+
+  // `Parameters<F>`, the parameters of `F` as a tuple
+  fn check_parameters_stdlib_type(&mut self, call: &GenericCallType) -> CheckResult<Type> {
+    self.check_type_operator_arity(call, 1)?;
+    match self.check_type(&call.types[0])? {
+      Type::Function(function) => {
+        let params = function.params.iter().map(|param| self.check_type(param)).collect::<CheckResult<Vec<_>>>()?;
+        Ok(Type::Table(TableType { tuple: Some(params), ..Default::default() }))
+      }
+      found => Err(self.create_diagnostic(TypeError::ExpectedFunction(found.to_string(), Some(call.range.clone())))),
+    }
+  }
+
+  fn check_table_operand(&mut self, call: &GenericCallType) -> CheckResult<TableType> {
+    self.check_type_operator_arity(call, 1)?;
+    match self.check_type(&call.types[0])? {
+      Type::Table(table) => Ok(table),
+      found => Err(self.create_diagnostic(TypeError::ExpectedTable(found.to_string(), Some(call.range.clone())))),
+    }
+  }
+
+  fn check_type_operator_arity(&self, call: &GenericCallType, expected: usize) -> CheckResult<()> {
+    if call.types.len() != expected {
+      let diagnostic = TypeError::GenericCallArityMismatch(expected, call.types.len(), Some(call.range.clone()));
+      return Err(self.create_diagnostic(diagnostic));
+    }
+    Ok(())
+  }
 }
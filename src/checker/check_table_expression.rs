@@ -1,47 +1,89 @@
-use std::collections::{BTreeMap, HashSet};
+use std::collections::BTreeMap;
 
 use super::{type_utils::CheckResult, Checker};
-use crate::{ast::ast, diagnostics::TypeError, types::Type};
+use crate::{
+  ast::ast,
+  diagnostics::TypeError,
+  types::{IndexType, TableType, Type},
+};
 
 impl<'a> Checker<'a> {
   pub fn check_table_expression(&mut self, table_expr: &ast::TableExpression) -> CheckResult<Option<Type>> {
-    let mut array_elements = HashSet::new();
+    let mut positional = vec![];
+    let mut is_list = false;
     let mut map_elements = BTreeMap::new();
+    let mut index_keys = vec![];
+    let mut index_values = vec![];
 
-    for (key_expr, value_expr) in &table_expr.values {
-      if let Some(value_expr) = value_expr {
-        let value_type = self.check_expression(value_expr)?.unwrap();
-        let key_str = self.extract_table_key(key_expr)?;
-        map_elements.insert(key_str, value_type);
-      } else {
-        let array_element_type = self.check_expression(key_expr)?.unwrap();
-        array_elements.insert(array_element_type);
+    for field in &table_expr.fields {
+      let value_type = self.check_expression(field.get_value())?.unwrap_or(Type::Nil).widen();
+      match field {
+        // `{...}` packs the values of a variadic, so its length is only known at runtime
+        ast::TableField::Positional(_) => match value_type {
+          Type::Variadic(variadic) => {
+            is_list = true;
+            positional.push(*variadic.inner_type);
+          }
+          _ => positional.push(value_type),
+        },
+        ast::TableField::Named(name, _) => {
+          map_elements.insert(name.name.clone(), value_type);
+        }
+        // `["name"] = value` is the same as `name = value`
+        ast::TableField::Computed(ast::Expression::Literal(ast::LiteralExpression::String(string)), _) => {
+          map_elements.insert(string.value.clone(), value_type);
+        }
+        ast::TableField::Computed(key_expr, _) => {
+          let key_type = self.check_table_key(key_expr)?;
+          if !index_keys.contains(&key_type) {
+            index_keys.push(key_type);
+          }
+          if !index_values.contains(&value_type) {
+            index_values.push(value_type);
+          }
+        }
       }
     }
 
-    let table_type = Type::new_table(
-      if array_elements.is_empty() { None } else { Some(array_elements) },
-      if map_elements.is_empty() { None } else { Some(map_elements) },
-    );
+    let index = match (self.create_type_based_array(index_keys), self.create_type_based_array(index_values)) {
+      (Some(key), Some(value)) => Some(IndexType::new(key, value)),
+      _ => None,
+    };
 
-    Ok(Some(table_type))
-  }
+    // `{1, "a"}` is a tuple, the values of `{...}` are a list
+    let (array, tuple) = match positional.is_empty() {
+      true => (None, None),
+      false if is_list => {
+        let mut elements = vec![];
+        for element in positional {
+          if !elements.contains(&element) {
+            elements.push(element);
+          }
+        }
+        (self.create_type_based_array(elements).map(Box::new), None)
+      }
+      false => (None, Some(positional)),
+    };
 
-  fn extract_table_key(&mut self, key_expr: &ast::Expression) -> CheckResult<String> {
-    match key_expr {
-      ast::Expression::Identifier(identifier) => Ok(identifier.name.clone()),
-      ast::Expression::Literal(literal) => match literal {
-        ast::LiteralExpression::String(string) => Ok(string.value.clone()),
-        _ => self.create_invalid_literal_key_error(key_expr),
-      },
-      _ => self.create_invalid_literal_key_error(key_expr),
-    }
+    let table_type = TableType {
+      array,
+      tuple,
+      map: if map_elements.is_empty() { None } else { Some(map_elements) },
+      index,
+      ..Default::default()
+    };
+
+    Ok(Some(Type::Table(table_type)))
   }
 
-  fn create_invalid_literal_key_error(&mut self, key_expr: &ast::Expression) -> CheckResult<String> {
-    let range = key_expr.get_range();
-    let expr_type = self.check_expression(key_expr)?.unwrap();
-    let diagnostic = TypeError::MismatchedKeyType(expr_type.to_string(), Some(range));
-    Err(self.create_diagnostic(diagnostic))
+  fn check_table_key(&mut self, key_expr: &ast::Expression) -> CheckResult<Type> {
+    let key_type = self.check_expression(key_expr)?.unwrap_or(Type::Nil).widen();
+    match key_type {
+      Type::String | Type::Number | Type::Boolean | Type::Any => Ok(key_type),
+      _ => {
+        let diagnostic = TypeError::MismatchedKeyType(key_type.to_string(), Some(key_expr.get_range()));
+        Err(self.create_diagnostic(diagnostic))
+      }
+    }
   }
 }
@@ -1,7 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use super::{type_utils::CheckResult, Checker};
-use crate::types::Type;
+use crate::{
+  diagnostics::TypeError,
+  types::{FunctionType, IntersectionType, OverloadType, TableType, Type},
+};
 
 type GenericBinds = HashMap<String, Type>;
 
@@ -11,8 +14,11 @@ impl<'a> Checker<'a> {
       Some(Type::Alias(alias)) => self.check_type_alias(alias),
       Some(Type::Generic(generic)) => self.check_generic_type(generic),
       Some(Type::GenericCall(generic_call)) => self.check_generic_call(generic_call),
+      Some(ty @ (Type::Function(_) | Type::Overload(_) | Type::Intersection(_) | Type::Table(_))) => {
+        self.check_type(ty)
+      }
       Some(t) => Ok(t.to_owned()),
-      None => Ok(if assume_nil { Type::Nil } else { Type::Unknown }),
+      None => Ok(if assume_nil { Type::Nil } else { Type::Any }),
     }
   }
 
@@ -21,7 +27,101 @@ impl<'a> Checker<'a> {
       Type::Alias(alias) => self.check_type_alias(&alias),
       Type::Generic(generic) => self.check_generic_type(&generic),
       Type::GenericCall(generic_call) => self.check_generic_call(&generic_call),
+      Type::Function(function) => self.check_function_type(function),
+      Type::Overload(overload) => self.check_overload_type(overload),
+      Type::Intersection(intersection) => self.check_intersection_type(intersection),
+      Type::Table(table) => self.check_table_type(table),
       _ => Ok(ty.to_owned()),
     }
   }
+
+  // `A & B` is one table with the fields of both, a field on both sides has to agree
+  fn check_intersection_type(&mut self, intersection: &IntersectionType) -> CheckResult<Type> {
+    let range = Some(intersection.range.clone());
+    let mut merged = TableType::default();
+    for member in &intersection.types {
+      let table = match self.check_type(member)? {
+        Type::Table(table) => table,
+        found => return Err(self.create_diagnostic(TypeError::ExpectedTable(found.to_string(), range))),
+      };
+      for (key, value) in table.map.iter().flatten() {
+        let optional = table.is_optional(key);
+        match merged.get_type(key) {
+          Some(existing) if !existing.check_match(value) || !value.check_match(existing) => {
+            let (left, right) = (existing.to_string(), value.to_string());
+            return Err(self.create_diagnostic(TypeError::ConflictingField(key.clone(), left, right, range)));
+          }
+          // optional only if it's optional on every side
+          Some(_) if !optional => {
+            merged.optional.remove(key);
+          }
+          Some(_) => {}
+          None => {
+            if optional {
+              merged.optional.insert(key.clone());
+            }
+            merged.map.get_or_insert_with(BTreeMap::new).insert(key.clone(), value.clone());
+          }
+        }
+      }
+      merged.readonly.extend(table.readonly);
+      merged.array = merged.array.or(table.array);
+      merged.tuple = merged.tuple.or(table.tuple);
+      merged.index = merged.index.or(table.index);
+      merged.metatable = merged.metatable.or(table.metatable);
+    }
+    Ok(Type::Table(merged))
+  }
+
+  // `{ a: option<number> }` is compared against checked tables, so the types of its members have to be resolved
+  fn check_table_type(&mut self, table: &TableType) -> CheckResult<Type> {
+    // names stay as they are, like in function types
+    let mut resolve = |ty: &Type| match ty {
+      Type::Alias(_) => Ok(ty.clone()),
+      _ => self.check_type(ty),
+    };
+    let mut table = table.clone();
+    for value in table.map.iter_mut().flat_map(|map| map.values_mut()) {
+      *value = resolve(value)?;
+    }
+    if let Some(array) = &mut table.array {
+      **array = resolve(array)?;
+    }
+    for element in table.tuple.iter_mut().flatten() {
+      *element = resolve(element)?;
+    }
+    if let Some(index) = &mut table.index {
+      *index.key = resolve(&index.key)?;
+      *index.value = resolve(&index.value)?;
+    }
+    Ok(Type::Table(table))
+  }
+
+  // `function(): option<T>` is compared against checked functions, so its `option<T>` has to be resolved
+  fn check_function_type(&mut self, function: &FunctionType) -> CheckResult<Type> {
+    // type parameters are only bound at each call
+    if !function.generics.is_empty() {
+      return Ok(Type::Function(function.clone()));
+    }
+    // names stay as they are, `type F = function(): F` would never end
+    let mut resolve = |ty: &Type| match ty {
+      Type::Alias(_) => Ok(ty.clone()),
+      _ => self.check_type(ty),
+    };
+    let params = function.params.iter().map(&mut resolve).collect::<CheckResult<Vec<_>>>()?;
+    let return_type = Box::new(resolve(&function.return_type)?);
+    Ok(Type::Function(FunctionType { generics: vec![], params, return_type }))
+  }
+
+  // signatures are compared against each other, so `option<T>` in them has to be resolved
+  fn check_overload_type(&mut self, overload: &OverloadType) -> CheckResult<Type> {
+    let functions = overload.functions.iter().map(|function| self.check_function_signature(function));
+    Ok(Type::new_overload(functions.collect::<CheckResult<Vec<_>>>()?))
+  }
+
+  fn check_function_signature(&mut self, function: &FunctionType) -> CheckResult<FunctionType> {
+    let params = function.params.iter().map(|param| self.check_type(param)).collect::<CheckResult<Vec<_>>>()?;
+    let return_type = Box::new(self.check_type(&function.return_type)?);
+    Ok(FunctionType { generics: function.generics.clone(), params, return_type })
+  }
 }
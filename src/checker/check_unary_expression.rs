@@ -9,7 +9,7 @@ type Unary = ast::UnaryExpression;
 
 impl<'a> Checker<'a> {
   pub fn check_unary_expression(&mut self, unary_expr: &Unary) -> CheckResult<Option<Type>> {
-    let operand_type = self.check_expression(&unary_expr.operand)?.unwrap_or(Type::Nil);
+    let operand_type = self.check_expression(&unary_expr.operand)?.unwrap_or(Type::Nil).widen();
     self.check_unary_operator(&operand_type, unary_expr)
   }
 
@@ -18,13 +18,17 @@ impl<'a> Checker<'a> {
       ast::UnaryOperator::Negate => self.validate_unary_operator(operand_t, unary_expr),
       ast::UnaryOperator::Not => self.validate_unary_operator(operand_t, unary_expr),
       ast::UnaryOperator::Hash => self.validate_unary_operator(operand_t, unary_expr),
+      ast::UnaryOperator::BitwiseNot => self.validate_unary_operator(operand_t, unary_expr),
     }
   }
 
   fn validate_unary_operator(&mut self, operand_t: &Type, unary_expr: &Unary) -> CheckResult<Option<Type>> {
     // let result_type = self.check_type(operand_t.clone())?;
-    if operand_t.suport_unary_operator(&unary_expr.operator) {
-      let result_type = operand_t.get_unary_operator_result_type(&unary_expr.operator);
+    let range = unary_expr.get_range();
+    if let Some(result_type) = self.check_unary_metamethod(operand_t, &unary_expr.operator, &range)? {
+      return Ok(Some(result_type));
+    }
+    if let Some(result_type) = operand_t.get_unary_operator_result_type(&unary_expr.operator) {
       return Ok(Some(result_type));
     }
 
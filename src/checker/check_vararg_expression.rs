@@ -0,0 +1,18 @@
+use super::{type_utils::CheckResult, Checker};
+use crate::{ast::ast, diagnostics::TypeError, types::Type};
+
+impl<'a> Checker<'a> {
+  // `...` has the declared variadic type, the main chunk receives unknown values
+  pub fn check_vararg_expression(&mut self, vararg: &ast::VarargExpression) -> CheckResult<Option<Type>> {
+    let (defined, scope_pointer) = self.ctx.defined_in_any_scope("...");
+    if !defined {
+      return Ok(Some(Type::new_variadic(Type::Any)));
+    }
+
+    self.ctx.use_variable("...", Some(scope_pointer));
+    match self.ctx.get_variable("...", Some(scope_pointer)) {
+      Some(vararg_type) if vararg_type.is_variadic() => Ok(Some(vararg_type.clone())),
+      _ => Err(self.create_diagnostic(TypeError::VarargOutsideFunction(Some(vararg.get_range())))),
+    }
+  }
+}
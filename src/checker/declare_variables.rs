@@ -54,8 +54,9 @@ impl<'a> Checker<'a> {
     // If a type is specified in the variable declaration, check if it's redundant
     if let Some(declared_type) = &current_ty {
       let declared_type = self.check_type(declared_type)?;
-      if !assign_ty.check_match(&declared_type) {
-        return Err(self.create_type_mismatch(assign_ty, declared_type, range));
+      self.check_record_fields(&declared_type, &assign_ty, &range)?;
+      if !declared_type.check_match(&assign_ty) {
+        return Err(self.create_type_mismatch(declared_type, assign_ty, range));
       }
 
       // check redundant type
@@ -77,7 +78,7 @@ impl<'a> Checker<'a> {
     }
 
     // declare the variable as global
-    self.ctx.declare_global_variable(name, assign_ty);
+    self.ctx.declare_global_variable(name, assign_ty.widen());
     // declare the variable range in global scope
     self.ctx.declare_variable_range(name, range, Some(0));
     Ok(())
@@ -104,8 +105,9 @@ impl<'a> Checker<'a> {
     // If a type is specified in the variable declaration, check if it's redundant
     if let Some(declared_type) = &current_ty {
       let declared_type = self.check_type(declared_type)?;
-      if !assign_ty.check_match(&declared_type) {
-        return Err(self.create_type_mismatch(assign_ty, declared_type, range));
+      self.check_record_fields(&declared_type, &assign_ty, &range)?;
+      if !declared_type.check_match(&assign_ty) {
+        return Err(self.create_type_mismatch(declared_type, assign_ty, range));
       }
 
       // check redundant type
@@ -126,7 +128,7 @@ impl<'a> Checker<'a> {
     }
 
     // declare the variable as local
-    self.ctx.declare_variable(name, assign_ty, None);
+    self.ctx.declare_variable(name, assign_ty.widen(), None);
     self.ctx.set_local_declaration(name);
     // declare the variable range in current scope
     self.ctx.declare_variable_range(name, range, None);
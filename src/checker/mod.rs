@@ -6,8 +6,13 @@ pub mod check_assign_expression;
 pub mod check_binary_expression;
 pub mod check_block_statement;
 pub mod check_call_expression;
+pub mod check_class_declaration;
+pub mod check_compound_assign_expression;
 pub mod check_empty_statement;
+pub mod check_enum_declaration;
 pub mod check_expression;
+pub mod check_expression_list;
+pub mod check_for_in_statement;
 pub mod check_for_statement;
 pub mod check_function_expression;
 pub mod check_function_statement;
@@ -16,9 +21,13 @@ pub mod check_grouped_expression;
 pub mod check_identifier;
 pub mod check_if_statement;
 pub mod check_index_expression;
+pub mod check_labels;
 pub mod check_literal_expression;
 pub mod check_local_statement;
 pub mod check_member_expression;
+pub mod check_metatable;
+pub mod check_overload_call;
+pub mod check_record_fields;
 pub mod check_repeat_statement;
 pub mod check_require_expression;
 pub mod check_return_statement;
@@ -30,10 +39,12 @@ pub mod check_type;
 pub mod check_type_declaration;
 pub mod check_unary_expression;
 pub mod check_unused_variables;
+pub mod check_vararg_expression;
 pub mod check_while_statement;
 pub mod declare_variables;
 pub mod narrowing;
 pub mod type_utils;
+pub mod unify;
 
 use type_utils::CheckResult;
 
@@ -53,6 +64,10 @@ pub struct Checker<'a> {
   pub resolver: Resolver,
   pub expect: Option<Type>,
   pub raw: &'a str,
+  // reading `{[K]: V}` gives `V` instead of `option<V>`
+  pub unchecked_index: bool,
+  // the type names being resolved, `type Node = { next: option<Node> }` refers to itself
+  resolving_aliases: Vec<String>,
 }
 
 impl<'a> Checker<'a> {
@@ -62,11 +77,31 @@ impl<'a> Checker<'a> {
     let mut resolver = Resolver::new();
     resolver.add_search_path(file_name);
     let diagnostics = DiagnosticManager::new();
-    Checker { ctx, file_name: file_name.to_string(), diagnostics, loader, resolver, raw, expect: None }
+    let file_name = file_name.to_string();
+    let resolving_aliases = vec![];
+    Checker {
+      ctx,
+      file_name,
+      diagnostics,
+      loader,
+      resolver,
+      raw,
+      expect: None,
+      unchecked_index: false,
+      resolving_aliases,
+    }
   }
 
   pub fn check(&mut self, program: &ast::Program) -> CheckResult<Type> {
+    let last_t = self.check_program(program);
+    self.show_diagnostics();
+    return Ok(last_t);
+  }
+
+  // checks the program and collects the diagnostics without reporting them
+  pub fn check_program(&mut self, program: &ast::Program) -> Type {
     let mut last_t = Type::Nil;
+    self.check_labels(&program.statements);
     for statement in &program.statements {
       match self.check_statement(statement) {
         Ok(Some(ty)) => last_t = ty,
@@ -74,8 +109,8 @@ impl<'a> Checker<'a> {
         Err(diag) => self.diagnostics.add(diag),
       }
     }
-    self.show_diagnostics();
-    return Ok(last_t);
+    self.check_unused_variables();
+    last_t
   }
 
   // pub fn check_break_statement(&mut self, break_: &ast::BreakStatement) {
@@ -96,7 +131,6 @@ impl<'a> Checker<'a> {
   }
 
   pub fn show_diagnostics(&mut self) {
-    self.check_unused_variables();
     self.diagnostics.emit_all(self.raw, &self.file_name);
   }
 
@@ -1,99 +1,365 @@
 use super::{type_utils::CheckResult, Checker};
 use crate::{
-  ast::ast::{BinaryOperator, Expression, LiteralExpression},
-  types::Type,
+  ast::ast::{self, BinaryOperator, Expression, LiteralExpression, UnaryOperator},
+  types::{LiteralType, Type},
 };
+
+// what a condition tells about the variables it tests
+#[derive(Debug, Clone, Default)]
+pub struct Narrowing {
+  // the types when the condition is true
+  pub truthy: Vec<(String, Type)>,
+  // the types when the condition is false
+  pub falsy: Vec<(String, Type)>,
+}
+
+impl Narrowing {
+  fn negate(self) -> Narrowing {
+    Narrowing { truthy: self.falsy, falsy: self.truthy }
+  }
+}
+
 impl<'a> Checker<'a> {
-  pub fn is_condition_narrowing(&self, condition: &Expression) -> bool {
-    if let Expression::Binary(binary_expr) = condition {
-      match binary_expr.operator {
-        BinaryOperator::Equal => {
-          return self.is_literal_narrowing(&*binary_expr.left);
-        }
-        _ => {}
-      }
+  // `if x then`, `while x ~= nil do`
+  //
+  // a condition is a boolean or a value that can be nil, `if n + 1 then` is always true
+  pub fn check_condition(&mut self, condition: &Expression) -> CheckResult<Narrowing> {
+    let condition_type = self.check_expression(condition)?.unwrap_or(Type::Nil);
+    if !can_be_falsy(&self.check_type(&condition_type)?) {
+      return Err(self.create_type_mismatch(Type::new_boolean(), condition_type, condition.get_range()));
     }
-    false
+    self.narrow_condition(condition)
   }
 
-  pub fn get_specified_type(&mut self, condition: &Expression) -> CheckResult<(Option<String>, Option<Type>)> {
+  pub fn narrow_condition(&mut self, condition: &Expression) -> CheckResult<Narrowing> {
     match condition {
-      Expression::Binary(binary_expr) => match binary_expr.operator {
-        BinaryOperator::Equal => {
-          if let Some(string) = self.get_identifier_string(&*binary_expr.left) {
-            let right_type = self.check_expression(&*binary_expr.right)?;
-            return Ok((Some(string), right_type));
-          }
-        }
-        _ => {}
-      },
-      Expression::Call(call_expr) => match &*call_expr.left {
-        Expression::Identifier(identifier) => {
-          let name = identifier.name.as_str();
-          return Ok((Some(name.to_string()), None));
-        }
-        _ => {}
+      Expression::Identifier(identifier) => self.narrow_truthiness(&identifier.name),
+      Expression::Grouped(grouped) if grouped.expressions.len() == 1 => self.narrow_condition(&grouped.expressions[0]),
+      Expression::Unary(unary) if unary.operator == UnaryOperator::Not => {
+        Ok(self.narrow_condition(&unary.operand)?.negate())
+      }
+      Expression::Binary(binary) => match binary.operator {
+        BinaryOperator::Equal => self.narrow_equality(&binary.left, &binary.right),
+        BinaryOperator::NotEqual => Ok(self.narrow_equality(&binary.left, &binary.right)?.negate()),
+        BinaryOperator::And => self.narrow_and(&binary.left, &binary.right),
+        BinaryOperator::Or => self.narrow_or(&binary.left, &binary.right),
+        _ => Ok(Narrowing::default()),
       },
-      _ => {}
-    }
-    Ok((None, None))
-  }
-
-  fn check_narrowing(&mut self, expr: &Expression) -> CheckResult<Option<Type>> {
-    match expr {
-      Expression::Binary(binary_expr) => {
-        match binary_expr.operator {
-          BinaryOperator::Equal => {
-            let left_type = self.check_expression(&*binary_expr.left)?.unwrap_or(Type::Nil);
-            let left_type = self.check_type(&left_type)?;
-            let right_type = self.check_expression(&*binary_expr.right)?.unwrap_or(Type::Nil);
-            let right_type = self.check_type(&right_type)?;
-            match left_type {
-              Type::Union(union) => {
-                for left_type in union.types {
-                  if left_type.check_match(&right_type) {
-                    return Ok(Some(left_type));
-                  }
-                }
-              }
-              Type::Option(option) => {
-                return if right_type.is_nil() {
-                  Ok(Some(Type::Nil))
-                } else {
-                  return Ok(Some(*option.inner_type));
-                }
-              }
-              _ => {
-                return Ok(Some(right_type));
-              }
-            }
-          }
-          _ => {}
+      _ => Ok(Narrowing::default()),
+    }
+  }
+
+  // checks an expression as if the narrowings were in effect
+  pub fn with_narrowings<T>(
+    &mut self,
+    narrowings: &[(String, Type)],
+    check: impl FnOnce(&mut Self) -> CheckResult<T>,
+  ) -> CheckResult<T> {
+    self.ctx.enter_scope();
+    self.apply_narrowings(narrowings);
+    let result = check(self);
+    self.ctx.leave_scope();
+    result
+  }
+
+  // later narrowings of the same variable win, as they were computed from the earlier ones
+  pub fn apply_narrowings(&mut self, narrowings: &[(String, Type)]) {
+    for (name, narrowed_type) in narrowings {
+      self.ctx.narrow_variable(name, narrowed_type.clone());
+    }
+  }
+
+  // `x = nil`, the variable holds only the declared members the value fits
+  pub fn narrow_assignment(&mut self, name: &str, assigned_type: &Type) -> CheckResult<()> {
+    let Some(members) = self.variable_members(name)? else {
+      return Ok(());
+    };
+    if members.iter().any(is_opaque) {
+      return Ok(());
+    }
+    let assigned_members = union_members(assigned_type);
+    let kept = members.into_iter().filter(|member| assigned_members.iter().any(|value| member.check_match(value)));
+    if let Some(narrowed_type) = self.create_union(kept.collect()) {
+      self.ctx.narrow_variable(name, narrowed_type);
+    }
+    Ok(())
+  }
+
+  // a loop body can run again after it assigns a variable, so the narrowings before it don't hold there
+  pub fn forget_loop_narrowings(&mut self, body: &ast::Statement) {
+    let mut names = vec![];
+    assigned_names(body, &mut names);
+    for name in names {
+      self.ctx.forget_narrowing(&name);
+    }
+  }
+
+  // `a and b`, `b` is only checked when `a` is true
+  fn narrow_and(&mut self, left: &Expression, right: &Expression) -> CheckResult<Narrowing> {
+    let left = self.narrow_condition(left)?;
+    let right = self.with_narrowings(&left.truthy, |checker| checker.narrow_condition(right))?;
+    let truthy = left.truthy.into_iter().chain(right.truthy).collect();
+    Ok(Narrowing { truthy, falsy: self.merge_either(&left.falsy, &right.falsy) })
+  }
+
+  // `a or b`, `b` is only checked when `a` is false
+  fn narrow_or(&mut self, left: &Expression, right: &Expression) -> CheckResult<Narrowing> {
+    let left = self.narrow_condition(left)?;
+    let right = self.with_narrowings(&left.falsy, |checker| checker.narrow_condition(right))?;
+    let falsy = left.falsy.into_iter().chain(right.falsy).collect();
+    Ok(Narrowing { truthy: self.merge_either(&left.truthy, &right.truthy), falsy })
+  }
+
+  // only a variable narrowed on both paths is known after either of them
+  fn merge_either(&self, left: &[(String, Type)], right: &[(String, Type)]) -> Vec<(String, Type)> {
+    let mut merged = vec![];
+    for (name, left_type) in left.iter().rev() {
+      if merged.iter().any(|(merged_name, _)| merged_name == name) {
+        continue;
+      }
+      if let Some((_, right_type)) = right.iter().rev().find(|(right_name, _)| right_name == name) {
+        let members = union_members(left_type).into_iter().chain(union_members(right_type)).collect();
+        if let Some(merged_type) = self.create_union(members) {
+          merged.push((name.clone(), merged_type));
         }
-        Ok(None)
       }
-      _ => Ok(None),
+    }
+    merged
+  }
+
+  // `if x then`, x is neither nil nor false
+  fn narrow_truthiness(&mut self, name: &str) -> CheckResult<Narrowing> {
+    let Some(members) = self.variable_members(name)? else {
+      return Ok(Narrowing::default());
+    };
+    // a `boolean` or an opaque value is on both sides
+    let exhaustive = !members.iter().any(|member| is_opaque(member) || matches!(member, Type::Boolean));
+    let (falsy, truthy): (Vec<_>, Vec<_>) = members.into_iter().partition(is_falsy_member);
+    Ok(self.create_partition_narrowing(name, truthy, falsy, exhaustive))
+  }
+
+  // `x == nil`, `x == "up"`, `type(x) == "string"`
+  fn narrow_equality(&mut self, left: &Expression, right: &Expression) -> CheckResult<Narrowing> {
+    if let Some((name, type_name)) = type_call_comparison(left, right) {
+      return self.narrow_type_name(&name, &type_name);
+    }
+    let (name, value) = match (left, right) {
+      (Expression::Identifier(identifier), value) | (value, Expression::Identifier(identifier)) => {
+        (identifier.name.as_str(), value)
+      }
+      _ => return Ok(Narrowing::default()),
+    };
+    let Some(members) = self.variable_members(name)? else {
+      return Ok(Narrowing::default());
+    };
+
+    let value_type = self.check_expression(value)?.unwrap_or(Type::Nil);
+    let exhaustive = !members.iter().any(is_opaque);
+    // only a literal of a union narrows to the literal itself, `x == 5` of a `number` could be any number
+    match value_type {
+      Type::Nil => {
+        let (truthy, falsy) = members.into_iter().partition(Type::is_nil);
+        Ok(self.create_partition_narrowing(name, truthy, falsy, exhaustive))
+      }
+      Type::Literal(_) if members.contains(&value_type) => {
+        let falsy = members.into_iter().filter(|member| *member != value_type).collect();
+        Ok(self.create_partition_narrowing(name, vec![value_type], falsy, exhaustive))
+      }
+      value_type => {
+        let value_type = value_type.widen();
+        let truthy = members.into_iter().filter(|member| member.widen().check_match(&value_type)).collect();
+        Ok(self.create_narrowing(name, truthy, vec![]))
+      }
+    }
+  }
+
+  // `type(x) == "string"`
+  fn narrow_type_name(&mut self, name: &str, type_name: &str) -> CheckResult<Narrowing> {
+    let Some(members) = self.variable_members(name)? else {
+      return Ok(Narrowing::default());
+    };
+    if members.iter().any(|member| matches!(member, Type::Any | Type::Unknown)) {
+      let truthy = primitive_type(type_name).into_iter().collect();
+      return Ok(self.create_narrowing(name, truthy, vec![]));
+    }
+    let exhaustive = members.iter().all(has_known_type_name);
+    let (truthy, falsy) = members.into_iter().partition(|member| has_type_name(member, type_name));
+    Ok(self.create_partition_narrowing(name, truthy, falsy, exhaustive))
+  }
+
+  // the members of the variable type, `option<number>` is `number` and `nil`
+  fn variable_members(&mut self, name: &str) -> CheckResult<Option<Vec<Type>>> {
+    let Some(variable_type) = self.ctx.get_variable(name, None).cloned() else {
+      return Ok(None);
+    };
+    let variable_type = self.check_type(&variable_type)?;
+    let mut members = vec![];
+    for member in union_members(&variable_type) {
+      let member = self.check_type(&member)?;
+      members.extend(union_members(&member));
+    }
+    Ok(Some(members))
+  }
+
+  // an empty side narrows nothing, that path can't be taken
+  fn create_narrowing(&self, name: &str, truthy: Vec<Type>, falsy: Vec<Type>) -> Narrowing {
+    let narrow = |members: Vec<Type>| self.create_union(members).map(|narrowed| (name.to_string(), narrowed));
+    Narrowing { truthy: narrow(truthy).into_iter().collect(), falsy: narrow(falsy).into_iter().collect() }
+  }
+
+  // when every member went to one side, the other side is `never`
+  fn create_partition_narrowing(&self, name: &str, truthy: Vec<Type>, falsy: Vec<Type>, exhaustive: bool) -> Narrowing {
+    let exhaust = |members: Vec<Type>| if exhaustive && members.is_empty() { vec![Type::Never] } else { members };
+    self.create_narrowing(name, exhaust(truthy), exhaust(falsy))
+  }
+
+  pub fn create_union(&self, members: Vec<Type>) -> Option<Type> {
+    let mut unique = vec![];
+    for member in members {
+      if !unique.contains(&member) {
+        unique.push(member);
+      }
+    }
+    match unique.iter().position(Type::is_nil) {
+      Some(nil_position) if unique.len() > 1 => {
+        unique.remove(nil_position);
+        self.create_type_based_array(unique).map(Type::new_option)
+      }
+      _ => self.create_type_based_array(unique),
     }
   }
 
-  fn is_literal_narrowing(&self, expr: &Expression) -> bool {
-    match expr {
-      Expression::Call(call_expr) => match &*call_expr.left {
-        Expression::Identifier(identifier) => identifier.name.as_str() == "type",
+  // `if not x then return end`
+  //
+  // the rest of the block only runs when the statement did not leave it
+  pub fn always_exits(&self, statement: &ast::Statement) -> bool {
+    match statement {
+      ast::Statement::Return(_) | ast::Statement::Break(_) | ast::Statement::Goto(_) | ast::Statement::Continue(_) => {
+        true
+      }
+      ast::Statement::Block(block) => block.statements.last().is_some_and(|last| self.always_exits(last)),
+      ast::Statement::If(if_stmt) => {
+        let Some(else_body) = &if_stmt.else_body else {
+          return false;
+        };
+        self.always_exits(&if_stmt.then_body)
+          && if_stmt.else_if_branches.iter().all(|branch| self.always_exits(&branch.then_branch))
+          && self.always_exits(else_body)
+      }
+      // `error(...)`, or any other function that never returns
+      ast::Statement::Expression(Expression::Call(call)) => match &*call.left {
+        Expression::Identifier(identifier) => matches!(
+          self.ctx.get_variable(&identifier.name, None),
+          Some(Type::Function(function)) if matches!(*function.return_type, Type::Never)
+        ),
         _ => false,
       },
-      Expression::Identifier(identifier) => {
-        return true;
-      }
-      Expression::Literal(literal) => matches!(literal, LiteralExpression::Nil(_)),
       _ => false,
     }
   }
+}
+
+// the variables a statement assigns, without looking into nested functions
+fn assigned_names(statement: &ast::Statement, names: &mut Vec<String>) {
+  match statement {
+    ast::Statement::Expression(Expression::Assign(assign)) => {
+      for left in &assign.left {
+        if let Expression::Identifier(identifier) = left {
+          names.push(identifier.name.clone());
+        }
+      }
+    }
+    ast::Statement::Expression(Expression::CompoundAssign(assign)) => {
+      if let Expression::Identifier(identifier) = &*assign.left {
+        names.push(identifier.name.clone());
+      }
+    }
+    ast::Statement::Block(block) => block.statements.iter().for_each(|statement| assigned_names(statement, names)),
+    ast::Statement::If(if_stmt) => {
+      assigned_names(&if_stmt.then_body, names);
+      if_stmt.else_if_branches.iter().for_each(|branch| assigned_names(&branch.then_branch, names));
+      if let Some(else_body) = &if_stmt.else_body {
+        assigned_names(else_body, names);
+      }
+    }
+    ast::Statement::While(while_) => assigned_names(&while_.body, names),
+    ast::Statement::Repeat(repeat) => assigned_names(&repeat.body, names),
+    ast::Statement::For(for_) => assigned_names(&for_.body, names),
+    ast::Statement::ForIn(for_in) => assigned_names(&for_in.body, names),
+    _ => {}
+  }
+}
+
+pub fn union_members(union_type: &Type) -> Vec<Type> {
+  match union_type {
+    Type::Option(option) => union_members(&option.inner_type).into_iter().chain([Type::Nil]).collect(),
+    Type::Union(union) => union.types.iter().flat_map(union_members).collect(),
+    _ => vec![union_type.clone()],
+  }
+}
+
+pub fn is_falsy_member(member: &Type) -> bool {
+  matches!(member, Type::Nil | Type::Literal(LiteralType::Boolean(false)))
+}
 
-  fn get_identifier_string(&self, expr: &Expression) -> Option<String> {
-    if let Expression::Identifier(identifier) = expr {
-      return Some(identifier.name.clone());
+fn can_be_falsy(condition_type: &Type) -> bool {
+  union_members(condition_type).iter().any(|member| {
+    matches!(member, Type::Boolean | Type::Literal(LiteralType::Boolean(_)))
+      || is_opaque(member)
+      || is_falsy_member(member)
+  })
+}
+
+// `type(x) == "string"` or `"string" == type(x)`
+fn type_call_comparison(left: &Expression, right: &Expression) -> Option<(String, String)> {
+  match (left, right) {
+    (Expression::Call(call), Expression::Literal(LiteralExpression::String(type_name)))
+    | (Expression::Literal(LiteralExpression::String(type_name)), Expression::Call(call)) => {
+      let Expression::Identifier(function) = &*call.left else {
+        return None;
+      };
+      match super::check_call_expression::call_arguments(&call.args) {
+        [Expression::Identifier(argument)] if function.name == "type" => {
+          Some((argument.name.clone(), type_name.value.clone()))
+        }
+        _ => None,
+      }
     }
-    None
+    _ => None,
+  }
+}
+
+fn primitive_type(type_name: &str) -> Option<Type> {
+  match type_name {
+    "string" => Some(Type::String),
+    "number" => Some(Type::Number),
+    "boolean" => Some(Type::Boolean),
+    "nil" => Some(Type::Nil),
+    _ => None,
+  }
+}
+
+// a value of any type, it can't be split by a condition
+fn is_opaque(member: &Type) -> bool {
+  matches!(member, Type::Unknown | Type::Any | Type::Alias(_))
+}
+
+fn has_known_type_name(member: &Type) -> bool {
+  matches!(
+    member.widen(),
+    Type::String | Type::Number | Type::Boolean | Type::Nil | Type::Table(_) | Type::Function(_) | Type::Overload(_)
+  )
+}
+
+// the name `type(x)` gives for a value of the member type
+fn has_type_name(member: &Type, type_name: &str) -> bool {
+  match member.widen() {
+    Type::String => type_name == "string",
+    Type::Number => type_name == "number",
+    Type::Boolean => type_name == "boolean",
+    Type::Nil => type_name == "nil",
+    Type::Table(_) => type_name == "table",
+    Type::Function(_) | Type::Overload(_) => type_name == "function",
+    _ => true,
   }
 }
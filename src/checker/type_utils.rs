@@ -15,7 +15,11 @@ impl<'a> Checker<'a> {
     variables.iter().cloned().zip(types.iter().cloned()).collect()
   }
 
-  pub fn create_type_based_array(&self, types: Vec<Type>) -> Option<Type> {
+  pub fn create_type_based_array(&self, mut types: Vec<Type>) -> Option<Type> {
+    // `never` adds no value to the others
+    if types.iter().any(|ty| !matches!(ty, Type::Never)) {
+      types.retain(|ty| !matches!(ty, Type::Never));
+    }
     if types.len() == 0 {
       return None;
     }
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use super::{check_call_expression::call_arguments, type_utils::CheckResult, Checker};
+use crate::{
+  ast::ast,
+  diagnostics::TypeError,
+  types::{FunctionType, TableType, Type},
+  utils::range::Range,
+};
+
+type GenericBinds = HashMap<String, Type>;
+
+impl<'a> Checker<'a> {
+  // map<T, U>(xs: {T}, f: function(x: T): U): {U}
+  //
+  // type parameters are inferred from the arguments, then the call is checked as a regular one
+  pub fn check_generic_function_call(
+    &mut self,
+    function: &FunctionType,
+    args: &ast::Expression,
+  ) -> CheckResult<Option<Type>> {
+    let expressions = call_arguments(args);
+    let arg_types = self.check_expression_list(expressions)?;
+
+    let mut binds = GenericBinds::new();
+    for (position, arg_type) in arg_types.iter().enumerate() {
+      let param = match function.params.get(position).or(function.params.last()) {
+        Some(Type::Variadic(variadic)) => &variadic.inner_type,
+        Some(param) if position < function.params.len() => param,
+        _ => break,
+      };
+      let range = expressions.get(position).or(expressions.last()).unwrap().get_range();
+      self.unify(param, arg_type, &function.generics, &mut binds, &range)?;
+    }
+
+    // parameters without an argument to infer from are unknown
+    for name in &function.generics {
+      binds.entry(name.clone()).or_insert(Type::Any);
+    }
+
+    let Type::Function(bound) = self.apply_generic_bind_function(function, &binds)? else {
+      unreachable!("binding a function gives a function");
+    };
+    self.check_call_argument_types(args, arg_types, &bound.params)?;
+    Ok(Some(*bound.return_type))
+  }
+
+  fn unify(
+    &self,
+    param: &Type,
+    arg: &Type,
+    generics: &[String],
+    binds: &mut GenericBinds,
+    range: &Range,
+  ) -> CheckResult<()> {
+    match (param, arg) {
+      // nothing to learn from an unknown argument
+      (_, Type::Any) => Ok(()),
+      (Type::Alias(alias), _) if generics.contains(&alias.name) => match binds.get(&alias.name) {
+        Some(bound) if !bound.check_match(arg) => {
+          let diagnostic = TypeError::GenericInferenceConflict(
+            alias.name.clone(),
+            bound.to_string(),
+            arg.widen().to_string(),
+            Some(range.clone()),
+          );
+          Err(self.create_diagnostic(diagnostic))
+        }
+        Some(_) => Ok(()),
+        None => {
+          binds.insert(alias.name.clone(), arg.widen());
+          Ok(())
+        }
+      },
+      (Type::Table(param), Type::Table(arg)) => self.unify_table(param, arg, generics, binds, range),
+      (Type::Function(param), Type::Function(arg)) => {
+        for (param, arg) in param.params.iter().zip(&arg.params) {
+          self.unify(param, arg, generics, binds, range)?;
+        }
+        self.unify(&param.return_type, &arg.return_type, generics, binds, range)
+      }
+      (Type::Option(param), Type::Option(arg)) => {
+        self.unify(&param.inner_type, &arg.inner_type, generics, binds, range)
+      }
+      (Type::Option(param), arg) if !arg.is_nil() => self.unify(&param.inner_type, arg, generics, binds, range),
+      (Type::Variadic(param), Type::Variadic(arg)) => {
+        self.unify(&param.inner_type, &arg.inner_type, generics, binds, range)
+      }
+      (Type::Group(param), Type::Group(arg)) => {
+        for (param, arg) in param.types.iter().zip(&arg.types) {
+          self.unify(param, arg, generics, binds, range)?;
+        }
+        Ok(())
+      }
+      _ => Ok(()),
+    }
+  }
+
+  fn unify_table(
+    &self,
+    param: &TableType,
+    arg: &TableType,
+    generics: &[String],
+    binds: &mut GenericBinds,
+    range: &Range,
+  ) -> CheckResult<()> {
+    if let Some(param_array) = &param.array {
+      for arg_element in arg.element_types() {
+        self.unify(param_array, &arg_element, generics, binds, range)?;
+      }
+    }
+
+    if let (Some(param_tuple), Some(arg_tuple)) = (&param.tuple, &arg.tuple) {
+      for (param_element, arg_element) in param_tuple.iter().zip(arg_tuple) {
+        self.unify(param_element, arg_element, generics, binds, range)?;
+      }
+    }
+
+    if let (Some(param_map), Some(arg_map)) = (&param.map, &arg.map) {
+      for (key, param_value) in param_map {
+        if let Some(arg_value) = arg_map.get(key) {
+          self.unify(param_value, arg_value, generics, binds, range)?;
+        }
+      }
+    }
+
+    if let (Some(param_index), Some(arg_index)) = (&param.index, &arg.index) {
+      self.unify(&param_index.key, &arg_index.key, generics, binds, range)?;
+      self.unify(&param_index.value, &arg_index.value, generics, binds, range)?;
+    }
+    Ok(())
+  }
+}
@@ -1,4 +1,4 @@
-use clap::{Arg, Command};
+use clap::{Arg, ArgAction, Command};
 
 pub fn command_line() -> clap::ArgMatches {
   let matches = Command::new("Stella")
@@ -10,19 +10,29 @@ pub fn command_line() -> clap::ArgMatches {
     .subcommand(
       Command::new("check")
         .about("check a stella(lua) file.")
-        .arg(Arg::new("file").help("the stella(lua) file to check.").required(true)),
+        .arg(Arg::new("file").help("the stella(lua) file to check.").required(true))
+        .arg(unchecked_index_arg()),
     )
     .subcommand(
       Command::new("compile")
         .about("compile a stella(lua) file to a native executable.")
-        .arg(Arg::new("file").help("the stella(lua) file to compile.").required(true)),
+        .arg(Arg::new("file").help("the stella(lua) file to compile.").required(true))
+        .arg(unchecked_index_arg()),
     )
     .subcommand(
       Command::new("run")
         .about("run stella(lua) code.")
-        .arg(Arg::new("file").help("the stella(lua) file to run.").required(true)),
+        .arg(Arg::new("file").help("the stella(lua) file to run.").required(true))
+        .arg(unchecked_index_arg()),
     )
     .get_matches();
 
   return matches;
 }
+
+fn unchecked_index_arg() -> Arg {
+  Arg::new("unchecked-index")
+    .long("unchecked-index")
+    .help("read `{[K]: V}` tables as `V` instead of `option<V>`.")
+    .action(ArgAction::SetTrue)
+}
@@ -68,6 +68,8 @@ impl Context {
 
     if let Some(scope) = scope {
       scope.variables.insert(name.to_owned(), tyy);
+      // a new variable is not narrowed
+      scope.narrowings.remove(name);
       // set unused variable
       scope.unused_variables.insert(name.to_owned());
     };
@@ -110,12 +112,27 @@ impl Context {
       return None;
     }
     for scope in self.scopes.iter().rev() {
-      if let Some(ty) = scope.variables.get(name) {
+      if let Some(ty) = scope.narrowings.get(name).or_else(|| scope.variables.get(name)) {
         return Some(ty);
       }
     }
     None
   }
+
+  // Narrowing Management
+  pub fn narrow_variable(&mut self, name: &str, tyy: Type) {
+    if let Some(scope) = self.current_scope_mut() {
+      scope.narrowings.insert(name.to_owned(), tyy);
+    }
+  }
+
+  // after an assignment the variable can hold any value of its declared type again
+  pub fn forget_narrowing(&mut self, name: &str) {
+    for scope in self.scopes.iter_mut() {
+      scope.narrowings.remove(name);
+    }
+  }
+
   pub fn get_global_variable(&self, name: &str) -> Option<&Type> {
     self.scopes.get(0).and_then(|scope| scope.variables.get(name))
   }
@@ -135,7 +152,7 @@ impl Context {
   pub fn check_unused_variables(&self) -> Vec<String> {
     let scope = self.current_scope();
     scope.map_or(vec![], |scope| {
-      scope.unused_variables.iter().filter(|var| *var != &self.return_decl_name).cloned().collect()
+      scope.unused_variables.iter().filter(|var| *var != &self.return_decl_name && *var != "_").cloned().collect()
     })
   }
 
@@ -168,7 +185,7 @@ impl Context {
   }
 
   pub fn create_anonymous_function(&self) -> Type {
-    Type::new_function(vec![], Type::Unknown)
+    Type::new_function(vec![], Type::Any)
   }
 
   // Module Management
@@ -256,6 +273,8 @@ pub struct Scope {
   pub unused_variables: BTreeSet<String>,
   pub ranges: HashMap<String, Range>,
   pub local_declarations: BTreeMap<String, bool>,
+  // the narrower type a variable has in this scope, `x` is `number` in `if x ~= nil then`
+  pub narrowings: HashMap<String, Type>,
 }
 
 impl Scope {
@@ -266,6 +285,7 @@ impl Scope {
       types: HashMap::new(),
       unused_variables: BTreeSet::new(),
       ranges: HashMap::new(),
+      narrowings: HashMap::new(),
     }
   }
 }
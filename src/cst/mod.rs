@@ -0,0 +1,3 @@
+mod syntax_tree;
+
+pub use syntax_tree::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, SyntaxTree, Trivia, TriviaKind};
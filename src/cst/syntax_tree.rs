@@ -0,0 +1,279 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ast::ast;
+use crate::ast::tokens::{Token, TokenKind};
+use crate::diagnostics::Diagnostic;
+use crate::lexer::Lexer;
+use crate::parser::parser::Parser;
+use crate::utils::range::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TriviaKind {
+  Whitespace,
+  Comment,
+  // characters the lexer couldn't make sense of, kept so the source still round-trips
+  Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trivia {
+  pub kind: TriviaKind,
+  pub text: String,
+  pub range: Range,
+}
+
+impl Trivia {
+  pub fn new(kind: TriviaKind, text: &str, range: Range) -> Self {
+    Trivia { kind, text: text.to_string(), range }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyntaxToken {
+  pub kind: TokenKind,
+  // the token exactly as written in the source
+  pub text: String,
+  pub range: Range,
+  pub leading_trivia: Vec<Trivia>,
+  // whitespace and comments after the token up to the end of its line
+  pub trailing_trivia: Vec<Trivia>,
+}
+
+impl SyntaxToken {
+  pub fn new(token: Token, text: &str, leading_trivia: Vec<Trivia>) -> Self {
+    SyntaxToken {
+      kind: token.kind,
+      text: text.to_string(),
+      range: token.range,
+      leading_trivia,
+      trailing_trivia: vec![],
+    }
+  }
+
+  pub fn write_source(&self, source: &mut String) {
+    self.leading_trivia.iter().for_each(|trivia| source.push_str(&trivia.text));
+    source.push_str(&self.text);
+    self.trailing_trivia.iter().for_each(|trivia| source.push_str(&trivia.text));
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SyntaxKind {
+  Program,
+  Function,
+  Return,
+  If,
+  While,
+  Repeat,
+  For,
+  ForIn,
+  Break,
+  Goto,
+  Label,
+  Block,
+  Empty,
+  TypeDeclaration,
+  Enum,
+  Class,
+  Expression,
+  Continue,
+  Local,
+}
+
+impl SyntaxKind {
+  pub fn from_statement(statement: &ast::Statement) -> Self {
+    match statement {
+      ast::Statement::Function(_) => SyntaxKind::Function,
+      ast::Statement::Return(_) => SyntaxKind::Return,
+      ast::Statement::If(_) => SyntaxKind::If,
+      ast::Statement::While(_) => SyntaxKind::While,
+      ast::Statement::Repeat(_) => SyntaxKind::Repeat,
+      ast::Statement::For(_) => SyntaxKind::For,
+      ast::Statement::ForIn(_) => SyntaxKind::ForIn,
+      ast::Statement::Break(_) => SyntaxKind::Break,
+      ast::Statement::Goto(_) => SyntaxKind::Goto,
+      ast::Statement::Label(_) => SyntaxKind::Label,
+      ast::Statement::Block(_) => SyntaxKind::Block,
+      ast::Statement::Empty(_) => SyntaxKind::Empty,
+      ast::Statement::TypeDeclaration(_) => SyntaxKind::TypeDeclaration,
+      ast::Statement::Enum(_) => SyntaxKind::Enum,
+      ast::Statement::Class(_) => SyntaxKind::Class,
+      ast::Statement::Expression(_) => SyntaxKind::Expression,
+      ast::Statement::Continue(_) => SyntaxKind::Continue,
+      ast::Statement::Local(_) => SyntaxKind::Local,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SyntaxElement {
+  Node(SyntaxNode),
+  Token(SyntaxToken),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyntaxNode {
+  pub kind: SyntaxKind,
+  pub range: Range,
+  pub children: Vec<SyntaxElement>,
+}
+
+impl SyntaxNode {
+  pub fn new(kind: SyntaxKind, range: Range) -> Self {
+    SyntaxNode { kind, range, children: vec![] }
+  }
+
+  pub fn tokens(&self) -> Vec<&SyntaxToken> {
+    let mut tokens = vec![];
+    for child in &self.children {
+      match child {
+        SyntaxElement::Node(node) => tokens.extend(node.tokens()),
+        SyntaxElement::Token(token) => tokens.push(token),
+      }
+    }
+    tokens
+  }
+
+  pub fn write_source(&self, source: &mut String) {
+    self.tokens().iter().for_each(|token| token.write_source(source));
+  }
+}
+
+// lossless view of a file: every byte of the source belongs to exactly one token or trivia,
+// and tokens are grouped into nodes following the statements of the ast
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyntaxTree {
+  pub root: SyntaxNode,
+}
+
+impl SyntaxTree {
+  pub fn parse(raw: &str, file_name: &str) -> (SyntaxTree, Vec<Diagnostic>) {
+    let (program, diagnostics) = Parser::new(raw, file_name).parse_program();
+    let tokens = create_syntax_tokens(raw, file_name);
+    let statements: Vec<&ast::Statement> = program.statements.iter().collect();
+    let range = Range { start: 0, end: raw.len() };
+    let root = create_syntax_node(SyntaxKind::Program, range, &statements, &mut tokens.into_iter().peekable());
+    (SyntaxTree { root }, diagnostics)
+  }
+
+  pub fn to_source(&self) -> String {
+    let mut source = String::new();
+    self.root.write_source(&mut source);
+    source
+  }
+}
+
+fn create_syntax_tokens(raw: &str, file_name: &str) -> Vec<SyntaxToken> {
+  let mut lexer = Lexer::new(raw, file_name);
+  let mut tokens: Vec<SyntaxToken> = vec![];
+  let mut leading_trivia = vec![];
+  // trivia goes to the previous token until the end of its line
+  let mut trailing = false;
+  let mut cursor = 0;
+
+  loop {
+    let token = lexer.next_token();
+    let gap = Range { start: cursor, end: token.range.start };
+    cursor = token.range.end;
+
+    let mut trivia = create_gap_trivia(raw, gap);
+    if token.is_comment() {
+      trivia.push(Trivia::new(TriviaKind::Comment, &raw[token.range.start..token.range.end], token.range.clone()));
+    }
+
+    for trivia in trivia {
+      match tokens.last_mut() {
+        Some(last) if trailing => {
+          trailing = !trivia.text.ends_with('\n');
+          last.trailing_trivia.push(trivia);
+        }
+        _ => leading_trivia.push(trivia),
+      }
+    }
+
+    if token.is_comment() {
+      continue;
+    }
+
+    let is_eof = token.kind == TokenKind::EOF;
+    let text = &raw[token.range.start..token.range.end];
+    tokens.push(SyntaxToken::new(token, text, std::mem::take(&mut leading_trivia)));
+    trailing = true;
+    if is_eof {
+      return tokens;
+    }
+  }
+}
+
+// the text between two tokens; whitespace is split after the first line break, so that the
+// break can end the trailing trivia of the previous token
+fn create_gap_trivia(raw: &str, gap: Range) -> Vec<Trivia> {
+  let mut trivia = vec![];
+  let mut start = gap.start;
+  while start < gap.end {
+    let text = &raw[start..gap.end];
+    let (kind, length) = match text.find(|c: char| !c.is_whitespace()) {
+      Some(0) => (TriviaKind::Skipped, text.find(char::is_whitespace).unwrap_or(text.len())),
+      Some(length) => (TriviaKind::Whitespace, length),
+      None => (TriviaKind::Whitespace, text.len()),
+    };
+    let length = match (kind, text[..length].find('\n')) {
+      (TriviaKind::Whitespace, Some(line_break)) if line_break + 1 < length => line_break + 1,
+      _ => length,
+    };
+    trivia.push(Trivia::new(kind, &text[..length], Range { start, end: start + length }));
+    start += length;
+  }
+  trivia
+}
+
+fn create_syntax_node<I: Iterator<Item = SyntaxToken>>(
+  kind: SyntaxKind,
+  range: Range,
+  statements: &[&ast::Statement],
+  tokens: &mut std::iter::Peekable<I>,
+) -> SyntaxNode {
+  let mut node = SyntaxNode::new(kind, range.clone());
+  let mut statements = statements.iter().peekable();
+
+  while let Some(token) = tokens.peek() {
+    let start = token.range.start;
+    if kind != SyntaxKind::Program && start >= range.end {
+      break;
+    }
+
+    // statements without tokens of their own, like empty ones, end before the next token
+    while statements.peek().is_some_and(|statement| statement.get_range().end <= start) {
+      statements.next();
+    }
+
+    match statements.peek() {
+      Some(statement) if statement.get_range().start <= start => {
+        let statement = statements.next().unwrap();
+        let kind = SyntaxKind::from_statement(statement);
+        let children = child_statements(statement);
+        node.children.push(SyntaxElement::Node(create_syntax_node(kind, statement.get_range(), &children, tokens)));
+      }
+      _ => node.children.push(SyntaxElement::Token(tokens.next().unwrap())),
+    }
+  }
+  node
+}
+
+fn child_statements(statement: &ast::Statement) -> Vec<&ast::Statement> {
+  match statement {
+    ast::Statement::Function(function) => vec![function.body.as_ref()],
+    ast::Statement::If(if_) => {
+      let mut children = vec![if_.then_body.as_ref()];
+      children.extend(if_.else_if_branches.iter().map(|else_if| else_if.then_branch.as_ref()));
+      children.extend(if_.else_body.as_deref());
+      children
+    }
+    ast::Statement::While(while_) => vec![while_.body.as_ref()],
+    ast::Statement::Repeat(repeat) => vec![repeat.body.as_ref()],
+    ast::Statement::For(for_) => vec![for_.body.as_ref()],
+    ast::Statement::ForIn(for_in) => vec![for_in.body.as_ref()],
+    ast::Statement::Block(block) => block.statements.iter().collect(),
+    _ => vec![],
+  }
+}
@@ -20,7 +20,7 @@ pub fn format_undeclared_type(name: &str) -> String {
 }
 
 pub fn format_mismatched_key_type(key: &str) -> String {
-  format!("expected `string` key, but found `{}`", key)
+  format!("expected `string`, `number` or `boolean` key, but found `{}`", key)
 }
 
 pub fn format_mismatched_accessor_type(index: &str) -> String {
@@ -38,6 +38,10 @@ pub fn format_function_arity_mismatch(expected: usize, found: usize) -> String {
   }
 }
 
+pub fn format_function_arity_range_mismatch(min: usize, max: usize, found: usize) -> String {
+  format!("expected {} to {} args, found {}", min, max, found)
+}
+
 pub fn format_generic_call_arity_mismatch(expected: usize, found: usize) -> String {
   match expected {
     1 => format!("expected 1 type arg, found `{}`", found),
@@ -79,6 +83,12 @@ pub fn format_unsupported_operator(left: &str, operator: BinaryOperator, right:
     GreaterThanOrEqual => format!("`{}` cannot be greater than or equal to `{}`", left, right),
     DoubleDot => format!("cannot concatenate `{}` and `{}`", left, right),
     DoubleSlash => format!("cannot divide `{}` by `{}` with `//`", left, right),
+    Power => format!("cannot raise `{}` to the power of `{}`", left, right),
+    BitwiseAnd => format!("cannot perform bitwise and on `{}` and `{}`", left, right),
+    BitwiseOr => format!("cannot perform bitwise or on `{}` and `{}`", left, right),
+    BitwiseXor => format!("cannot perform bitwise xor on `{}` and `{}`", left, right),
+    ShiftLeft => format!("cannot shift `{}` left by `{}`", left, right),
+    ShiftRight => format!("cannot shift `{}` right by `{}`", left, right),
   }
 }
 
@@ -88,6 +98,7 @@ pub fn format_unsupported_unary_operator(operator: UnaryOperator, right: &str) -
     Negate => format!("cannot negate `{}`", right),
     Not => format!("cannot apply logical NOT to `{}`", right),
     Hash => format!("cannot compute length of `{}`", right),
+    BitwiseNot => format!("cannot perform bitwise not on `{}`", right),
   }
 }
 
@@ -96,6 +107,91 @@ pub fn format_redeclared_in_same_scope(name: &str) -> String {
   format!("`{}` redeclared in same scope", name)
 }
 
+pub fn format_undefined_label(label: &str) -> String {
+  format!("no visible label `{}` for goto", label)
+}
+
+pub fn format_duplicate_label(label: &str) -> String {
+  format!("label `{}` already defined", label)
+}
+
+pub fn format_jump_into_local_scope(label: &str, local: &str) -> String {
+  format!("goto `{}` jumps into the scope of local `{}`", label, local)
+}
+
+pub fn format_outside_loop(keyword: &str) -> String {
+  format!("`{}` outside a loop", keyword)
+}
+
+pub fn format_generic_inference_conflict(name: &str, first: &str, second: &str) -> String {
+  format!("cannot infer `{}`, found both `{}` and `{}`", name, first, second)
+}
+
+pub fn format_vararg_outside_function() -> String {
+  "cannot use `...` outside a vararg function".to_string()
+}
+
+pub fn format_missing_field(field: &str, table: &str) -> String {
+  format!("missing field `{}`, required by `{}`", field, table)
+}
+
+pub fn format_unknown_field(field: &str, table: &str) -> String {
+  format!("unknown field `{}`, not declared in `{}`", field, table)
+}
+
+pub fn format_readonly_field(field: &str) -> String {
+  format!("cannot assign to `{}`, it is a readonly field", field)
+}
+
+pub fn format_tuple_index_out_of_range(index: usize, length: usize) -> String {
+  format!("index `{}` is out of range for a tuple of length {}", index, length)
+}
+
+pub fn format_invalid_assign_target() -> String {
+  "cannot assign to this expression".to_string()
+}
+
+pub fn format_never_function_returns() -> String {
+  "function declared `never` can return".to_string()
+}
+
+pub fn format_expected_field_name(found: &str) -> String {
+  format!("expected a field name like `\"id\"`, found `{}`", found)
+}
+
+pub fn format_expected_class(name: &str) -> String {
+  format!("expected a class, found `{}`", name)
+}
+
+pub fn format_declared_in_base_class(member: &str, base: &str) -> String {
+  format!("`{}` is already declared in the base class `{}`", member, base)
+}
+
+pub fn format_incompatible_override(method: &str, base: &str, expected: &str, found: &str) -> String {
+  format!("method `{}` doesn't match `{}.{}`, expected `{}`, found `{}`", method, base, method, expected, found)
+}
+
+pub fn format_uninitialized_field(field: &str, class: &str) -> String {
+  format!("field `{}` is never set in the constructor of `{}`", field, class)
+}
+
+// one line per candidate signature, with the reason it was rejected
+pub fn format_no_matching_overload(candidates: &[(String, String)]) -> String {
+  let mut message = String::from("no overload matches this call");
+  for (signature, reason) in candidates {
+    message.push_str(&format!("\n  `{}`: {}", signature, reason));
+  }
+  message
+}
+
+pub fn format_unnarrowed_unknown() -> String {
+  "a value of type `unknown` has to be narrowed before it's used".to_string()
+}
+
+pub fn format_conflicting_field(field: &str, left: &str, right: &str) -> String {
+  format!("field `{}` is `{}` on one side of `&` and `{}` on the other", field, left, right)
+}
+
 pub fn format_module_not_found(name: &str) -> String {
   format!("module `{}` not found", name)
 }
@@ -161,6 +257,21 @@ pub fn format_warning_redundant_type(name: &str, type_name: &str) -> String {
   // redundant type `number` for `x`"
   format!("redundant `{}` for `{}`", type_name, name)
 }
+pub fn format_warning_missing_values(expected: usize, found: usize) -> String {
+  format!("expected {}, but found {}, the missing values are `nil`", format_values(expected), found)
+}
+
+pub fn format_warning_extra_values(expected: usize, found: usize) -> String {
+  format!("expected {}, but found {}, the extra values are dropped", format_values(expected), found)
+}
+
+fn format_values(count: usize) -> String {
+  if count == 1 {
+    return "1 value".to_string();
+  }
+  format!("{} values", count)
+}
+
 pub fn format_warning_unused_variable(name: &str) -> String {
   if name == "..." {
     return format!("unused values in variadic arguments");
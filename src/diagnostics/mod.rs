@@ -11,13 +11,19 @@ use report::report_error;
 use std::fmt::{self, Debug};
 
 use format::{
-  format_cannot_index_non_array, format_expected_function, format_expected_table, format_expected_variadic,
-  format_field_not_found_in_table, format_function_arity_mismatch, format_generic_call_arity_mismatch,
-  format_mismatched_accessor_type, format_mismatched_key_type, format_mismatched_types, format_module_not_exported,
-  format_module_not_found, format_no_field, format_option_call_arity_mismatch, format_redeclared_in_same_scope,
-  format_shadow_warning, format_type_mismatch_assignment, format_undeclared_type, format_undeclared_variable,
-  format_unsupported_operator, format_unsupported_unary_operator, format_warning_redundant_type,
-  format_warning_unused_variable,
+  format_cannot_index_non_array, format_conflicting_field, format_declared_in_base_class, format_duplicate_label,
+  format_expected_class, format_expected_field_name, format_expected_function, format_expected_table,
+  format_expected_variadic, format_field_not_found_in_table, format_function_arity_mismatch,
+  format_function_arity_range_mismatch, format_generic_call_arity_mismatch, format_generic_inference_conflict,
+  format_incompatible_override, format_invalid_assign_target, format_jump_into_local_scope,
+  format_mismatched_accessor_type, format_mismatched_key_type, format_mismatched_types, format_missing_field,
+  format_module_not_exported, format_module_not_found, format_never_function_returns, format_no_field,
+  format_no_matching_overload, format_option_call_arity_mismatch, format_outside_loop, format_readonly_field,
+  format_redeclared_in_same_scope, format_shadow_warning, format_tuple_index_out_of_range,
+  format_type_mismatch_assignment, format_undeclared_type, format_undeclared_variable, format_undefined_label,
+  format_uninitialized_field, format_unknown_field, format_unnarrowed_unknown, format_unsupported_operator,
+  format_unsupported_unary_operator, format_vararg_outside_function, format_warning_extra_values,
+  format_warning_missing_values, format_warning_redundant_type, format_warning_unused_variable,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -106,6 +112,8 @@ pub enum TypeWarning {
   UnusedVariable(String, Option<Range>),
   ShadowedVariable(String, Option<Range>),
   RedundantType(String, String, Option<Range>),
+  MissingValues(usize, usize, Option<Range>),
+  ExtraValues(usize, usize, Option<Range>),
 }
 
 impl From<TypeWarning> for Diagnostic {
@@ -114,6 +122,8 @@ impl From<TypeWarning> for Diagnostic {
       TypeWarning::UnusedVariable(name, loc) => (format_warning_unused_variable(&name), loc),
       TypeWarning::ShadowedVariable(name, loc) => (format_shadow_warning(&name), loc),
       TypeWarning::RedundantType(name, type_name, loc) => (format_warning_redundant_type(&name, &type_name), loc),
+      TypeWarning::MissingValues(expected, found, loc) => (format_warning_missing_values(expected, found), loc),
+      TypeWarning::ExtraValues(expected, found, loc) => (format_warning_extra_values(expected, found), loc),
     };
     Diagnostic::new(DiagnosticLevel::Warning, message, range)
   }
@@ -130,6 +140,7 @@ pub enum TypeError {
   TypeMismatchAssignment(String, String, Option<Range>),
   RedeclaredInSameScope(String, Option<Range>),
   FunctionArityMismatch(usize, usize, Option<Range>),
+  FunctionArityRangeMismatch(usize, usize, usize, Option<Range>),
   UnsupportedOperator(String, BinaryOperator, String, Option<Range>),
   UnsupportedUnaryOperator(UnaryOperator, String, Option<Range>),
   ExpectedTable(String, Option<Range>),
@@ -141,6 +152,26 @@ pub enum TypeError {
   GenericCallArityMismatch(usize, usize, Option<Range>),
   OptionCallArityMismatch(usize, Option<Range>),
   ExpectedVariadic(String, Option<Range>),
+  UndefinedLabel(String, Option<Range>),
+  DuplicateLabel(String, Option<Range>),
+  JumpIntoLocalScope(String, String, Option<Range>),
+  OutsideLoop(String, Option<Range>),
+  VarargOutsideFunction(Option<Range>),
+  GenericInferenceConflict(String, String, String, Option<Range>),
+  MissingField(String, String, Option<Range>),
+  UnknownField(String, String, Option<Range>),
+  ReadonlyField(String, Option<Range>),
+  TupleIndexOutOfRange(usize, usize, Option<Range>),
+  ExpectedClass(String, Option<Range>),
+  DeclaredInBaseClass(String, String, Option<Range>),
+  IncompatibleOverride(String, String, String, String, Option<Range>),
+  UninitializedField(String, String, Option<Range>),
+  NoMatchingOverload(Vec<(String, String)>, Option<Range>),
+  UnnarrowedUnknown(Option<Range>),
+  ConflictingField(String, String, String, Option<Range>),
+  ExpectedFieldName(String, Option<Range>),
+  InvalidAssignTarget(Option<Range>),
+  NeverFunctionReturns(Option<Range>),
 }
 impl From<TypeError> for Diagnostic {
   fn from(error: TypeError) -> Self {
@@ -148,6 +179,9 @@ impl From<TypeError> for Diagnostic {
       TypeError::MismatchedTypes(expected, found, rg) => (format_mismatched_types(&expected, &found), rg),
       TypeError::UndeclaredVariable(name, rg) => (format_undeclared_variable(&name), rg),
       TypeError::FunctionArityMismatch(expected, found, rg) => (format_function_arity_mismatch(expected, found), rg),
+      TypeError::FunctionArityRangeMismatch(min, max, found, rg) => {
+        (format_function_arity_range_mismatch(min, max, found), rg)
+      }
       TypeError::UnsupportedOperator(left, op, right, rg) => (format_unsupported_operator(&left, op, &right), rg),
       TypeError::RedeclaredInSameScope(name, rg) => (format_redeclared_in_same_scope(&name), rg),
       TypeError::ModuleNotFound(name, rg) => (format_module_not_found(&name), rg),
@@ -168,9 +202,33 @@ impl From<TypeError> for Diagnostic {
       }
       TypeError::OptionCallArityMismatch(found, rg) => (format_option_call_arity_mismatch(found), rg),
       TypeError::ExpectedVariadic(type_name, rg) => (format_expected_variadic(&type_name), rg),
+      TypeError::UndefinedLabel(label, rg) => (format_undefined_label(&label), rg),
+      TypeError::DuplicateLabel(label, rg) => (format_duplicate_label(&label), rg),
+      TypeError::JumpIntoLocalScope(label, local, rg) => (format_jump_into_local_scope(&label, &local), rg),
+      TypeError::OutsideLoop(keyword, rg) => (format_outside_loop(&keyword), rg),
+      TypeError::VarargOutsideFunction(rg) => (format_vararg_outside_function(), rg),
+      TypeError::GenericInferenceConflict(name, first, second, rg) => {
+        (format_generic_inference_conflict(&name, &first, &second), rg)
+      }
       TypeError::UnsupportedUnaryOperator(operator, right, rg) => {
         (format_unsupported_unary_operator(operator, &right), rg)
       }
+      TypeError::MissingField(field, table, rg) => (format_missing_field(&field, &table), rg),
+      TypeError::UnknownField(field, table, rg) => (format_unknown_field(&field, &table), rg),
+      TypeError::ReadonlyField(field, rg) => (format_readonly_field(&field), rg),
+      TypeError::TupleIndexOutOfRange(index, length, rg) => (format_tuple_index_out_of_range(index, length), rg),
+      TypeError::ExpectedClass(name, rg) => (format_expected_class(&name), rg),
+      TypeError::DeclaredInBaseClass(member, base, rg) => (format_declared_in_base_class(&member, &base), rg),
+      TypeError::IncompatibleOverride(method, base, expected, found, rg) => {
+        (format_incompatible_override(&method, &base, &expected, &found), rg)
+      }
+      TypeError::UninitializedField(field, class, rg) => (format_uninitialized_field(&field, &class), rg),
+      TypeError::NoMatchingOverload(candidates, rg) => (format_no_matching_overload(&candidates), rg),
+      TypeError::UnnarrowedUnknown(rg) => (format_unnarrowed_unknown(), rg),
+      TypeError::ConflictingField(field, left, right, rg) => (format_conflicting_field(&field, &left, &right), rg),
+      TypeError::ExpectedFieldName(found, rg) => (format_expected_field_name(&found), rg),
+      TypeError::InvalidAssignTarget(rg) => (format_invalid_assign_target(), rg),
+      TypeError::NeverFunctionReturns(rg) => (format_never_function_returns(), rg),
     };
 
     Diagnostic::new(DiagnosticLevel::Error, message, range)
@@ -1,6 +1,13 @@
 use crate::ast::ast::*;
+use crate::utils::range::Range;
+use std::collections::HashSet;
 pub mod emitter;
 
+// lua has no `continue`: it becomes a `goto` to a label at the end of the loop body, named by the
+// loop once its body is emitted. control characters are always escaped in emitted strings, so
+// the placeholder can't clash with user code
+const CONTINUE_LABEL: &str = "\u{1}continue\u{1}";
+
 impl Program {
   pub fn emit(&self) -> String {
     self.statements.iter().map(|stmt| stmt.emit()).collect::<Vec<String>>().join("\n")
@@ -16,11 +23,15 @@ impl Statement {
       Statement::While(while_) => while_.emit(),
       Statement::Repeat(repeat) => repeat.emit(),
       Statement::For(for_) => for_.emit(),
+      Statement::ForIn(for_in) => for_in.emit(),
       Statement::Break(break_) => break_.emit(),
       Statement::Goto(goto) => goto.emit(),
+      Statement::Label(label) => label.emit(),
       Statement::Block(block) => block.emit(),
       Statement::Empty(empty) => empty.emit(),
       Statement::TypeDeclaration(declaration) => declaration.emit(),
+      Statement::Enum(declaration) => declaration.emit(),
+      Statement::Class(declaration) => declaration.emit(),
       Statement::Continue(continue_) => continue_.emit(),
       Statement::Local(local) => local.emit(),
       Statement::Expression(expression) => expression.emit(),
@@ -38,7 +49,9 @@ impl LocalStatement {
       }
       raw.push_str(&variable.emit());
     }
-    raw.push_str(" = ");
+    if !self.initializer.is_empty() {
+      raw.push_str(" = ");
+    }
     for (index, initializer) in self.initializer.iter().enumerate() {
       if index > 0 {
         raw.push_str(", ");
@@ -54,6 +67,11 @@ impl FunctionStatement {
   fn emit(&self) -> String {
     let mut raw = String::new();
     raw.push_str("function ");
+    for (index, segment) in self.path.iter().enumerate() {
+      raw.push_str(segment.lexeme());
+      let last_segment = index == self.path.len() - 1;
+      raw.push_str(if last_segment && self.method { ":" } else { "." });
+    }
     raw.push_str(&self.name.lexeme());
     raw.push_str("(");
     for (index, argument) in self.arguments.iter().enumerate() {
@@ -64,6 +82,9 @@ impl FunctionStatement {
     }
     raw.push_str(")");
     raw.push_str("\n");
+    for argument in &self.arguments {
+      raw.push_str(&argument.emit_default());
+    }
     raw.push_str(&self.body.emit());
     raw.push_str("\nend\n");
     return raw;
@@ -87,9 +108,7 @@ impl ReturnStatement {
 
 impl ContinueStatement {
   fn emit(&self) -> String {
-    let mut raw = String::new();
-    raw.push_str("continue\n");
-    return raw;
+    format!("goto {}\n", CONTINUE_LABEL)
   }
 }
 
@@ -104,6 +123,7 @@ impl IfStatement {
       raw.push_str(&else_if_branch.emit());
     }
     if let Some(else_body) = &self.else_body {
+      raw.push_str("else\n");
       raw.push_str(&else_body.emit());
     }
     raw.push_str("\nend\n");
@@ -129,7 +149,7 @@ impl WhileStatement {
     raw.push_str("while ");
     raw.push_str(&self.condition.emit());
     raw.push_str(" do\n");
-    raw.push_str(&self.body.emit());
+    raw.push_str(&emit_loop_body(&self.body.emit(), &self.range, &[]));
     raw.push_str("\nend\n");
     return raw;
   }
@@ -139,7 +159,12 @@ impl RepeatStatement {
   fn emit(&self) -> String {
     let mut raw = String::new();
     raw.push_str("repeat\n");
-    raw.push_str(&self.body.emit());
+    let body = self.body.emit();
+    if body.contains(CONTINUE_LABEL) {
+      raw.push_str(&self.emit_body_with_continue());
+    } else {
+      raw.push_str(&body);
+    }
     raw.push_str("until ");
     raw.push_str(&self.condition.emit());
     raw.push_str("\n");
@@ -159,11 +184,155 @@ impl ForStatement {
       raw.push_str(&step.emit());
     }
     raw.push_str(" do\n");
-    raw.push_str(&self.body.emit());
+    raw.push_str(&emit_loop_body(&self.body.emit(), &self.range, &[]));
     raw.push_str("\nend\n");
     return raw;
   }
 }
+impl ForInStatement {
+  fn emit(&self) -> String {
+    let mut raw = String::new();
+    raw.push_str("for ");
+    for (index, variable) in self.variables.iter().enumerate() {
+      if index > 0 {
+        raw.push_str(", ");
+      }
+      raw.push_str(&variable.emit());
+    }
+    raw.push_str(" in ");
+    for (index, iterator) in self.iterators.iter().enumerate() {
+      if index > 0 {
+        raw.push_str(", ");
+      }
+      raw.push_str(&iterator.emit());
+    }
+    raw.push_str(" do\n");
+    raw.push_str(&emit_loop_body(&self.body.emit(), &self.range, &[]));
+    raw.push_str("\nend\n");
+    raw
+  }
+}
+
+impl RepeatStatement {
+  // `until` sees the body locals, but the label can't be inside their scope: the locals the
+  // condition uses are copied to fresh names declared before the body block, whenever it ends or
+  // continues, and declared again after the label. the body keeps its own locals, so their
+  // initializers still read what they read in the source
+  fn emit_body_with_continue(&self) -> String {
+    let mut used = HashSet::new();
+    collect_identifiers(&self.condition, &mut used);
+
+    let statements = match self.body.as_ref() {
+      Statement::Block(block) => block.statements.iter().collect(),
+      body => vec![body],
+    };
+    let continue_goto = format!("goto {}", CONTINUE_LABEL);
+    let mut copied = vec![];
+    let mut body = String::new();
+    for statement in &statements {
+      // a `continue` copies the locals declared before it
+      let copy_and_continue = format!("{}{}", emit_copies(&copied), continue_goto);
+      body.push_str(&statement.emit().replace(&continue_goto, &copy_and_continue));
+      if !body.ends_with('\n') {
+        body.push('\n');
+      }
+      if let Statement::Local(local) = statement {
+        for variable in &local.variables {
+          let name = variable.emit();
+          if used.contains(&name) && !copied.contains(&name) {
+            copied.push(name);
+          }
+        }
+      }
+    }
+    // nothing can follow a final `return`
+    if !matches!(statements.last(), Some(Statement::Return(_))) {
+      body.push_str(&emit_copies(&copied));
+    }
+    emit_loop_body(&body, &self.range, &copied)
+  }
+}
+
+// the name a body local of `repeat` is kept under while the loop jumps to its `until`
+fn copied_name(name: &str) -> String {
+  format!("__stella_{}", name)
+}
+
+// `__stella_a, __stella_b = a, b`
+fn emit_copies(names: &[String]) -> String {
+  if names.is_empty() {
+    return String::new();
+  }
+  let copied_names = names.iter().map(|name| copied_name(name)).collect::<Vec<_>>();
+  format!("{} = {}\n", copied_names.join(", "), names.join(", "))
+}
+
+fn emit_loop_body(body: &str, loop_range: &Range, copied: &[String]) -> String {
+  if !body.contains(CONTINUE_LABEL) {
+    return body.to_string();
+  }
+  let label = format!("continue_{}", loop_range.start);
+  let copied_names = copied.iter().map(|name| copied_name(name)).collect::<Vec<_>>();
+  let mut raw = String::new();
+  if !copied.is_empty() {
+    raw.push_str(&format!("local {}\n", copied_names.join(", ")));
+  }
+  // the body gets its own block, so the label is outside the scope of its locals and a final `return` stays last
+  raw.push_str("do\n");
+  raw.push_str(&body.replace(CONTINUE_LABEL, &label));
+  raw.push_str("\nend\n");
+  raw.push_str(&format!("::{}::\n", label));
+  if !copied.is_empty() {
+    raw.push_str(&format!("local {} = {}\n", copied.join(", "), copied_names.join(", ")));
+  }
+  raw
+}
+
+// names read by an expression, without looking into nested functions
+fn collect_identifiers(expression: &Expression, names: &mut HashSet<String>) {
+  match expression {
+    Expression::Identifier(identifier) => {
+      names.insert(identifier.name.clone());
+    }
+    Expression::Variable(variable) => {
+      names.insert(variable.name.lexeme().to_string());
+    }
+    Expression::Call(call) => {
+      collect_identifiers(&call.left, names);
+      collect_identifiers(&call.args, names);
+    }
+    Expression::Unary(unary) => collect_identifiers(&unary.operand, names),
+    Expression::Binary(binary) => {
+      collect_identifiers(&binary.left, names);
+      collect_identifiers(&binary.right, names);
+    }
+    Expression::Grouped(grouped) => {
+      grouped.expressions.iter().for_each(|expression| collect_identifiers(expression, names))
+    }
+    Expression::Table(table) => {
+      for field in &table.fields {
+        if let TableField::Computed(key, _) = field {
+          collect_identifiers(key, names);
+        }
+        collect_identifiers(field.get_value(), names);
+      }
+    }
+    Expression::Member(member) => collect_identifiers(&member.base, names),
+    Expression::Index(index) => {
+      collect_identifiers(&index.base, names);
+      collect_identifiers(&index.index, names);
+    }
+    Expression::Assign(assign) => {
+      assign.left.iter().chain(&assign.right).for_each(|expression| collect_identifiers(expression, names));
+    }
+    Expression::CompoundAssign(assign) => {
+      collect_identifiers(&assign.left, names);
+      collect_identifiers(&assign.right, names);
+    }
+    Expression::Literal(_) | Expression::Require(_) | Expression::Function(_) | Expression::Vararg(_) => {}
+  }
+}
+
 impl BreakStatement {
   fn emit(&self) -> String {
     let mut raw = String::new();
@@ -171,6 +340,12 @@ impl BreakStatement {
     return raw;
   }
 }
+impl LabelStatement {
+  fn emit(&self) -> String {
+    format!("::{}::\n", self.name)
+  }
+}
+
 impl GotoStatement {
   fn emit(&self) -> String {
     let mut raw = String::new();
@@ -188,6 +363,10 @@ impl BlockStatement {
     let mut raw = String::new();
     for statement in &self.statements {
       raw.push_str(&statement.emit());
+      // expression statements don't end their line
+      if !raw.ends_with('\n') {
+        raw.push('\n');
+      }
     }
     return raw;
   }
@@ -209,6 +388,77 @@ impl TypeDeclaration {
   }
 }
 
+// enums are plain tables at runtime
+impl EnumDeclaration {
+  fn emit(&self) -> String {
+    let mut raw = String::new();
+    raw.push_str("local ");
+    raw.push_str(&self.name.name);
+    raw.push_str(" = {");
+    for (index, member) in self.members.iter().enumerate() {
+      if index > 0 {
+        raw.push_str(", ");
+      }
+      raw.push_str(&member.name.name);
+      raw.push_str(" = ");
+      raw.push_str(&member.value.emit());
+    }
+    raw.push_str("}\n");
+    raw
+  }
+}
+
+// a class is the metatable of its instances, `new` creates an instance and `__stella_init` sets its
+// fields, so the constructor of a subclass can run the one of its base through `super(...)`
+impl ClassDeclaration {
+  fn emit(&self) -> String {
+    let name = &self.name.name;
+    let mut raw = String::new();
+    match &self.base {
+      Some(base) => raw.push_str(&format!("local {} = setmetatable({{}}, {{ __index = {} }})\n", name, base.name)),
+      None => raw.push_str(&format!("local {} = {{}}\n", name)),
+    }
+    raw.push_str(&format!("{}.__index = {}\n", name, name));
+
+    match (self.constructor(), &self.base) {
+      (Some(constructor), base) => {
+        raw.push_str(&format!("function {}.{}(self", name, CLASS_INIT_NAME));
+        for argument in &constructor.arguments {
+          raw.push_str(", ");
+          raw.push_str(&argument.emit());
+        }
+        raw.push_str(")\n");
+        constructor.arguments.iter().for_each(|argument| raw.push_str(&argument.emit_default()));
+        if let Some(base) = base {
+          let super_call = format!("{}.{}(self, ...)", base.name, CLASS_INIT_NAME);
+          raw.push_str(&format!("local function super(...)\n{}\nend\n", super_call));
+        }
+        raw.push_str(&constructor.body.emit());
+        raw.push_str("\nend\n");
+      }
+      // without a constructor, the one of the base runs with the same arguments
+      (None, Some(base)) => {
+        let base_call = format!("{}.{}(self, ...)", base.name, CLASS_INIT_NAME);
+        raw.push_str(&format!("function {}.{}(self, ...)\n{}\nend\n", name, CLASS_INIT_NAME, base_call))
+      }
+      (None, None) => raw.push_str(&format!("function {}.{}(self)\nend\n", name, CLASS_INIT_NAME)),
+    }
+    raw.push_str(&format!("function {}.new(...)\nlocal self = setmetatable({{}}, {})\n", name, name));
+    raw.push_str(&format!("{}.{}(self, ...)\nreturn self\nend\n", name, CLASS_INIT_NAME));
+
+    for method in self.methods.iter().filter(|method| method.name.lexeme() != "new") {
+      raw.push_str(&format!("function {}:{}(", name, method.name.lexeme()));
+      let arguments = method.arguments.iter().map(|argument| argument.emit()).collect::<Vec<_>>();
+      raw.push_str(&arguments.join(", "));
+      raw.push_str(")\n");
+      method.arguments.iter().for_each(|argument| raw.push_str(&argument.emit_default()));
+      raw.push_str(&method.body.emit());
+      raw.push_str("\nend\n");
+    }
+    raw
+  }
+}
+
 impl Variable {
   fn emit(&self) -> String {
     let mut raw = String::new();
@@ -216,6 +466,16 @@ impl Variable {
     // don't emit type's
     return raw;
   }
+
+  // `x: number = 1` falls back to its default when the caller leaves it out
+  fn emit_default(&self) -> String {
+    match &self.default {
+      Some(default) => {
+        format!("if {} == nil then\n{} = {}\nend\n", self.name.lexeme(), self.name.lexeme(), default.emit())
+      }
+      None => String::new(),
+    }
+  }
 }
 
 impl LiteralExpression {
@@ -237,7 +497,23 @@ impl NumberLiteral {
 
 impl StringLiteral {
   fn emit(&self) -> String {
-    format!("\"{}\"", self.value)
+    if let Some(source) = &self.source {
+      return source.clone();
+    }
+    let mut raw = String::from("\"");
+    for character in self.value.chars() {
+      match character {
+        '"' => raw.push_str("\\\""),
+        '\\' => raw.push_str("\\\\"),
+        '\n' => raw.push_str("\\n"),
+        '\r' => raw.push_str("\\r"),
+        '\t' => raw.push_str("\\t"),
+        c if c.is_ascii_control() => raw.push_str(&format!("\\{:03}", c as u32)),
+        c => raw.push(c),
+      }
+    }
+    raw.push('"');
+    raw
   }
 }
 
@@ -259,7 +535,10 @@ impl NilLiteral {
 
 impl UnaryOperator {
   fn emit(&self) -> String {
-    return self.to_str().to_string();
+    match self {
+      UnaryOperator::Not => "not ".to_string(),
+      _ => self.to_str().to_string(),
+    }
   }
 }
 
@@ -284,7 +563,9 @@ impl Expression {
       Expression::Member(member) => member.emit(),
       Expression::Index(index) => index.emit(),
       Expression::Assign(assign) => assign.emit(),
+      Expression::CompoundAssign(assign) => assign.emit(),
       Expression::Variable(variable) => variable.emit(),
+      Expression::Vararg(_) => "...".to_string(),
     }
   }
 }
@@ -308,6 +589,42 @@ impl AssignExpression {
     return raw;
   }
 }
+impl CompoundAssignExpression {
+  fn emit(&self) -> String {
+    // parts of the left side that could have side effects are stored in locals, so they run once
+    let mut locals = vec![];
+    let target = match self.left.as_ref() {
+      Expression::Member(member) => {
+        let base = emit_once(&member.base, "__stella_base", &mut locals);
+        format!("{}.{}", base, member.identifier.emit())
+      }
+      Expression::Index(index) => {
+        let base = emit_once(&index.base, "__stella_base", &mut locals);
+        let key = emit_once(&index.index, "__stella_key", &mut locals);
+        format!("{}[{}]", base, key)
+      }
+      left => left.emit(),
+    };
+    let assign = format!("{} = {} {} {}", target, target, self.operator.emit(), self.right.emit());
+    if locals.is_empty() {
+      return assign;
+    }
+    let (names, values): (Vec<_>, Vec<_>) = locals.into_iter().unzip();
+    format!("do\nlocal {} = {}\n{}\nend", names.join(", "), values.join(", "), assign)
+  }
+}
+
+// identifiers and literals can be emitted twice, anything else is bound to a local first
+fn emit_once(expression: &Expression, name: &str, locals: &mut Vec<(String, String)>) -> String {
+  match expression {
+    Expression::Identifier(_) | Expression::Literal(_) => expression.emit(),
+    _ => {
+      locals.push((name.to_string(), expression.emit()));
+      name.to_string()
+    }
+  }
+}
+
 impl RequireExpression {
   fn emit(&self) -> String {
     let mut raw = String::new();
@@ -352,7 +669,12 @@ impl UnaryExpression {
   fn emit(&self) -> String {
     let mut raw = String::new();
     raw.push_str(&self.operator.emit());
-    raw.push_str(&self.operand.emit());
+    let operand = self.operand.emit();
+    // `- -x` must not become the comment `--x`
+    if operand.starts_with('-') {
+      raw.push(' ');
+    }
+    raw.push_str(&operand);
     return raw;
   }
 }
@@ -361,7 +683,7 @@ impl MemberExpression {
   fn emit(&self) -> String {
     let mut raw = String::new();
     raw.push_str(&self.base.emit());
-    raw.push_str(".");
+    raw.push_str(if self.method { ":" } else { "." });
     raw.push_str(&self.identifier.emit());
     return raw;
   }
@@ -381,15 +703,23 @@ impl TableExpression {
   fn emit(&self) -> String {
     let mut raw = String::new();
     raw.push_str("{");
-    for (index, value) in self.values.iter().enumerate() {
+    for (index, field) in self.fields.iter().enumerate() {
       if index > 0 {
         raw.push_str(", ");
       }
-      raw.push_str(&value.0.emit());
-      if let Some(value) = &value.1 {
-        raw.push_str(" = ");
-        raw.push_str(&value.emit());
+      match field {
+        TableField::Positional(_) => {}
+        TableField::Named(name, _) => {
+          raw.push_str(&name.name);
+          raw.push_str(" = ");
+        }
+        TableField::Computed(key, _) => {
+          raw.push('[');
+          raw.push_str(&key.emit());
+          raw.push_str("] = ");
+        }
       }
+      raw.push_str(&field.get_value().emit());
     }
     raw.push_str("}");
     return raw;
@@ -409,6 +739,9 @@ impl FunctionExpression {
     }
     raw.push_str(")");
     raw.push_str("\n");
+    for argument in &self.arguments {
+      raw.push_str(&argument.emit_default());
+    }
     raw.push_str(&self.body.emit());
     raw.push_str("\nend\n");
     return raw;
@@ -421,7 +754,9 @@ impl BinaryExpression {
     // todo: fix this...
     raw.push_str("(");
     raw.push_str(&self.left.emit());
+    raw.push(' ');
     raw.push_str(&self.operator.emit());
+    raw.push(' ');
     raw.push_str(&self.right.emit());
     raw.push_str(")");
     return raw;
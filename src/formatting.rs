@@ -6,8 +6,8 @@ use crate::{
     tokens::TokenKind,
   },
   types::{
-    FunctionType, GenericCallType, GenericType, GroupType, IdentifierType, OptionType, TableType, Type, UnionType,
-    VariadicType,
+    FunctionType, GenericCallType, GenericType, GroupType, IdentifierType, IntersectionType, LiteralType, OptionType,
+    OverloadType, TableType, Type, UnionType, VariadicType,
   },
 };
 
@@ -19,15 +19,20 @@ impl fmt::Display for Type {
       Type::Boolean => write!(f, "boolean"),
       Type::Nil => write!(f, "nil"),
       Type::Unknown => write!(f, "unknown"),
+      Type::Any => write!(f, "any"),
+      Type::Never => write!(f, "never"),
       Type::Table(table) => write!(f, "{}", table),
       Type::Function(function) => write!(f, "{}", function),
+      Type::Overload(overload) => write!(f, "{}", overload),
       Type::Generic(generic) => write!(f, "{}", generic),
       Type::Union(union) => write!(f, "{}", union),
+      Type::Intersection(intersection) => write!(f, "{}", intersection),
       Type::Option(option) => write!(f, "{}", option),
       Type::Alias(alias) => write!(f, "{}", alias.name),
       Type::Group(group) => write!(f, "{}", group),
       Type::GenericCall(generic_call) => write!(f, "{}", generic_call),
       Type::Variadic(variadic) => write!(f, "{}", variadic),
+      Type::Literal(literal) => write!(f, "{}", literal),
     }
   }
 }
@@ -62,6 +67,16 @@ impl fmt::Display for VariadicType {
   }
 }
 
+impl fmt::Display for LiteralType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      LiteralType::String(string) => write!(f, "\"{}\"", string),
+      LiteralType::Number(number) => write!(f, "{}", number),
+      LiteralType::Boolean(boolean) => write!(f, "{}", boolean),
+    }
+  }
+}
+
 impl fmt::Display for UnionType {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     let types_str = self.types.iter().map(Type::to_string).collect::<Vec<_>>().join(", ");
@@ -77,29 +92,59 @@ impl fmt::Display for OptionType {
 
 impl fmt::Display for TableType {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    let array_str = self
-      .array
+    let array_str = self.array.as_ref().map(|array| format!("<{}>", array)).unwrap_or_default();
+
+    let tuple_str = self
+      .tuple
       .as_ref()
-      .map(|array| format!("<{}>", array.iter().map(Type::to_string).collect::<Vec<_>>().join(", ")))
-      .unwrap_or_else(String::new);
+      .map(|tuple| format!("<({})>", tuple.iter().map(Type::to_string).collect::<Vec<_>>().join(", ")))
+      .unwrap_or_default();
 
     let map_str = self
       .map
       .as_ref()
-      .map(|map| format!("<{}>", map.iter().map(|(k, v)| format!("{}: {}", k, v)).collect::<Vec<_>>().join(", ")))
+      .map(|map| format!("<{}>", map.iter().map(|(k, v)| self.format_field(k, v)).collect::<Vec<_>>().join(", ")))
       .unwrap_or_else(String::new);
 
-    write!(f, "table{}{}", array_str, map_str)
+    let index_str = self.index.as_ref().map(|index| format!("<[{}]: {}>", index.key, index.value)).unwrap_or_default();
+
+    write!(f, "table{}{}{}{}", array_str, tuple_str, map_str, index_str)
+  }
+}
+
+impl TableType {
+  // `readonly id: number`, `age?: number`
+  fn format_field(&self, key: &str, value: &Type) -> String {
+    let readonly = if self.is_readonly(key) { "readonly " } else { "" };
+    let optional = if self.is_optional(key) { "?" } else { "" };
+    format!("{}{}{}: {}", readonly, key, optional, value)
   }
 }
 
 impl fmt::Display for FunctionType {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     let params_str = self.params.iter().map(Type::to_string).collect::<Vec<_>>().join(", ");
+    if !self.generics.is_empty() {
+      return write!(f, "function<{}>({}): {}", self.generics.join(", "), params_str, self.return_type);
+    }
     write!(f, "function({}): {}", params_str, self.return_type)
   }
 }
 
+impl fmt::Display for IntersectionType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let types_str = self.types.iter().map(Type::to_string).collect::<Vec<_>>().join(" & ");
+    write!(f, "{}", types_str)
+  }
+}
+
+impl fmt::Display for OverloadType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let functions_str = self.functions.iter().map(FunctionType::to_string).collect::<Vec<_>>().join(" & ");
+    write!(f, "{}", functions_str)
+  }
+}
+
 impl fmt::Display for GenericType {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     let types_str = self.variables.join(", ");
@@ -129,6 +174,12 @@ impl fmt::Display for BinaryOperator {
       BinaryOperator::GreaterThanOrEqual => write!(f, ">="),
       BinaryOperator::DoubleDot => write!(f, ".."),
       BinaryOperator::DoubleSlash => write!(f, "//"),
+      BinaryOperator::Power => write!(f, "^"),
+      BinaryOperator::BitwiseAnd => write!(f, "&"),
+      BinaryOperator::BitwiseOr => write!(f, "|"),
+      BinaryOperator::BitwiseXor => write!(f, "~"),
+      BinaryOperator::ShiftLeft => write!(f, "<<"),
+      BinaryOperator::ShiftRight => write!(f, ">>"),
     }
   }
 }
@@ -139,6 +190,7 @@ impl fmt::Display for UnaryOperator {
       UnaryOperator::Negate => write!(f, "-"),
       UnaryOperator::Not => write!(f, "not"),
       UnaryOperator::Hash => write!(f, "#"),
+      UnaryOperator::BitwiseNot => write!(f, "~"),
     }
   }
 }
@@ -168,6 +220,7 @@ impl fmt::Display for TokenKind {
       TokenKind::Until => write!(f, "until"),
       TokenKind::Return => write!(f, "return"),
       TokenKind::Break => write!(f, "break"),
+      TokenKind::Goto => write!(f, "goto"),
       TokenKind::True => write!(f, "true"),
       TokenKind::False => write!(f, "false"),
       TokenKind::Nil => write!(f, "nil"),
@@ -179,6 +232,7 @@ impl fmt::Display for TokenKind {
       TokenKind::MinusAssign => write!(f, "-="),
       TokenKind::StarAssign => write!(f, "*="),
       TokenKind::SlashAssign => write!(f, "/="),
+      TokenKind::DoubleDotAssign => write!(f, "..="),
       TokenKind::NotEqual => write!(f, "~="),
       TokenKind::LessEqual => write!(f, "<="),
       TokenKind::GreaterEqual => write!(f, ">="),
@@ -190,6 +244,7 @@ impl fmt::Display for TokenKind {
       TokenKind::RightBrace => write!(f, "{}", "}"),
       TokenKind::LeftBracket => write!(f, "["),
       TokenKind::RightBracket => write!(f, "]"),
+      TokenKind::Question => write!(f, "?"),
       TokenKind::Comma => write!(f, ","),
       TokenKind::Semicolon => write!(f, ";"),
       TokenKind::Colon => write!(f, ":"),
@@ -204,6 +259,11 @@ impl fmt::Display for TokenKind {
       TokenKind::DoubleSlash => write!(f, "//"),
       TokenKind::Not => write!(f, "not"),
       TokenKind::Percent => write!(f, "%"),
+      TokenKind::Caret => write!(f, "^"),
+      TokenKind::Ampersand => write!(f, "&"),
+      TokenKind::Pipe => write!(f, "|"),
+      TokenKind::ShiftLeft => write!(f, "<<"),
+      TokenKind::ShiftRight => write!(f, ">>"),
       TokenKind::Equal => write!(f, "=="),
       TokenKind::Less => write!(f, "<"),
       TokenKind::Greater => write!(f, ">"),
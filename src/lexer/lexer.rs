@@ -1,8 +1,8 @@
 use crate::ast::tokens::{Token, TokenKind};
-use crate::diagnostics::report::report_and_exit;
-use crate::utils::match_number;
+use crate::diagnostics::{Diagnostic, DiagnosticLevel};
 use crate::utils::range::Range;
 
+#[derive(Clone)]
 pub struct Lexer<'a> {
   raw: &'a str,
   column: usize,
@@ -10,12 +10,26 @@ pub struct Lexer<'a> {
   cursor: usize,
   range_start: usize,
   peeked_token: Option<Token>,
+  last_range: Range,
+  #[allow(dead_code)]
   pub file_name: &'a str,
+  pub diagnostics: Vec<Diagnostic>,
 }
 
 impl<'a> Lexer<'a> {
   pub fn new(raw: &'a str, file_name: &'a str) -> Lexer<'a> {
-    Lexer { raw, column: 0, range_start: 0, line: 1, cursor: 0, peeked_token: None, file_name }
+    let last_range = Range::new();
+    Lexer {
+      raw,
+      column: 0,
+      range_start: 0,
+      line: 1,
+      cursor: 0,
+      peeked_token: None,
+      last_range,
+      file_name,
+      diagnostics: vec![],
+    }
   }
 
   pub fn peek_token(&mut self) -> Token {
@@ -25,11 +39,35 @@ impl<'a> Lexer<'a> {
     self.peeked_token.clone().unwrap()
   }
 
-  pub fn next_token(&mut self) -> Token {
-    if let Some(token) = self.peeked_token.take() {
-      return token;
+  // the token after the peeked one, skipping comments; reading ahead never changes the lexer state
+  pub fn peek_second_token(&mut self) -> Token {
+    self.peek_token();
+    let mut lookahead = self.clone();
+    lookahead.next_token();
+    loop {
+      let token = lookahead.next_token();
+      if !token.is_comment() {
+        return token;
+      }
     }
-    self.read_next_token()
+  }
+
+  pub fn next_token(&mut self) -> Token {
+    let token = match self.peeked_token.take() {
+      Some(token) => token,
+      None => self.read_next_token(),
+    };
+    self.last_range = token.range.clone();
+    token
+  }
+
+  // the range of the last consumed token, used to close ranges of constructs that end in a nested one
+  pub fn source(&self, range: &Range) -> &'a str {
+    &self.raw[range.start..range.end]
+  }
+
+  pub fn last_token_range(&self) -> Range {
+    self.last_range.clone()
   }
 
   fn read_next_token(&mut self) -> Token {
@@ -46,36 +84,48 @@ impl<'a> Lexer<'a> {
       '*' => self.read_check_ahead("*=", TokenKind::Star, TokenKind::StarAssign),
       '=' => self.read_check_ahead("==", TokenKind::Assign, TokenKind::Equal),
       '~' => self.read_check_ahead("~=", TokenKind::Tilde, TokenKind::NotEqual),
-      '<' => self.read_check_ahead("<=", TokenKind::Less, TokenKind::LessEqual),
-      '>' => self.read_check_ahead(">=", TokenKind::Greater, TokenKind::GreaterEqual),
+      '<' => self.read_less(),
+      '>' => self.read_greater(),
       '(' => self.read_simple_token(TokenKind::LeftParen),
       ')' => self.read_simple_token(TokenKind::RightParen),
       '%' => self.read_simple_token(TokenKind::Percent),
+      '^' => self.read_simple_token(TokenKind::Caret),
+      '&' => self.read_simple_token(TokenKind::Ampersand),
+      '|' => self.read_simple_token(TokenKind::Pipe),
       '#' => self.read_simple_token(TokenKind::Hash),
       ',' => self.read_simple_token(TokenKind::Comma),
-      ':' => self.read_simple_token(TokenKind::Colon),
+      ':' => self.read_check_ahead("::", TokenKind::Colon, TokenKind::DoubleColon),
       ';' => self.read_simple_token(TokenKind::Semicolon),
       '{' => self.read_simple_token(TokenKind::LeftBrace),
       '}' => self.read_simple_token(TokenKind::RightBrace),
-      '[' => self.read_simple_token(TokenKind::LeftBracket),
+      '[' => self.read_long_string_or_left_bracket(),
       ']' => self.read_simple_token(TokenKind::RightBracket),
-      '"' => self.read_string(),
+      '?' => self.read_simple_token(TokenKind::Question),
+      '"' | '\'' => self.read_string(),
       '/' => self.read_slash(),
       '0'..='9' => self.read_number(),
       'a'..='z' | 'A'..='Z' | '_' => self.read_keyword_or_identifier(),
       '.' => self.read_dot(),
       _ => {
-        let mut range = self.create_range();
-        let message = format!("Invalid character '{}'", current_char);
-        report_and_exit(&message, &mut range, &self.raw, &self.file_name);
+        // skip the character and keep lexing, so the parser still sees the rest of the file
+        self.advance_one();
+        self.report_error(&format!("Invalid character '{}'", current_char));
+        self.read_next_token()
       }
     }
   }
 
   fn read_dot(&mut self) -> Token {
+    if self.peek_nth(1).is_ascii_digit() {
+      return self.read_number();
+    }
     if self.starts_with("...") {
       self.advance_many(3);
       return Token::new(TokenKind::TripleDot, self.create_range());
+    }
+    if self.starts_with("..=") {
+      self.advance_many(3);
+      Token::new(TokenKind::DoubleDotAssign, self.create_range())
     } else {
       self.read_check_ahead("..", TokenKind::Dot, TokenKind::DoubleDot)
     }
@@ -89,6 +139,22 @@ impl<'a> Lexer<'a> {
     }
   }
 
+  fn read_less(&mut self) -> Token {
+    if self.starts_with("<<") {
+      self.read_check_ahead("<<", TokenKind::ShiftLeft, TokenKind::ShiftLeft)
+    } else {
+      self.read_check_ahead("<=", TokenKind::Less, TokenKind::LessEqual)
+    }
+  }
+
+  fn read_greater(&mut self) -> Token {
+    if self.starts_with(">>") {
+      self.read_check_ahead(">>", TokenKind::ShiftRight, TokenKind::ShiftRight)
+    } else {
+      self.read_check_ahead(">=", TokenKind::Greater, TokenKind::GreaterEqual)
+    }
+  }
+
   fn read_simple_token(&mut self, kind: TokenKind) -> Token {
     self.advance_one();
     let range = self.create_range();
@@ -113,23 +179,20 @@ impl<'a> Lexer<'a> {
   }
 
   fn read_comment(&mut self) -> Token {
-    if self.starts_with("--[[") {
-      self.read_block_comment()
-    } else {
-      self.read_line_comment()
+    self.consume_expect("--");
+    match self.read_long_bracket_level() {
+      Some(level) => self.read_block_comment(level),
+      None => self.read_line_comment(),
     }
   }
 
-  fn read_block_comment(&mut self) -> Token {
-    self.consume_expect("--[[");
-    let text = self.read_until("--]]");
-    self.consume_expect_with_custom_error("--]]", "unexpected end of block comment");
+  fn read_block_comment(&mut self, level: usize) -> Token {
+    let text = self.read_long_bracket_content(level, "unfinished long comment");
     let range = self.create_range();
     Token::new_block_comment(range, text)
   }
 
   fn read_line_comment(&mut self) -> Token {
-    self.consume_expect("--");
     let text = self.read_while(|c| c != '\n');
     let range = self.create_range();
     Token::new_comment(range, text)
@@ -142,36 +205,227 @@ impl<'a> Lexer<'a> {
   }
 
   fn read_number(&mut self) -> Token {
-    let number = self.read_while(match_number);
+    let range_start = self.cursor;
+    if self.starts_with("0x") || self.starts_with("0X") {
+      self.advance_many(2);
+      self.read_numeral_digits(|c| c.is_ascii_hexdigit(), ['p', 'P']);
+    } else {
+      self.read_numeral_digits(|c| c.is_ascii_digit(), ['e', 'E']);
+    }
+    // a numeral glued to a letter, like `3x` or `0xg`, is malformed in lua
+    if self.peek_one().is_ascii_alphanumeric() || self.peek_one() == '_' {
+      self.read_while(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+      let message = format!("malformed number near '{}'", &self.raw[range_start..self.cursor]);
+      self.report_error(&message);
+    }
+    let number = self.raw[range_start..self.cursor].to_string();
     let range = self.create_range();
     Token::new_number(range, number)
   }
 
+  // digits, an optional fraction and an optional (always decimal) exponent
+  fn read_numeral_digits(&mut self, is_digit: impl Fn(char) -> bool, exponent: [char; 2]) {
+    let mut digits = self.read_while(&is_digit).len();
+    // `1..x` is a concatenation, not a fraction
+    if self.peek_one() == '.' && self.peek_nth(1) != '.' {
+      self.advance_one();
+      digits += self.read_while(&is_digit).len();
+    }
+    if digits == 0 {
+      self.report_error("malformed number, expected digits");
+    }
+    if exponent.contains(&self.peek_one()) {
+      self.advance_one();
+      if matches!(self.peek_one(), '+' | '-') {
+        self.advance_one();
+      }
+      if self.read_while(|c| c.is_ascii_digit()).is_empty() {
+        self.report_error("malformed number, expected exponent digits");
+      }
+    }
+  }
+
   fn read_string(&mut self) -> Token {
-    self.consume_expect("\"");
-    let string = self.read_while(|c| c != '"');
-    self.consume_expect_with_custom_error("\"", "unterminated string");
+    let delimiter = self.peek_one();
+    self.advance_one();
+    let mut bytes = vec![];
+    loop {
+      match self.peek_one() {
+        c if c == delimiter => {
+          self.advance_one();
+          break;
+        }
+        '\\' => self.read_escape_sequence(&mut bytes),
+        _ if self.is_end() || matches!(self.peek_one(), '\n' | '\r') => {
+          self.report_error("unfinished string");
+          break;
+        }
+        c => {
+          self.advance_one();
+          bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+        }
+      }
+    }
     let range = self.create_range();
-    Token::new_string(range, string)
+    Token::new_string(range, String::from_utf8_lossy(&bytes).into_owned())
   }
 
-  fn read_while(&mut self, mut test: impl FnMut(char) -> bool) -> String {
-    let range_start = self.cursor;
-    while !self.is_end() && test(self.peek_one()) {
+  fn read_escape_sequence(&mut self, bytes: &mut Vec<u8>) {
+    self.consume_expect("\\");
+    let escaped = self.peek_one();
+    let byte = match escaped {
+      'a' => 0x07,
+      'b' => 0x08,
+      'f' => 0x0c,
+      'n' => b'\n',
+      'r' => b'\r',
+      't' => b'\t',
+      'v' => 0x0b,
+      '\\' | '"' | '\'' => escaped as u8,
+      '\n' | '\r' => {
+        self.skip_new_line();
+        bytes.push(b'\n');
+        return;
+      }
+      'z' => {
+        self.advance_one();
+        self.read_while(|c| c.is_ascii_whitespace());
+        return;
+      }
+      'x' => {
+        self.advance_one();
+        let hex = self.read_escape_digits(2, |c| c.is_ascii_hexdigit());
+        match u8::from_str_radix(&hex, 16) {
+          Ok(byte) if hex.len() == 2 => bytes.push(byte),
+          _ => self.report_error("hexadecimal digit expected in escape sequence"),
+        }
+        return;
+      }
+      'u' => return self.read_utf8_escape(bytes),
+      '0'..='9' => {
+        let decimal = self.read_escape_digits(3, |c| c.is_ascii_digit());
+        match decimal.parse::<u8>() {
+          Ok(byte) => bytes.push(byte),
+          Err(_) => self.report_error("decimal escape too large"),
+        }
+        return;
+      }
+      // the string itself reports that it is unfinished
+      _ if self.is_end() => return,
+      _ => {
+        self.advance_one();
+        self.report_error(&format!("invalid escape sequence '\\{}'", escaped));
+        return;
+      }
+    };
+    self.advance_one();
+    bytes.push(byte);
+  }
+
+  // \u{XXX}, encoded like lua does, which allows code points up to 2^31
+  fn read_utf8_escape(&mut self, bytes: &mut Vec<u8>) {
+    self.consume_expect("u");
+    if self.peek_one() != '{' {
+      return self.report_error("missing '{' in \\u{xxxx}");
+    }
+    self.advance_one();
+    let hex = self.read_while(|c| c.is_ascii_hexdigit());
+    if hex.is_empty() {
+      return self.report_error("hexadecimal digit expected in escape sequence");
+    }
+    let code_point = match u32::from_str_radix(&hex, 16) {
+      Ok(code_point) if code_point <= 0x7FFF_FFFF => code_point,
+      _ => return self.report_error("UTF-8 value too large"),
+    };
+    if self.peek_one() != '}' {
+      return self.report_error("missing '}' in \\u{xxxx}");
+    }
+    self.advance_one();
+    bytes.extend(encode_utf8_escape(code_point));
+  }
+
+  fn read_escape_digits(&mut self, max: usize, test: impl Fn(char) -> bool) -> String {
+    let mut count = 0;
+    self.read_while(|c| {
+      count += 1;
+      count <= max && test(c)
+    })
+  }
+
+  fn read_long_string_or_left_bracket(&mut self) -> Token {
+    match self.read_long_bracket_level() {
+      Some(level) => {
+        let string = self.read_long_bracket_content(level, "unfinished long string");
+        let range = self.create_range();
+        Token::new_string(range, string)
+      }
+      None => {
+        if self.starts_with("[=") {
+          self.report_error("invalid long string delimiter");
+        }
+        self.read_simple_token(TokenKind::LeftBracket)
+      }
+    }
+  }
+
+  // consumes `[[` or `[==[` and returns the number of `=`, without consuming anything else
+  fn read_long_bracket_level(&mut self) -> Option<usize> {
+    let rest = self.raw[self.cursor..].strip_prefix('[')?;
+    let level = rest.chars().take_while(|c| *c == '=').count();
+    if !rest[level..].starts_with('[') {
+      return None;
+    }
+    self.advance_many(level + 2);
+    Some(level)
+  }
+
+  fn read_long_bracket_content(&mut self, level: usize, error_message: &str) -> String {
+    let close = format!("]{}]", "=".repeat(level));
+    // a line break right after the opening bracket is not part of the content
+    if matches!(self.peek_one(), '\n' | '\r') {
+      self.skip_new_line();
+    }
+    let mut content = String::new();
+    while !self.starts_with(&close) {
+      match self.peek_one() {
+        _ if self.is_end() => {
+          self.report_error(error_message);
+          return content;
+        }
+        '\n' | '\r' => {
+          self.skip_new_line();
+          content.push('\n');
+        }
+        c => {
+          self.advance_one();
+          content.push(c);
+        }
+      }
+    }
+    self.advance_many(close.len());
+    content
+  }
+
+  // `\n`, `\r`, `\r\n` and `\n\r` all count as a single line break
+  fn skip_new_line(&mut self) {
+    let first = self.peek_one();
+    self.advance_one();
+    let second = self.peek_one();
+    if matches!(second, '\n' | '\r') && second != first {
       self.advance_one();
     }
-    self.raw[range_start..self.cursor].to_string()
   }
 
-  fn read_until(&mut self, end: &str) -> String {
+  // lexical errors don't stop the lexer, the caller decides how to recover
+  fn report_error(&mut self, message: &str) {
+    let range = Range { start: self.range_start, end: self.cursor };
+    self.diagnostics.push(Diagnostic::new(DiagnosticLevel::Error, message.to_string(), Some(range)));
+  }
+
+  fn read_while(&mut self, mut test: impl FnMut(char) -> bool) -> String {
     let range_start = self.cursor;
-    while !self.is_end() && !self.starts_with(end) {
-      let c = self.peek_one();
-      if c == '\n' {
-        self.advance_new_line();
-      } else {
-        self.advance_one();
-      }
+    while !self.is_end() && test(self.peek_one()) {
+      self.advance_one();
     }
     self.raw[range_start..self.cursor].to_string()
   }
@@ -198,22 +452,10 @@ impl<'a> Lexer<'a> {
     self.range_start = self.cursor;
   }
 
+  // only called after peeking, so `text` is always there
   fn consume_expect(&mut self, text: &str) {
-    if self.starts_with(text) {
-      self.advance_many(text.len());
-    } else {
-      let range = self.create_range();
-      panic!("expected '{}' at {:?}", text, range);
-    }
-  }
-
-  fn consume_expect_with_custom_error(&mut self, text: &str, error_message: &str) {
-    if self.starts_with(text) {
-      self.advance_many(text.len());
-    } else {
-      let location = self.create_range();
-      panic!("{} at {:?}", error_message, location);
-    }
+    debug_assert!(self.starts_with(text), "expected '{}'", text);
+    self.advance_many(text.len());
   }
 
   fn is_end(&self) -> bool {
@@ -224,6 +466,10 @@ impl<'a> Lexer<'a> {
     self.raw[self.cursor..].chars().next().unwrap_or('\0')
   }
 
+  fn peek_nth(&self, n: usize) -> char {
+    self.raw[self.cursor..].chars().nth(n).unwrap_or('\0')
+  }
+
   fn advance_many(&mut self, count: usize) {
     for _ in 0..count {
       self.advance_one();
@@ -250,3 +496,21 @@ impl<'a> Lexer<'a> {
     }
   }
 }
+
+// same scheme as lua's `luaO_utf8esc`, sequences can be up to 6 bytes long
+fn encode_utf8_escape(code_point: u32) -> Vec<u8> {
+  if code_point < 0x80 {
+    return vec![code_point as u8];
+  }
+  let mut bytes = vec![];
+  let mut code_point = code_point;
+  let mut first_byte_max = 0x3f;
+  while code_point > first_byte_max {
+    bytes.push(0x80 | (code_point & 0x3f) as u8);
+    code_point >>= 6;
+    first_byte_max >>= 1;
+  }
+  bytes.push(((!first_byte_max << 1) | code_point) as u8);
+  bytes.reverse();
+  bytes
+}
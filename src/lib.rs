@@ -2,6 +2,7 @@ pub mod ast;
 pub mod checker;
 pub mod cli;
 pub mod context;
+pub mod cst;
 pub mod diagnostics;
 pub mod emit;
 pub mod formatting;
@@ -18,7 +18,9 @@ use std::{
   path::Path,
 };
 
+use ast::ast::Program;
 use checker::Checker;
+use diagnostics::DiagnosticManager;
 use parser::parser::Parser;
 use rlua::Lua;
 use stella_checker::utils::highlight_text_with_red;
@@ -31,29 +33,30 @@ fn main() {
   match matches.subcommand() {
     Some(("check", matches)) => {
       let path_name = matches.get_one::<String>("file").unwrap();
-      run_check(path_name);
+      run_check(path_name, matches.get_flag("unchecked-index"));
     }
 
     Some(("compile", matches)) => {
       let path_name = matches.get_one::<String>("file").unwrap();
-      let result = run_compile(path_name);
+      let result = run_compile(path_name, matches.get_flag("unchecked-index"));
       if result.is_err() {
         std::process::exit(1);
       }
     }
     Some(("run", matches)) => {
       let path_name = matches.get_one::<String>("file").unwrap();
-      run(path_name);
+      run(path_name, matches.get_flag("unchecked-index"));
     }
     _ => panic!("No subcommand provided."),
   }
 }
 
-fn run_check(path_name: &str) {
+fn run_check(path_name: &str, unchecked_index: bool) {
   let raw = std::fs::read_to_string(path_name).unwrap();
   let mut parser = Parser::new(&raw, path_name);
-  let program = parser.parse_program();
+  let program = parse_or_exit(&mut parser, &raw, path_name);
   let mut checker = Checker::new(path_name, &raw);
+  checker.unchecked_index = unchecked_index;
   let type_result = checker.check(&program);
   if type_result.is_err() || checker.diagnostics.error_count > 0 {
     checker.diagnostics.emit_all(&raw, path_name);
@@ -63,11 +66,12 @@ fn run_check(path_name: &str) {
   println!("Result Type: {}", type_);
 }
 
-fn run_compile(path_name: &str) -> Result<(), std::io::Error> {
+fn run_compile(path_name: &str, unchecked_index: bool) -> Result<(), std::io::Error> {
   let raw = std::fs::read_to_string(path_name).unwrap();
   let mut parser = Parser::new(&raw, path_name);
-  let program = parser.parse_program();
+  let program = parse_or_exit(&mut parser, &raw, path_name);
   let mut checker = Checker::new(path_name, &raw);
+  checker.unchecked_index = unchecked_index;
   let type_result = checker.check(&program);
 
   if type_result.is_err() || checker.diagnostics.error_count > 0 {
@@ -87,11 +91,12 @@ fn run_compile(path_name: &str) -> Result<(), std::io::Error> {
   Ok(())
 }
 
-fn run(path_name: &str) {
+fn run(path_name: &str, unchecked_index: bool) {
   let raw = std::fs::read_to_string(path_name).unwrap();
   let mut parser = Parser::new(&raw, path_name);
-  let program = parser.parse_program();
+  let program = parse_or_exit(&mut parser, &raw, path_name);
   let mut checker = Checker::new(path_name, &raw);
+  checker.unchecked_index = unchecked_index;
   let type_result = checker.check(&program);
 
   if type_result.is_err() || checker.diagnostics.error_count > 0 {
@@ -113,6 +118,17 @@ fn run(path_name: &str) {
   }
 }
 
+// reports every syntax error of the file at once, there is nothing to check until they are fixed
+fn parse_or_exit(parser: &mut Parser, raw: &str, path_name: &str) -> Program {
+  let (program, diagnostics) = parser.parse_program();
+  if !diagnostics.is_empty() {
+    let mut manager = DiagnosticManager::new();
+    diagnostics.into_iter().for_each(|diagnostic| manager.add(diagnostic));
+    manager.emit_all(raw, path_name);
+  }
+  program
+}
+
 fn create_output_directory(path_name: &str) -> String {
   let output_path = std::path::Path::new(OUTPUT_DIRECTORY);
   if !output_path.exists() {
@@ -1,72 +1,146 @@
 // #![allow(dead_code)]
 
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet};
 
 use super::precedence::Precedence;
 use crate::ast::ast;
 use crate::ast::tokens::{Token, TokenKind};
-use crate::diagnostics::report::report_and_exit;
+use crate::diagnostics::{Diagnostic, DiagnosticLevel};
 use crate::lexer::Lexer;
-use crate::types::Type;
+use crate::types::{FunctionType, IndexType, LiteralType, TableType, Type};
 use crate::utils::range::{create_middle_range, Range};
+
+pub type ParseResult<T> = Result<T, Diagnostic>;
+
+const COMPOUND_ASSIGN_TOKENS: [TokenKind; 5] = [
+  TokenKind::PlusAssign,
+  TokenKind::MinusAssign,
+  TokenKind::StarAssign,
+  TokenKind::SlashAssign,
+  TokenKind::DoubleDotAssign,
+];
+
 pub struct Parser<'a> {
   lexer: Lexer<'a>,
-  raw: &'a str,
+  diagnostics: Vec<Diagnostic>,
 }
 
 impl<'a> Parser<'a> {
   pub fn new(raw: &'a str, file_name: &'a str) -> Self {
-    Self { lexer: Lexer::new(raw, file_name), raw }
+    Self { lexer: Lexer::new(raw, file_name), diagnostics: vec![] }
   }
 
-  pub fn parse_program(&mut self) -> ast::Program {
+  // returns everything that could be parsed, along with every syntax error found on the way
+  pub fn parse_program(&mut self) -> (ast::Program, Vec<Diagnostic>) {
     let mut program = ast::Program::new();
 
     while !self.is_end() {
-      let statement = self.parse_statement();
-      program.statements.push(statement);
+      if let Some(statement) = self.parse_statement_or_recover(&[]) {
+        program.statements.push(statement);
+      }
     }
-    program
+
+    let mut diagnostics = std::mem::take(&mut self.lexer.diagnostics);
+    diagnostics.append(&mut self.diagnostics);
+    diagnostics.sort_by_key(|diagnostic| diagnostic.range.as_ref().map(|range| range.start));
+    (program, diagnostics)
   }
 
-  fn parse_statement(&mut self) -> ast::Statement {
+  fn parse_statement_or_recover(&mut self, end_tokens: &[TokenKind]) -> Option<ast::Statement> {
+    let start = self.lexer.peek_token().range.start;
+    match self.parse_statement() {
+      Ok(statement) => Some(statement),
+      Err(diagnostic) => {
+        self.diagnostics.push(diagnostic);
+        // always make progress, even if the error is on the very first token
+        if self.lexer.peek_token().range.start == start {
+          self.consume_token();
+        }
+        self.synchronize(end_tokens);
+        None
+      }
+    }
+  }
+
+  // skips tokens until the start of the next statement, or the end of the enclosing block
+  fn synchronize(&mut self, end_tokens: &[TokenKind]) {
+    loop {
+      let token = self.lexer.peek_token();
+      match token.kind {
+        TokenKind::EOF => break,
+        _ if end_tokens.contains(&token.kind) => break,
+        TokenKind::End => {
+          self.consume_token();
+          break;
+        }
+        TokenKind::Local
+        | TokenKind::Function
+        | TokenKind::Return
+        | TokenKind::If
+        | TokenKind::While
+        | TokenKind::For
+        | TokenKind::Repeat
+        | TokenKind::Break
+        | TokenKind::Goto
+        | TokenKind::DoubleColon
+        | TokenKind::Type => break,
+        _ => {
+          self.consume_token();
+        }
+      }
+    }
+  }
+
+  fn parse_statement(&mut self) -> ParseResult<ast::Statement> {
     self.skip_comments();
     if self.is_end() {
-      return ast::Statement::Empty(ast::EmptyStatement {});
+      return Ok(ast::Statement::Empty(ast::EmptyStatement {}));
     }
 
     let token = self.lexer.peek_token();
     let statement = match token.kind {
-      TokenKind::Local => self.parse_local_declaration(),
-      TokenKind::If => self.parse_if_statement(),
-      TokenKind::While => self.parse_while_statement(),
-      TokenKind::Repeat => self.parse_repeat_statement(),
-      TokenKind::For => self.parse_for_statement(),
-      TokenKind::Break => self.parse_break_statement(),
-      TokenKind::Continue => self.parse_continue_statement(),
-      TokenKind::Return => self.parse_return_statement(),
-      TokenKind::Function => self.parse_function_declaration(None),
-      TokenKind::Type => self.parse_type_declaration(),
-      _ => self.parse_expression_statement(),
+      TokenKind::Local => self.parse_local_declaration()?,
+      TokenKind::If => self.parse_if_statement()?,
+      TokenKind::While => self.parse_while_statement()?,
+      TokenKind::Repeat => self.parse_repeat_statement()?,
+      TokenKind::For => self.parse_for_statement()?,
+      TokenKind::Break => self.parse_break_statement()?,
+      TokenKind::Goto => self.parse_goto_statement()?,
+      TokenKind::DoubleColon => self.parse_label_statement()?,
+      TokenKind::Continue => self.parse_continue_statement()?,
+      TokenKind::Return => self.parse_return_statement()?,
+      TokenKind::Function => self.parse_function_declaration(None)?,
+      // `type(x)` is a call of the `type` function
+      TokenKind::Type if matches!(self.lexer.peek_second_token().kind, TokenKind::Identifier(_)) => {
+        self.parse_type_declaration()?
+      }
+      TokenKind::Enum => self.parse_enum_declaration()?,
+      // `class` alone is still a name
+      TokenKind::Identifier(ref name)
+        if name == "class" && matches!(self.lexer.peek_second_token().kind, TokenKind::Identifier(_)) =>
+      {
+        self.parse_class_declaration()?
+      }
+      _ => self.parse_expression_statement()?,
     };
     self.match_token_and_consume(TokenKind::Semicolon);
-    statement
+    Ok(statement)
   }
 
-  fn parse_local_declaration(&mut self) -> ast::Statement {
-    let local = self.consume_expect_token(TokenKind::Local);
+  fn parse_local_declaration(&mut self) -> ParseResult<ast::Statement> {
+    let local = self.consume_expect_token(TokenKind::Local)?;
     if self.match_token(&TokenKind::Function) {
       return self.parse_function_declaration(Some(local.range));
     }
     self.parse_local_variable(local.range)
   }
 
-  fn parse_local_variable(&mut self, local_range: Range) -> ast::Statement {
-    let mut variables = vec![self.parse_variable()];
+  fn parse_local_variable(&mut self, local_range: Range) -> ParseResult<ast::Statement> {
+    let mut variables = vec![self.parse_variable()?];
     let mut end_range = variables.first().unwrap().get_range();
 
     while self.match_token_and_consume(TokenKind::Comma).is_some() {
-      let variable = self.parse_variable();
+      let variable = self.parse_variable()?;
       if !self.match_token(&TokenKind::Comma) {
         end_range = variable.get_range();
       }
@@ -76,12 +150,12 @@ impl<'a> Parser<'a> {
     let mut initializer = vec![];
 
     if self.match_token_and_consume(TokenKind::Assign).is_some() {
-      initializer.push(self.parse_expression());
+      initializer.push(self.parse_expression()?);
     }
 
     while self.match_token(&TokenKind::Comma) {
-      self.consume_expect_token(TokenKind::Comma);
-      let expression = self.parse_expression();
+      self.consume_expect_token(TokenKind::Comma)?;
+      let expression = self.parse_expression()?;
 
       if !self.match_token(&TokenKind::Comma) {
         end_range = expression.get_range();
@@ -94,102 +168,254 @@ impl<'a> Parser<'a> {
 
     let local = ast::LocalStatement::new(variables, initializer, range);
 
-    return ast::Statement::Local(local);
+    return Ok(ast::Statement::Local(local));
   }
 
-  fn parse_variables(&mut self) -> Vec<ast::Variable> {
+  fn parse_variables(&mut self) -> ParseResult<Vec<ast::Variable>> {
     let mut variables = vec![];
     let peeked = self.lexer.peek_token();
 
-    if !peeked.is_identifier() {
-      return variables;
+    if !peeked.is_identifier() && !peeked.is_triple_dot() {
+      return Ok(variables);
     }
 
-    variables.push(self.parse_variable());
+    let mut after_optional = false;
+    variables.push(self.parse_ordered_parameter(&mut after_optional)?);
 
-    while self.match_token_and_consume(TokenKind::Comma).is_some() {
-      variables.push(self.parse_variable());
+    // `...` is always the last parameter
+    while !variables.last().unwrap().name.is_triple_dot() && self.match_token_and_consume(TokenKind::Comma).is_some() {
+      variables.push(self.parse_ordered_parameter(&mut after_optional)?);
     }
 
-    variables
+    Ok(variables)
   }
-  fn parse_variable(&mut self) -> ast::Variable {
+  fn parse_variable(&mut self) -> ParseResult<ast::Variable> {
     let name = self.lexer.next_token();
 
-    if !name.is_identifier() {
-      self.report_unexpected_token(name);
+    if !name.is_identifier() && !name.is_triple_dot() {
+      return Err(self.create_unexpected_token(name));
     }
 
     let ty = if self.match_token_and_consume(TokenKind::Colon).is_some() {
-      Some(self.parse_type(true))
+      Some(self.parse_type(true)?)
     } else {
       None
     };
 
-    ast::Variable::new(name, ty)
+    Ok(ast::Variable::new(name, ty))
+  }
+
+  // `a?: number, b: number` can't leave out `a` without leaving out `b`
+  fn parse_ordered_parameter(&mut self, after_optional: &mut bool) -> ParseResult<ast::Variable> {
+    let marked = matches!(self.lexer.peek_second_token().kind, TokenKind::Question);
+    let parameter = self.parse_parameter()?;
+    let optional = marked || parameter.default.is_some();
+    if *after_optional && !optional && !parameter.name.is_triple_dot() {
+      return Err(self.create_required_after_optional(parameter.name.clone()));
+    }
+    *after_optional |= optional;
+    Ok(parameter)
   }
 
-  fn parse_type_declaration(&mut self) -> ast::Statement {
-    let range = self.consume_expect_token(TokenKind::Type).range.clone();
+  // `x?: number` can be left out, `x: number = 1` falls back to its default
+  fn parse_parameter(&mut self) -> ParseResult<ast::Variable> {
+    if !matches!(self.lexer.peek_second_token().kind, TokenKind::Question) {
+      let mut parameter = self.parse_variable()?;
+      if self.match_token_and_consume(TokenKind::Assign).is_some() {
+        parameter.default = Some(Box::new(self.parse_expression()?));
+      }
+      return Ok(parameter);
+    }
     let name = self.consume_token();
-    let generics = self.parse_generic_type_names();
-    self.consume_expect_token(TokenKind::Assign);
-    let initializer = self.parse_type(false);
-    ast::Statement::TypeDeclaration(ast::TypeDeclaration::new(name, generics, initializer, range))
+    if !name.is_identifier() {
+      return Err(self.create_unexpected_token(name));
+    }
+    self.consume_expect_token(TokenKind::Question)?;
+    let ty = if self.match_token_and_consume(TokenKind::Colon).is_some() {
+      self.parse_type(true)?
+    } else {
+      Type::Any
+    };
+    Ok(ast::Variable::new(name, Some(Type::new_option(ty))))
   }
 
-  fn parse_function_declaration(&mut self, local_range: Option<Range>) -> ast::Statement {
-    let function_keyword = self.consume_expect_token(TokenKind::Function);
+  fn parse_enum_declaration(&mut self) -> ParseResult<ast::Statement> {
+    let start_range = self.consume_expect_token(TokenKind::Enum)?.range;
+    let name = self.parse_identifier()?;
+    self.consume_expect_token(TokenKind::LeftBrace)?;
+    let mut members = vec![];
+    // `enum Color { Red, Green }` counts from 1, like arrays
+    let mut next_number = Some(1);
+    while !self.match_token(&TokenKind::RightBrace) {
+      let member_name = self.parse_identifier()?;
+      let value = if self.match_token_and_consume(TokenKind::Assign).is_some() {
+        let token = self.consume_token();
+        match token.kind {
+          TokenKind::String(string) => ast::LiteralExpression::String(self.create_string_literal(string, token.range)),
+          TokenKind::Number(number) => ast::LiteralExpression::Number(ast::NumberLiteral::new(number, token.range)),
+          _ => return Err(self.create_unexpected_token(token)),
+        }
+      } else if let Some(number) = next_number {
+        ast::LiteralExpression::Number(ast::NumberLiteral::new(number.to_string(), member_name.range.clone()))
+      } else {
+        let message = format!("enum member '{}' needs a value", member_name.name);
+        return Err(Diagnostic::new(DiagnosticLevel::Error, message, Some(member_name.range)));
+      };
+      next_number = match &value {
+        ast::LiteralExpression::Number(number) => number.value.parse::<i64>().ok().map(|number| number + 1),
+        _ => None,
+      };
+      members.push(ast::EnumMember::new(member_name, value));
+      if self.match_token_and_consume(TokenKind::Comma).is_none()
+        && self.match_token_and_consume(TokenKind::Semicolon).is_none()
+      {
+        break;
+      }
+    }
+    let end_range = self.consume_expect_token(TokenKind::RightBrace)?.range;
+    let range = create_middle_range(&start_range, &end_range);
+    Ok(ast::Statement::Enum(ast::EnumDeclaration::new(name, members, range)))
+  }
+
+  // class Dog extends Animal
+  //   breed: string
+  //   function new(name: string, breed: string) ... end
+  // end
+  fn parse_class_declaration(&mut self) -> ParseResult<ast::Statement> {
+    let start_range = self.consume_token().range;
+    let name = self.parse_identifier()?;
+    let base = match self.match_token_and_consume(TokenKind::Identifier("extends".to_string())) {
+      Some(_) => Some(self.parse_identifier()?),
+      None => None,
+    };
+
+    let mut fields = vec![];
+    let mut methods = vec![];
+    loop {
+      self.skip_comments();
+      if self.match_token(&TokenKind::End) || self.is_end() {
+        break;
+      }
+      if self.match_token(&TokenKind::Function) {
+        methods.push(self.parse_class_method()?);
+        continue;
+      }
+      let readonly = self.match_readonly_modifier();
+      let field_name = self.parse_identifier()?;
+      let optional = self.match_token_and_consume(TokenKind::Question).is_some();
+      self.consume_expect_token(TokenKind::Colon)?;
+      let ty = self.parse_type(false)?;
+      fields.push(ast::ClassField::new(field_name, ty, optional, readonly));
+      self.match_token_and_consume(TokenKind::Semicolon);
+    }
+
+    let end_range = self.consume_expect_token(TokenKind::End)?.range;
+    let range = create_middle_range(&start_range, &end_range);
+    Ok(ast::Statement::Class(ast::ClassDeclaration::new(name, base, fields, methods, range)))
+  }
+
+  // `function speak(): string ... end`, `self` is implicit
+  fn parse_class_method(&mut self) -> ParseResult<ast::FunctionStatement> {
+    let function_token = self.lexer.peek_token();
+    match self.parse_function_declaration(None)? {
+      ast::Statement::Function(method) if method.path.is_empty() => Ok(method),
+      _ => Err(self.create_syntax_error("expected a method name without a table path".to_string(), function_token)),
+    }
+  }
+
+  fn parse_type_declaration(&mut self) -> ParseResult<ast::Statement> {
+    let range = self.consume_expect_token(TokenKind::Type)?.range.clone();
+    let name = self.consume_token();
+    let generics = self.parse_generic_type_names()?;
+    self.consume_expect_token(TokenKind::Assign)?;
+    let initializer = self.parse_type(false)?;
+    Ok(ast::Statement::TypeDeclaration(ast::TypeDeclaration::new(name, generics, initializer, range)))
+  }
+
+  fn parse_function_declaration(&mut self, local_range: Option<Range>) -> ParseResult<ast::Statement> {
+    let function_keyword = self.consume_expect_token(TokenKind::Function)?;
 
     let local = local_range.is_some();
 
     let start_range = local_range.unwrap_or(function_keyword.range);
-    let name = self.consume_token();
+    let mut name = self.parse_function_name_segment()?;
+    let mut path = vec![];
+    let mut method = false;
+
+    // function a.b.c() ... end, function a.b:c() ... end
+    while self.match_token_and_consume(TokenKind::Dot).is_some() {
+      path.push(name);
+      name = self.parse_function_name_segment()?;
+    }
 
-    if !name.is_identifier() {
-      self.report_unexpected_token(name);
+    if self.match_token_and_consume(TokenKind::Colon).is_some() {
+      path.push(name);
+      name = self.parse_function_name_segment()?;
+      method = true;
     }
 
-    let generics = self.parse_generic_types();
+    let generics = self.parse_generic_type_names()?;
 
-    self.consume_expect_token(TokenKind::LeftParen);
+    self.consume_expect_token(TokenKind::LeftParen)?;
 
-    let parameters = self.parse_variables();
+    let parameters = self.parse_variables()?;
 
-    self.consume_expect_token(TokenKind::RightParen);
+    self.consume_expect_token(TokenKind::RightParen)?;
 
     let mut return_type = None;
     let mut return_type_range = None;
 
     if self.match_token_and_consume(TokenKind::Colon).is_some() {
       return_type_range = Some(self.lexer.peek_token().range.clone());
-      return_type = Some(self.parse_type(true));
+      return_type = Some(self.parse_type(true)?);
     }
     let body = self.parse_block_statement(&[TokenKind::End]);
-    let end_range = self.consume_expect_token(TokenKind::End).range;
+    let end_range = self.consume_expect_token(TokenKind::End)?.range;
     let range = create_middle_range(&start_range, &end_range);
-    let function =
-      ast::FunctionStatement::new(name, local, generics, parameters, return_type, body, range, return_type_range);
-    ast::Statement::new_function(function)
+    let function = ast::FunctionStatement::new(
+      name,
+      path,
+      method,
+      local,
+      generics,
+      parameters,
+      return_type,
+      body,
+      range,
+      return_type_range,
+    );
+    Ok(ast::Statement::new_function(function))
   }
 
-  fn parse_expression_statement(&mut self) -> ast::Statement {
-    let expression = self.parse_expression();
-    ast::Statement::Expression(expression)
+  fn parse_function_name_segment(&mut self) -> ParseResult<Token> {
+    let name = self.consume_token();
+    if !name.is_identifier() && !name.is_triple_dot() {
+      return Err(self.create_unexpected_token(name));
+    }
+    Ok(name)
+  }
+
+  fn parse_expression_statement(&mut self) -> ParseResult<ast::Statement> {
+    let expression = self.parse_expression()?;
+    // `a, b = 1, 2`
+    if self.match_token(&TokenKind::Comma) {
+      return Ok(ast::Statement::Expression(self.parse_assign_expression(Some(expression))?));
+    }
+    Ok(ast::Statement::Expression(expression))
   }
 
-  fn parse_if_statement(&mut self) -> ast::Statement {
-    let start_range = self.consume_expect_token(TokenKind::If).range;
-    let condition = self.parse_expression();
-    self.consume_expect_token(TokenKind::Then);
+  fn parse_if_statement(&mut self) -> ParseResult<ast::Statement> {
+    let start_range = self.consume_expect_token(TokenKind::If)?.range;
+    let condition = self.parse_expression()?;
+    self.consume_expect_token(TokenKind::Then)?;
     let then_body = self.parse_block_statement(&[TokenKind::Else, TokenKind::ElseIf, TokenKind::End]);
 
     let mut else_if: Vec<ast::ElseIfStatement> = Vec::new();
 
     while self.match_token(&TokenKind::ElseIf) {
-      let start_range = self.consume_expect_token(TokenKind::ElseIf).range;
-      let condition = self.parse_expression();
-      self.consume_expect_token(TokenKind::Then);
+      let start_range = self.consume_expect_token(TokenKind::ElseIf)?.range;
+      let condition = self.parse_expression()?;
+      self.consume_expect_token(TokenKind::Then)?;
       let body = self.parse_block_statement(&[TokenKind::Else, TokenKind::ElseIf, TokenKind::End]);
       let body_range = body.get_range();
       let branch = ast::ElseIfStatement::new(condition, body, create_middle_range(&start_range, &body_range));
@@ -201,109 +427,166 @@ impl<'a> Parser<'a> {
     } else {
       None
     };
-    let end_range = self.consume_expect_token(TokenKind::End).range;
+    let end_range = self.consume_expect_token(TokenKind::End)?.range;
     let range = create_middle_range(&start_range, &end_range);
-    ast::Statement::If(ast::IfStatement::new(condition, then_body, else_if, else_body, range))
+    Ok(ast::Statement::If(ast::IfStatement::new(condition, then_body, else_if, else_body, range)))
   }
 
-  fn parse_while_statement(&mut self) -> ast::Statement {
-    let while_token = self.consume_expect_token(TokenKind::While);
-    let condition = self.parse_expression();
-    self.consume_expect_token(TokenKind::Do);
+  fn parse_while_statement(&mut self) -> ParseResult<ast::Statement> {
+    let while_token = self.consume_expect_token(TokenKind::While)?;
+    let condition = self.parse_expression()?;
+    self.consume_expect_token(TokenKind::Do)?;
     let body = self.parse_block_statement(&[TokenKind::End]);
-    self.consume_expect_token(TokenKind::End);
-    ast::Statement::While(ast::WhileStatement::new(condition, body, while_token.range))
+    self.consume_expect_token(TokenKind::End)?;
+    Ok(ast::Statement::While(ast::WhileStatement::new(condition, body, while_token.range)))
   }
 
-  fn parse_repeat_statement(&mut self) -> ast::Statement {
-    let repeat_token = self.consume_expect_token(TokenKind::Repeat);
+  fn parse_repeat_statement(&mut self) -> ParseResult<ast::Statement> {
+    let repeat_token = self.consume_expect_token(TokenKind::Repeat)?;
     let body = self.parse_block_statement(&[TokenKind::Until]);
-    self.consume_expect_token(TokenKind::Until);
-    let condition = self.parse_expression();
-    ast::Statement::Repeat(ast::RepeatStatement::new(body, condition, repeat_token.range))
+    self.consume_expect_token(TokenKind::Until)?;
+    let condition = self.parse_expression()?;
+    Ok(ast::Statement::Repeat(ast::RepeatStatement::new(body, condition, repeat_token.range)))
+  }
+
+  fn parse_for_statement(&mut self) -> ParseResult<ast::Statement> {
+    let start_range = self.consume_expect_token(TokenKind::For)?.range;
+    let first_variable = self.parse_variable()?;
+    if self.match_token(&TokenKind::Assign) {
+      return self.parse_numeric_for_statement(first_variable, start_range);
+    }
+    self.parse_for_in_statement(first_variable, start_range)
   }
 
-  fn parse_for_statement(&mut self) -> ast::Statement {
-    let start_range = self.consume_expect_token(TokenKind::For).range;
-    // todo: I think this is wrong... :(
-    let init = self.parse_simple_assignment();
-    self.consume_expect_token(TokenKind::Comma);
-    let limit = self.parse_expression();
+  fn parse_numeric_for_statement(
+    &mut self,
+    variable: ast::Variable,
+    start_range: Range,
+  ) -> ParseResult<ast::Statement> {
+    let init = self.parse_simple_assignment(variable)?;
+    self.consume_expect_token(TokenKind::Comma)?;
+    let limit = self.parse_expression()?;
     let step = if self.match_token_and_consume(TokenKind::Comma).is_some() {
-      Some(self.parse_expression())
+      Some(self.parse_expression()?)
     } else {
       None
     };
-    self.consume_expect_token(TokenKind::Do);
+    self.consume_expect_token(TokenKind::Do)?;
     let body = self.parse_block_statement(&[TokenKind::End]);
-    let end_range = self.consume_expect_token(TokenKind::End).range;
+    let end_range = self.consume_expect_token(TokenKind::End)?.range;
     let range = create_middle_range(&start_range, &end_range);
-    ast::Statement::For(ast::ForStatement::new(init, limit, step, body, range))
+    Ok(ast::Statement::For(ast::ForStatement::new(init, limit, step, body, range)))
   }
 
-  fn parse_simple_assignment(&mut self) -> ast::AssignExpresion {
-    let ident = self.parse_identifier();
-    let range = ident.range.clone();
-    let ident_expression = ast::Expression::Identifier(ident);
-    self.consume_expect_token(TokenKind::Assign);
-    let value = self.parse_primary_expression();
-    ast::AssignExpresion::new(vec![ident_expression], vec![value], range)
+  // for k, v in pairs(t) do ... end
+  fn parse_for_in_statement(
+    &mut self,
+    first_variable: ast::Variable,
+    start_range: Range,
+  ) -> ParseResult<ast::Statement> {
+    let mut variables = vec![first_variable];
+    while self.match_token_and_consume(TokenKind::Comma).is_some() {
+      variables.push(self.parse_variable()?);
+    }
+    self.consume_expect_token(TokenKind::In)?;
+    let mut iterators = vec![self.parse_expression()?];
+    while self.match_token_and_consume(TokenKind::Comma).is_some() {
+      iterators.push(self.parse_expression()?);
+    }
+    self.consume_expect_token(TokenKind::Do)?;
+    let body = self.parse_block_statement(&[TokenKind::End]);
+    let end_range = self.consume_expect_token(TokenKind::End)?.range;
+    let range = create_middle_range(&start_range, &end_range);
+    Ok(ast::Statement::ForIn(ast::ForInStatement::new(variables, iterators, body, range)))
+  }
+
+  fn parse_simple_assignment(&mut self, variable: ast::Variable) -> ParseResult<ast::AssignExpresion> {
+    let range = variable.get_range();
+    let ident_expression = ast::Expression::new_identifier(variable.name.lexeme().to_owned(), range.clone());
+    self.consume_expect_token(TokenKind::Assign)?;
+    let value = self.parse_expression()?;
+    Ok(ast::AssignExpresion::new(vec![ident_expression], vec![value], range))
+  }
+
+  fn parse_break_statement(&mut self) -> ParseResult<ast::Statement> {
+    let break_token = self.consume_expect_token(TokenKind::Break)?;
+    Ok(ast::Statement::Break(ast::BreakStatement::new(break_token.range)))
   }
 
-  fn parse_break_statement(&mut self) -> ast::Statement {
-    let break_token = self.consume_expect_token(TokenKind::Break);
-    ast::Statement::Break(ast::BreakStatement::new(break_token.range))
+  fn parse_goto_statement(&mut self) -> ParseResult<ast::Statement> {
+    let goto_token = self.consume_expect_token(TokenKind::Goto)?;
+    let label = self.parse_label_name()?;
+    let range = create_middle_range(&goto_token.range, &label.range);
+    Ok(ast::Statement::Goto(ast::GotoStatement::new(Some(label.lexeme().to_string()), range)))
+  }
+
+  fn parse_label_statement(&mut self) -> ParseResult<ast::Statement> {
+    let start_range = self.consume_expect_token(TokenKind::DoubleColon)?.range;
+    let label = self.parse_label_name()?;
+    let end_range = self.consume_expect_token(TokenKind::DoubleColon)?.range;
+    let range = create_middle_range(&start_range, &end_range);
+    Ok(ast::Statement::Label(ast::LabelStatement::new(label.lexeme().to_string(), range)))
+  }
+
+  fn parse_label_name(&mut self) -> ParseResult<Token> {
+    let label = self.lexer.next_token();
+    if !label.is_identifier() {
+      return Err(self.create_unexpected_token(label));
+    }
+    Ok(label)
   }
 
-  fn parse_continue_statement(&mut self) -> ast::Statement {
-    let continue_token = self.consume_expect_token(TokenKind::Continue);
-    ast::Statement::Continue(ast::ContinueStatement::new(continue_token.range))
+  fn parse_continue_statement(&mut self) -> ParseResult<ast::Statement> {
+    let continue_token = self.consume_expect_token(TokenKind::Continue)?;
+    Ok(ast::Statement::Continue(ast::ContinueStatement::new(continue_token.range)))
   }
 
-  fn parse_return_statement(&mut self) -> ast::Statement {
-    let return_token = self.consume_expect_token(TokenKind::Return);
-    let values = self.parse_return_values();
-    ast::Statement::Return(ast::ReturnStatement::new(values, return_token.range))
+  fn parse_return_statement(&mut self) -> ParseResult<ast::Statement> {
+    let return_token = self.consume_expect_token(TokenKind::Return)?;
+    let values = self.parse_return_values()?;
+    Ok(ast::Statement::Return(ast::ReturnStatement::new(values, return_token.range)))
   }
 
-  fn parse_function_expression(&mut self) -> ast::Expression {
-    let start_range = self.consume_expect_token(TokenKind::Function).range;
-    self.consume_expect_token(TokenKind::LeftParen);
-    let parameters = self.parse_variables();
-    self.consume_expect_token(TokenKind::RightParen);
+  fn parse_function_expression(&mut self) -> ParseResult<ast::Expression> {
+    let start_range = self.consume_expect_token(TokenKind::Function)?.range;
+    let generics = self.parse_generic_type_names()?;
+    self.consume_expect_token(TokenKind::LeftParen)?;
+    let parameters = self.parse_variables()?;
+    self.consume_expect_token(TokenKind::RightParen)?;
 
     let mut return_type = None;
     let mut return_type_range = None;
     if self.match_token_and_consume(TokenKind::Colon).is_some() {
       return_type_range = Some(self.lexer.peek_token().range.clone());
-      return_type = Some(self.parse_type(false));
+      return_type = Some(self.parse_type(false)?);
     }
 
     let body = self.parse_block_statement(&[TokenKind::End]);
-    let end_range = self.consume_expect_token(TokenKind::End).range;
+    let end_range = self.consume_expect_token(TokenKind::End)?.range;
     let range = create_middle_range(&start_range, &end_range);
 
-    ast::Expression::new_function(parameters, return_type, body, range, return_type_range)
+    Ok(ast::Expression::new_function(generics, parameters, return_type, body, range, return_type_range))
   }
 
-  fn parse_expression(&mut self) -> ast::Expression {
+  fn parse_expression(&mut self) -> ParseResult<ast::Expression> {
     self.parse_precedence(Precedence::Assignment)
   }
 
   // ! this is a recursive function :(... I think is better than the previous one
-  fn parse_precedence(&mut self, precedence: Precedence) -> ast::Expression {
+  fn parse_precedence(&mut self, precedence: Precedence) -> ParseResult<ast::Expression> {
     let mut left = if precedence == Precedence::Unary {
-      self.parse_unary_expression()
+      self.parse_unary_expression()?
     } else {
-      self.parse_precedence(precedence.next())
+      self.parse_precedence(precedence.next())?
     };
     while let Some(token) = self.match_any_token(precedence.operators()) {
-      let operator = self.token_to_binary_operator(&token);
-      let right = self.parse_precedence(precedence.next());
+      let operator = self.token_to_binary_operator(&token)?;
+      let right_precedence = if precedence.is_right_associative() { precedence } else { precedence.next() };
+      let right = self.parse_precedence(right_precedence)?;
       left = ast::Expression::new_binary(operator, left, right, token.range);
     }
 
-    left
+    Ok(left)
   }
 
   // fn parse_expression(&mut self) -> ast::Expression {
@@ -365,23 +648,40 @@ impl<'a> Parser<'a> {
   //   left
   // }
 
-  fn parse_unary_expression(&mut self) -> ast::Expression {
-    if let Some(token) = self.match_any_token(&[TokenKind::Minus, TokenKind::Not, TokenKind::Hash]) {
-      let operator = self.token_to_unary_operator(&token);
-      let expr = self.parse_unary_expression();
-      ast::Expression::new_unary(operator, expr, token.range)
+  fn parse_unary_expression(&mut self) -> ParseResult<ast::Expression> {
+    let unary_operators = &[TokenKind::Minus, TokenKind::Not, TokenKind::Hash, TokenKind::Tilde];
+    if let Some(token) = self.match_any_token(unary_operators) {
+      let operator = self.token_to_unary_operator(&token)?;
+      let expr = self.parse_unary_expression()?;
+      Ok(ast::Expression::new_unary(operator, expr, token.range))
     } else {
-      self.parse_primary_expression()
+      self.parse_power_expression()
     }
   }
 
-  fn token_to_binary_operator(&self, token: &Token) -> ast::BinaryOperator {
-    match token.kind {
+  // `^` binds tighter than unary operators on its left, but not on its right: `-x^-y` is `-(x^(-y))`
+  fn parse_power_expression(&mut self) -> ParseResult<ast::Expression> {
+    let base = self.parse_primary_expression()?;
+    if let Some(token) = self.match_any_token(&[TokenKind::Caret]) {
+      let exponent = self.parse_unary_expression()?;
+      return Ok(ast::Expression::new_binary(ast::BinaryOperator::Power, base, exponent, token.range));
+    }
+    Ok(base)
+  }
+
+  fn token_to_binary_operator(&self, token: &Token) -> ParseResult<ast::BinaryOperator> {
+    let operator = match token.kind {
       TokenKind::Plus => ast::BinaryOperator::Add,
       TokenKind::Minus => ast::BinaryOperator::Subtract,
       TokenKind::Star => ast::BinaryOperator::Multiply,
       TokenKind::Slash => ast::BinaryOperator::Divide,
       TokenKind::Percent => ast::BinaryOperator::Modulus,
+      TokenKind::DoubleSlash => ast::BinaryOperator::DoubleSlash,
+      TokenKind::Ampersand => ast::BinaryOperator::BitwiseAnd,
+      TokenKind::Pipe => ast::BinaryOperator::BitwiseOr,
+      TokenKind::Tilde => ast::BinaryOperator::BitwiseXor,
+      TokenKind::ShiftLeft => ast::BinaryOperator::ShiftLeft,
+      TokenKind::ShiftRight => ast::BinaryOperator::ShiftRight,
       TokenKind::And => ast::BinaryOperator::And,
       TokenKind::Or => ast::BinaryOperator::Or,
       TokenKind::Equal => ast::BinaryOperator::Equal,
@@ -391,170 +691,276 @@ impl<'a> Parser<'a> {
       TokenKind::LessEqual => ast::BinaryOperator::LessThanOrEqual,
       TokenKind::GreaterEqual => ast::BinaryOperator::GreaterThanOrEqual,
       TokenKind::DoubleDot => ast::BinaryOperator::DoubleDot,
-      _ => self.report_unexpected_token(token.clone()),
-    }
+      _ => return Err(self.create_unexpected_token(token.clone())),
+    };
+    Ok(operator)
   }
 
-  fn token_to_unary_operator(&self, token: &Token) -> ast::UnaryOperator {
-    match token.kind {
+  fn token_to_unary_operator(&self, token: &Token) -> ParseResult<ast::UnaryOperator> {
+    let operator = match token.kind {
       TokenKind::Minus => ast::UnaryOperator::Negate,
       TokenKind::Not => ast::UnaryOperator::Not,
       TokenKind::Hash => ast::UnaryOperator::Hash,
-      _ => self.report_unexpected_token(token.clone()),
-    }
+      TokenKind::Tilde => ast::UnaryOperator::BitwiseNot,
+      _ => return Err(self.create_unexpected_token(token.clone())),
+    };
+    Ok(operator)
   }
 
-  fn parse_primary_expression(&mut self) -> ast::Expression {
+  fn parse_primary_expression(&mut self) -> ParseResult<ast::Expression> {
     let token = self.lexer.peek_token();
     let mut expression = match token.kind {
-      TokenKind::Number(_) | TokenKind::String(_) => self.parse_literal_expression(),
-      TokenKind::Identifier(_) => self.parse_identifier_expression(),
-      TokenKind::Nil | TokenKind::True | TokenKind::False => self.parse_literal_expression(),
-      TokenKind::LeftParen => self.parse_grouped_expression(),
-      TokenKind::Require => self.parse_require_expression(),
-      TokenKind::Function => self.parse_function_expression(),
-      TokenKind::LeftBrace => self.parse_table_expression(),
-      _ => self.report_unexpected_token(token),
+      TokenKind::Number(_) | TokenKind::String(_) => self.parse_literal_expression()?,
+      TokenKind::Identifier(_) => self.parse_identifier_expression()?,
+      TokenKind::Type => ast::Expression::new_identifier("type".to_string(), self.consume_token().range),
+      TokenKind::Nil | TokenKind::True | TokenKind::False => self.parse_literal_expression()?,
+      TokenKind::LeftParen => self.parse_grouped_expression()?,
+      TokenKind::Require => self.parse_require_expression()?,
+      TokenKind::Function => self.parse_function_expression()?,
+      TokenKind::LeftBrace => self.parse_table_expression()?,
+      TokenKind::TripleDot => return Ok(ast::Expression::new_vararg(self.consume_token().range)),
+      _ => return Err(self.create_unexpected_token(token)),
     };
 
-    // index expression and member expression
-    while self.match_token(&TokenKind::Dot) || self.match_token(&TokenKind::LeftBracket) {
-      if self.match_token(&TokenKind::Dot) {
-        expression = self.parse_member_expression(expression);
-      } else if self.match_token(&TokenKind::LeftBracket) {
-        expression = self.parse_index_expression(expression);
-      }
-    }
-    // call expression
-    while self.match_token(&TokenKind::LeftParen) {
-      expression = self.parse_call_expression(Some(expression));
+    // index, member, method and call expressions, e.g. `a.b[c]:d(e)(f)`
+    loop {
+      expression = match self.lexer.peek_token().kind {
+        TokenKind::Dot => self.parse_member_expression(expression)?,
+        TokenKind::LeftBracket => self.parse_index_expression(expression)?,
+        TokenKind::LeftParen => self.parse_call_expression(Some(expression))?,
+        TokenKind::Colon => match self.parse_method_or_typed_variable(expression)? {
+          ast::Expression::Variable(variable) => {
+            return self.parse_assign_expression(Some(ast::Expression::Variable(variable)));
+          }
+          method => method,
+        },
+        _ => break,
+      };
     }
 
     // assign expression
-    if self.match_token(&TokenKind::Assign) || self.match_token(&TokenKind::Colon) {
+    if self.match_token(&TokenKind::Assign) {
       return self.parse_assign_expression(Some(expression));
     }
 
+    if let Some(token) = self.match_any_token(&COMPOUND_ASSIGN_TOKENS) {
+      return self.parse_compound_assign_expression(expression, token);
+    }
+
     // call expression
     // if self.match_token(&TokenKind::LeftParen) {
     //   return self.parse_call_expression(Some(expression));
     // }
-    expression
+    Ok(expression)
   }
 
-  fn parse_member_expression(&mut self, base: ast::Expression) -> ast::Expression {
-    self.consume_expect_token(TokenKind::Dot); // consume '.'
-    let member_expression = self.parse_identifier();
-    ast::Expression::new_member(base, member_expression)
+  fn parse_member_expression(&mut self, base: ast::Expression) -> ParseResult<ast::Expression> {
+    self.consume_expect_token(TokenKind::Dot)?; // consume '.'
+    let member_expression = self.parse_identifier()?;
+    Ok(ast::Expression::new_member(base, member_expression))
   }
 
-  fn parse_index_expression(&mut self, base: ast::Expression) -> ast::Expression {
-    let start_range = self.consume_expect_token(TokenKind::LeftBracket).range; // consume '['
-    let index_expression = self.parse_expression();
-    let end_range = self.consume_expect_token(TokenKind::RightBracket).range; // consume ']'
+  // `base:name(args)` is a method call, `name: type = value` a typed global assignment
+  fn parse_method_or_typed_variable(&mut self, base: ast::Expression) -> ParseResult<ast::Expression> {
+    self.consume_expect_token(TokenKind::Colon)?;
+    let base_identifier = match &base {
+      ast::Expression::Identifier(identifier) => {
+        Some(Token::new_identifier(identifier.range.clone(), identifier.name.clone()))
+      }
+      _ => None,
+    };
+
+    let peeked = self.lexer.peek_token();
+    if !peeked.is_identifier() {
+      match base_identifier {
+        Some(name) => return Ok(ast::Expression::Variable(ast::Variable::new(name, Some(self.parse_type(false)?)))),
+        None => return Err(self.create_unexpected_token(peeked)),
+      }
+    }
+
+    let method = self.parse_identifier()?;
+    if self.match_token(&TokenKind::LeftParen) {
+      let member = ast::Expression::new_method_member(base, method);
+      return self.parse_call_expression(Some(member));
+    }
+
+    match base_identifier {
+      Some(name) => {
+        let method_token = Token::new_identifier(method.range, method.name);
+        let ty = self.parse_identifier_type_with_name(method_token)?;
+        Ok(ast::Expression::Variable(ast::Variable::new(name, Some(ty))))
+      }
+      None => {
+        let token = self.lexer.peek_token();
+        Err(self.create_unexpected_token(token))
+      }
+    }
+  }
+
+  fn parse_index_expression(&mut self, base: ast::Expression) -> ParseResult<ast::Expression> {
+    let start_range = self.consume_expect_token(TokenKind::LeftBracket)?.range; // consume '['
+    let index_expression = self.parse_expression()?;
+    let end_range = self.consume_expect_token(TokenKind::RightBracket)?.range; // consume ']'
 
     let bracket_range = create_middle_range(&start_range, &end_range);
 
-    ast::Expression::Index(ast::IndexExpression {
+    Ok(ast::Expression::Index(ast::IndexExpression {
       base: Box::new(base),
       index: Box::new(index_expression),
       bracket_range,
-    })
+    }))
   }
 
-  fn parse_table_expression(&mut self) -> ast::Expression {
-    let left_range = self.consume_expect_token(TokenKind::LeftBrace).range;
-    let mut values = vec![];
+  fn parse_table_expression(&mut self) -> ParseResult<ast::Expression> {
+    let left_range = self.consume_expect_token(TokenKind::LeftBrace)?.range;
+    let mut fields = vec![];
     while !self.match_token(&TokenKind::RightBrace) {
-      let key_or_value = self.parse_expression();
-      if self.match_token_and_consume(TokenKind::Assign).is_some() {
-        let value = self.parse_expression();
-        values.push((key_or_value, Some(value)));
-      } else {
-        values.push((key_or_value, None));
-      }
-      if self.match_token(&TokenKind::RightBrace) {
+      fields.push(self.parse_table_field()?);
+      if self.match_token_and_consume(TokenKind::Comma).is_none()
+        && self.match_token_and_consume(TokenKind::Semicolon).is_none()
+      {
         break;
       }
-      self.consume_expect_token(TokenKind::Comma);
     }
-    let right_range = self.consume_expect_token(TokenKind::RightBrace).range;
+    let right_range = self.consume_expect_token(TokenKind::RightBrace)?.range;
     let range = create_middle_range(&left_range, &right_range);
-    ast::Expression::new_table(values, range)
+    Ok(ast::Expression::new_table(fields, range))
   }
 
-  fn parse_literal_expression(&mut self) -> ast::Expression {
-    let token = self.lexer.next_token();
-    match token.kind {
-      TokenKind::Number(value) => ast::Expression::new_literal(ast::LiteralExpression::new_number(value, token.range)),
-      TokenKind::String(value) => ast::Expression::new_literal(ast::LiteralExpression::new_string(value, token.range)),
-      TokenKind::True => ast::Expression::new_literal(ast::LiteralExpression::new_bool(true, token.range)),
-      TokenKind::False => ast::Expression::new_literal(ast::LiteralExpression::new_bool(false, token.range)),
-      TokenKind::Nil => ast::Expression::new_literal(ast::LiteralExpression::new_nil(token.range)),
-      _ => self.report_unexpected_token(token),
+  fn parse_table_field(&mut self) -> ParseResult<ast::TableField> {
+    if self.match_token_and_consume(TokenKind::LeftBracket).is_some() {
+      let key = self.parse_expression()?;
+      self.consume_expect_token(TokenKind::RightBracket)?;
+      self.consume_expect_token(TokenKind::Assign)?;
+      let value = self.parse_expression()?;
+      return Ok(ast::TableField::Computed(key, value));
     }
+
+    // `name = value`, told apart from an expression by looking one token past the name
+    if self.lexer.peek_token().is_identifier() && self.lexer.peek_second_token().kind == TokenKind::Assign {
+      let name = self.parse_identifier()?;
+      self.consume_expect_token(TokenKind::Assign)?;
+      return Ok(ast::TableField::Named(name, self.parse_expression()?));
+    }
+
+    match self.parse_expression()? {
+      ast::Expression::Assign(assign) => {
+        let message = "expected a field name or '[' before '='".to_string();
+        Err(Diagnostic::new(DiagnosticLevel::Error, message, Some(assign.get_range())))
+      }
+      value => Ok(ast::TableField::Positional(value)),
+    }
+  }
+
+  fn parse_literal_expression(&mut self) -> ParseResult<ast::Expression> {
+    let token = self.lexer.next_token();
+    let literal = match token.kind {
+      TokenKind::Number(value) => ast::LiteralExpression::new_number(value, token.range),
+      TokenKind::String(value) => ast::LiteralExpression::String(self.create_string_literal(value, token.range)),
+      TokenKind::True => ast::LiteralExpression::new_bool(true, token.range),
+      TokenKind::False => ast::LiteralExpression::new_bool(false, token.range),
+      TokenKind::Nil => ast::LiteralExpression::new_nil(token.range),
+      _ => return Err(self.create_unexpected_token(token)),
+    };
+    Ok(ast::Expression::new_literal(literal))
   }
 
-  fn parse_grouped_expression(&mut self) -> ast::Expression {
-    let left_range = self.consume_expect_token(TokenKind::LeftParen).range;
+  fn parse_grouped_expression(&mut self) -> ParseResult<ast::Expression> {
+    let left_range = self.consume_expect_token(TokenKind::LeftParen)?.range;
     let mut expressions = vec![];
 
     if self.match_token(&TokenKind::RightParen) {
-      let right_range = self.consume_expect_token(TokenKind::RightParen).range;
-      return ast::Expression::new_grouped(expressions, create_middle_range(&left_range, &right_range));
+      let right_range = self.consume_expect_token(TokenKind::RightParen)?.range;
+      return Ok(ast::Expression::new_grouped(expressions, create_middle_range(&left_range, &right_range)));
     }
 
-    expressions.push(self.parse_expression());
+    expressions.push(self.parse_expression()?);
 
     while self.match_token(&TokenKind::Comma) {
-      self.consume_expect_token(TokenKind::Comma);
-      expressions.push(self.parse_expression());
+      self.consume_expect_token(TokenKind::Comma)?;
+      expressions.push(self.parse_expression()?);
     }
-    let right_range = self.consume_expect_token(TokenKind::RightParen).range;
+    let right_range = self.consume_expect_token(TokenKind::RightParen)?.range;
 
     let range = create_middle_range(&left_range, &right_range);
-    ast::Expression::new_grouped(expressions, range)
+    Ok(ast::Expression::new_grouped(expressions, range))
   }
 
-  fn parse_require_expression(&mut self) -> ast::Expression {
-    let require_token = self.consume_expect_token(TokenKind::Require);
+  fn parse_require_expression(&mut self) -> ParseResult<ast::Expression> {
+    let require_token = self.consume_expect_token(TokenKind::Require)?;
     let module_name = self.consume_token();
     let range = require_token.range;
-    ast::Expression::new_require(module_name, range)
+    Ok(ast::Expression::new_require(module_name, range))
   }
 
-  fn parse_call_expression(&mut self, left: Option<ast::Expression>) -> ast::Expression {
-    let left = left.unwrap_or_else(|| self.parse_expression());
-    let args = self.parse_grouped_expression();
-    ast::Expression::new_call(left, args)
+  fn parse_call_expression(&mut self, left: Option<ast::Expression>) -> ParseResult<ast::Expression> {
+    let left = match left {
+      Some(left) => left,
+      None => self.parse_expression()?,
+    };
+    let args = self.parse_grouped_expression()?;
+    Ok(ast::Expression::new_call(left, args))
   }
 
   fn parse_block_statement(&mut self, end_tokens: &[TokenKind]) -> ast::Statement {
     let mut statements = Vec::new();
     self.skip_comments();
-    while !self.contains_token(end_tokens) {
-      statements.push(self.parse_statement());
+    while !self.is_end() && !self.contains_token(end_tokens) {
+      if let Some(statement) = self.parse_statement_or_recover(end_tokens) {
+        statements.push(statement);
+      }
       self.skip_comments();
     }
     ast::Statement::Block(ast::BlockStatement::new(statements))
   }
 
-  fn parse_assign_expression(&mut self, left: Option<ast::Expression>) -> ast::Expression {
-    let mut variables = vec![left.unwrap_or_else(|| self.parse_expression())];
+  // `a += b`, `t[k] ..= s`...
+  fn parse_compound_assign_expression(&mut self, left: ast::Expression, token: Token) -> ParseResult<ast::Expression> {
+    if !matches!(left, ast::Expression::Identifier(_) | ast::Expression::Member(_) | ast::Expression::Index(_)) {
+      let message = format!("cannot assign with '{}' to this expression", token.kind);
+      return Err(Diagnostic::new(DiagnosticLevel::Error, message, Some(left.get_range())));
+    }
+    let operator = match token.kind {
+      TokenKind::PlusAssign => ast::BinaryOperator::Add,
+      TokenKind::MinusAssign => ast::BinaryOperator::Subtract,
+      TokenKind::StarAssign => ast::BinaryOperator::Multiply,
+      TokenKind::SlashAssign => ast::BinaryOperator::Divide,
+      _ => ast::BinaryOperator::DoubleDot,
+    };
+    let right = self.parse_expression()?;
+    Ok(ast::Expression::new_compound_assign(left, operator, right))
+  }
+
+  fn parse_assign_expression(&mut self, left: Option<ast::Expression>) -> ParseResult<ast::Expression> {
+    let left = match left {
+      Some(left) => left,
+      None => self.parse_expression()?,
+    };
+    let mut variables = vec![left];
     let start_range = variables.first().unwrap().get_range();
 
     while self.match_token_and_consume(TokenKind::Comma).is_some() {
-      variables.push(self.parse_expression());
+      match self.parse_expression()? {
+        // the last variable already parsed the rest, e.g. `a, b = 1, 2`
+        ast::Expression::Assign(assign) => {
+          variables.extend(assign.left);
+          self.check_assign_targets(&variables)?;
+          let range = create_middle_range(&start_range, &assign.range);
+          return Ok(ast::Expression::new_assign(variables, assign.right, range));
+        }
+        variable => variables.push(variable),
+      }
     }
+    self.check_assign_targets(&variables)?;
 
     let mut end_range = variables.last().unwrap().get_range();
 
-    self.consume_expect_token(TokenKind::Assign); // consume '='
+    self.consume_expect_token(TokenKind::Assign)?; // consume '='
 
-    let mut initializer = vec![self.parse_expression()];
+    let mut initializer = vec![self.parse_expression()?];
 
     while self.match_token_and_consume(TokenKind::Comma).is_some() {
-      let expression = self.parse_expression();
+      let expression = self.parse_expression()?;
       if !self.match_token(&TokenKind::Comma) {
         end_range = expression.get_range();
       }
@@ -563,181 +969,314 @@ impl<'a> Parser<'a> {
 
     let range = create_middle_range(&start_range, &end_range);
 
-    ast::Expression::new_assign(variables, initializer, range)
+    Ok(ast::Expression::new_assign(variables, initializer, range))
+  }
+
+  // only names, fields and indexes hold a value, `f() = 1` or `(x) = 1` can't be assigned
+  fn check_assign_targets(&self, variables: &[ast::Expression]) -> ParseResult<()> {
+    let is_target = |variable: &&ast::Expression| {
+      matches!(
+        variable,
+        ast::Expression::Identifier(_)
+          | ast::Expression::Variable(_)
+          | ast::Expression::Member(_)
+          | ast::Expression::Index(_)
+      )
+    };
+    match variables.iter().find(|variable| !is_target(variable)) {
+      Some(variable) => {
+        let message = "cannot assign to this expression".to_string();
+        Err(Diagnostic::new(DiagnosticLevel::Error, message, Some(variable.get_range())))
+      }
+      None => Ok(()),
+    }
   }
 
-  pub fn parse_function_type(&mut self) -> Type {
-    self.consume_expect_token(TokenKind::Function);
-    self.consume_expect_token(TokenKind::LeftParen);
-    let params = self.parse_parameters_with_type();
-    self.consume_expect_token(TokenKind::RightParen);
-    self.consume_expect_token(TokenKind::Colon);
-    let return_type = self.parse_type(true);
-    Type::new_function(params, return_type)
+  // `function(x: number): number & function(x: string): string` is an overload set
+  pub fn parse_function_type(&mut self) -> ParseResult<Type> {
+    self.consume_expect_token(TokenKind::Function)?;
+    self.consume_expect_token(TokenKind::LeftParen)?;
+    let params = self.parse_parameters_with_type()?;
+    self.consume_expect_token(TokenKind::RightParen)?;
+    self.consume_expect_token(TokenKind::Colon)?;
+    // a `&` after the return type starts the next signature
+    let return_type = self.parse_union_type(true, false)?;
+    let function = FunctionType { generics: vec![], params, return_type: Box::new(return_type) };
+    if !self.match_token(&TokenKind::Ampersand) {
+      return Ok(Type::Function(function));
+    }
+    self.consume_token();
+    let peeked = self.lexer.peek_token();
+    let mut functions = vec![function];
+    match self.parse_function_type()? {
+      Type::Function(next) => functions.push(next),
+      Type::Overload(next) => functions.extend(next.functions),
+      _ => return Err(self.create_syntax_error("expected a function type after '&'".to_string(), peeked)),
+    }
+    Ok(Type::new_overload(functions))
   }
 
-  pub fn parse_parameters_with_type(&mut self) -> Vec<Type> {
+  pub fn parse_parameters_with_type(&mut self) -> ParseResult<Vec<Type>> {
     let mut parameters = Vec::new();
+    let mut after_optional = false;
     while !self.match_token(&TokenKind::RightParen) {
-      let _ = self.consume_token();
-      self.consume_expect_token(TokenKind::Colon);
-      let ty = self.parse_type(false);
-      parameters.push(ty);
+      let name = self.consume_token();
+      // `x?: number` can be left out
+      let optional = self.match_token_and_consume(TokenKind::Question).is_some();
+      if after_optional && !optional && !name.is_triple_dot() {
+        return Err(self.create_required_after_optional(name));
+      }
+      after_optional |= optional;
+      self.consume_expect_token(TokenKind::Colon)?;
+      let ty = self.parse_type(false)?;
+      parameters.push(if optional { Type::new_option(ty) } else { ty });
       self.match_token_and_consume(TokenKind::Comma);
     }
-    parameters
+    Ok(parameters)
   }
 
-  fn parse_nill_type(&mut self) -> Type {
+  fn parse_nill_type(&mut self) -> ParseResult<Type> {
     self.consume_token();
-    return Type::Nil;
+    return Ok(Type::Nil);
   }
 
-  fn parse_group_return_type(&mut self) -> Type {
-    self.consume_expect_token(TokenKind::LeftParen);
+  fn parse_group_return_type(&mut self) -> ParseResult<Type> {
+    self.consume_expect_token(TokenKind::LeftParen)?;
     let mut types = Vec::new();
     while !self.match_token(&TokenKind::RightParen) {
-      types.push(self.parse_type(false));
+      types.push(self.parse_type(false)?);
       self.match_token_and_consume(TokenKind::Comma);
     }
-    self.consume_expect_token(TokenKind::RightParen);
-    Type::new_group(types)
-  }
-
-  fn parse_generic_types(&mut self) -> Vec<Type> {
-    let mut types = Vec::new();
-    if !self.match_token(&TokenKind::Less) {
-      return types;
-    }
-    self.consume_expect_token(TokenKind::Less);
-    while !self.match_token(&TokenKind::Greater) {
-      let ty = self.parse_type(false);
-      types.push(ty);
-      self.match_token_and_consume(TokenKind::Comma);
-    }
-
-    self.consume_expect_token(TokenKind::Greater);
-    types
+    self.consume_expect_token(TokenKind::RightParen)?;
+    Ok(Type::new_group(types))
   }
 
-  fn parse_generic_type_names(&mut self) -> Vec<String> {
+  fn parse_generic_type_names(&mut self) -> ParseResult<Vec<String>> {
     if !self.match_token(&TokenKind::Less) {
-      return vec![];
+      return Ok(vec![]);
     }
-    self.consume_expect_token(TokenKind::Less);
+    self.consume_expect_token(TokenKind::Less)?;
     let mut generics = vec![];
     while !self.match_token(&TokenKind::Greater) {
-      let name = self.consume_token();
-      generics.push(name.lexeme().to_string());
+      generics.push(self.parse_identifier()?.name);
       self.match_token_and_consume(TokenKind::Comma);
     }
-    self.consume_expect_token(TokenKind::Greater);
-    generics
+    self.consume_expect_token(TokenKind::Greater)?;
+    Ok(generics)
   }
 
-  fn parse_return_values(&mut self) -> Vec<ast::Expression> {
+  fn parse_return_values(&mut self) -> ParseResult<Vec<ast::Expression>> {
     let mut values = Vec::new();
     if self.match_token(&TokenKind::Semicolon) {
-      return values;
+      return Ok(values);
     }
-    values.push(self.parse_expression());
+    values.push(self.parse_expression()?);
     while self.match_token_and_consume(TokenKind::Comma).is_some() {
-      values.push(self.parse_expression());
+      values.push(self.parse_expression()?);
     }
-    values
+    Ok(values)
   }
 
-  fn parse_identifier(&mut self) -> ast::Identifier {
+  fn parse_identifier(&mut self) -> ParseResult<ast::Identifier> {
     let token = self.lexer.next_token();
     match token.kind {
-      TokenKind::Identifier(name) => ast::Identifier::new(name, token.range),
-      _ => self.report_unexpected_token(token),
+      TokenKind::Identifier(name) => Ok(ast::Identifier::new(name, token.range)),
+      _ => Err(self.create_unexpected_token(token)),
     }
   }
 
-  fn parse_identifier_expression(&mut self) -> ast::Expression {
+  fn parse_identifier_expression(&mut self) -> ParseResult<ast::Expression> {
     let token = self.lexer.next_token();
 
     if !token.is_identifier() {
-      self.report_unexpected_token(token);
+      return Err(self.create_unexpected_token(token));
     }
 
-    ast::Expression::new_identifier(token.lexeme().to_owned(), token.range)
+    Ok(ast::Expression::new_identifier(token.lexeme().to_owned(), token.range))
   }
 
-  fn parse_identifier_type(&mut self) -> Type {
+  fn parse_identifier_type(&mut self) -> ParseResult<Type> {
     let token = self.lexer.next_token();
+    self.parse_identifier_type_with_name(token)
+  }
+
+  fn parse_identifier_type_with_name(&mut self, token: Token) -> ParseResult<Type> {
     match token.kind {
       TokenKind::Identifier(name) => {
         if self.match_token(&TokenKind::Less) {
-          self.consume_expect_token(TokenKind::Less);
+          self.consume_expect_token(TokenKind::Less)?;
           let mut types = Vec::new();
           while !self.match_token(&TokenKind::Greater) {
-            let ty = self.parse_type(false);
+            let ty = self.parse_type(false)?;
             types.push(ty);
             self.match_token_and_consume(TokenKind::Comma);
           }
-          let right_range = self.consume_expect_token(TokenKind::Greater).range;
+          let right_range = self.consume_expect_token(TokenKind::Greater)?.range;
           let range = create_middle_range(&token.range, &right_range);
-          return Type::new_generic_call(name, types, range);
+          return Ok(Type::new_generic_call(name, types, range));
         }
-        Type::new(&name, token.range)
+        Ok(Type::new(&name, token.range))
       }
-      _ => self.report_unexpected_token(token),
+      _ => Err(self.create_unexpected_token(token)),
+    }
+  }
+
+  fn parse_type(&mut self, allow_parenthesis: bool) -> ParseResult<Type> {
+    self.parse_union_type(allow_parenthesis, true)
+  }
+
+  // `A | B | C` is a union, `A & B | C` is `(A & B) | C`
+  fn parse_union_type(&mut self, allow_parenthesis: bool, allow_intersection: bool) -> ParseResult<Type> {
+    let first_type = self.parse_intersection_type(allow_parenthesis, allow_intersection)?;
+    if !self.match_token(&TokenKind::Pipe) {
+      return Ok(first_type);
+    }
+    let mut types = vec![first_type];
+    while self.match_token_and_consume(TokenKind::Pipe).is_some() {
+      types.push(self.parse_intersection_type(false, allow_intersection)?);
     }
+    Ok(Type::new_union(types))
   }
 
-  fn parse_type(&mut self, allow_parenthesis: bool) -> Type {
+  fn parse_intersection_type(&mut self, allow_parenthesis: bool, allow_intersection: bool) -> ParseResult<Type> {
+    let start_range = self.lexer.peek_token().range.clone();
+    let first_type = self.parse_single_type(allow_parenthesis)?;
+    if !allow_intersection || !self.match_token(&TokenKind::Ampersand) {
+      return Ok(first_type);
+    }
+    let mut types = vec![first_type];
+    while self.match_token_and_consume(TokenKind::Ampersand).is_some() {
+      types.push(self.parse_single_type(false)?);
+    }
+    let end_range = self.lexer.last_token_range();
+    Ok(Type::new_intersection(types, create_middle_range(&start_range, &end_range)))
+  }
+
+  fn parse_single_type(&mut self, allow_parenthesis: bool) -> ParseResult<Type> {
     let token = self.lexer.peek_token();
     if !allow_parenthesis && token.kind == TokenKind::LeftParen {
-      self.report_unexpected_token(token)
+      return Err(self.create_unexpected_token(token));
     }
     match token.kind {
+      TokenKind::Identifier(ref name) if name == "keyof" && self.starts_keyof_operand() => self.parse_keyof_type(),
       TokenKind::Identifier(_) => self.parse_identifier_type(),
       TokenKind::Nil => self.parse_nill_type(),
       TokenKind::LeftParen => self.parse_group_return_type(),
       TokenKind::Function => self.parse_function_type(),
       TokenKind::LeftBrace => self.parse_table_type(),
-      _ => self.report_unexpected_token(token),
+      TokenKind::String(_) | TokenKind::Number(_) | TokenKind::True | TokenKind::False => self.parse_literal_type(),
+      _ => Err(self.create_unexpected_token(token)),
     }
   }
 
-  fn parse_table_type(&mut self) -> Type {
-    self.consume_expect_token(TokenKind::LeftBrace);
-    let mut array_elements = HashSet::new();
+  // `keyof` alone is still a type name
+  fn starts_keyof_operand(&mut self) -> bool {
+    matches!(self.lexer.peek_second_token().kind, TokenKind::Identifier(_) | TokenKind::LeftBrace)
+  }
+
+  // `keyof T` is the union of the field names of `T`
+  fn parse_keyof_type(&mut self) -> ParseResult<Type> {
+    let keyof_range = self.consume_token().range;
+    let operand = self.parse_single_type(false)?;
+    let range = create_middle_range(&keyof_range, &self.lexer.last_token_range());
+    Ok(Type::new_generic_call("keyof".to_string(), vec![operand], range))
+  }
+
+  // `"up"`, `1`, `true`
+  fn parse_literal_type(&mut self) -> ParseResult<Type> {
+    let token = self.consume_token();
+    let literal = match token.kind {
+      TokenKind::String(string) => LiteralType::String(string),
+      TokenKind::Number(number) => LiteralType::Number(number),
+      TokenKind::True => LiteralType::Boolean(true),
+      TokenKind::False => LiteralType::Boolean(false),
+      _ => return Err(self.create_unexpected_token(token)),
+    };
+    Ok(Type::Literal(literal))
+  }
+
+  fn parse_table_type(&mut self) -> ParseResult<Type> {
+    self.consume_expect_token(TokenKind::LeftBrace)?;
+    let mut array_elements = vec![];
     let mut map_elements = BTreeMap::new();
+    let mut index = None;
+    let mut optional = BTreeSet::new();
+    let mut readonly = BTreeSet::new();
     while !self.match_token(&TokenKind::RightBrace) {
-      let type_or_key = self.parse_type(false);
+      // `[K]: V`
+      if self.match_token_and_consume(TokenKind::LeftBracket).is_some() {
+        let key_type = self.parse_type(false)?;
+        self.consume_expect_token(TokenKind::RightBracket)?;
+        self.consume_expect_token(TokenKind::Colon)?;
+        index = Some(IndexType::new(key_type, self.parse_type(false)?));
+        if self.match_token_and_consume(TokenKind::Comma).is_none() {
+          break;
+        }
+        continue;
+      }
+      // `readonly id: number`, `readonly` alone is still a field name
+      let is_readonly = self.match_readonly_modifier();
+      let type_or_key = self.parse_type(false)?;
+      // `age?: number`
+      let is_optional = matches!(type_or_key, Type::Alias(_)) && self.match_token(&TokenKind::Question);
+      if is_optional {
+        self.consume_token();
+      }
       let peeked = self.lexer.peek_token();
       match (&type_or_key, &peeked.kind) {
         (Type::Alias(identifier), &TokenKind::Colon) => {
-          self.consume_expect_token(TokenKind::Colon);
-          let value_type = self.parse_type(false);
-          map_elements.insert(identifier.name.to_string(), value_type);
+          self.consume_expect_token(TokenKind::Colon)?;
+          let value_type = self.parse_type(false)?;
+          let name = identifier.name.to_string();
+          if is_optional {
+            optional.insert(name.clone());
+          }
+          if is_readonly {
+            readonly.insert(name.clone());
+          }
+          map_elements.insert(name, value_type);
+        }
+        _ if is_optional || is_readonly => {
+          return Err(self.create_syntax_error("expected ':' after field name".to_string(), peeked));
         }
         _ => {
-          array_elements.insert(type_or_key);
+          array_elements.push(type_or_key);
         }
       }
       if self.match_token(&TokenKind::RightBrace) {
         break;
       }
-      self.consume_expect_token(TokenKind::Comma);
+      self.consume_expect_token(TokenKind::Comma)?;
     }
 
-    self.consume_expect_token(TokenKind::RightBrace);
-    let array = if array_elements.is_empty() { None } else { Some(array_elements) };
+    self.consume_expect_token(TokenKind::RightBrace)?;
+    // `{T}` is a list, `{A, B}` a tuple
+    let (array, tuple) = match array_elements.len() {
+      0 => (None, None),
+      1 => (array_elements.pop().map(Box::new), None),
+      _ => (None, Some(array_elements)),
+    };
     let map = if map_elements.is_empty() { None } else { Some(map_elements) };
-    Type::new_table(array, map)
+    Ok(Type::Table(TableType { array, tuple, map, index, optional, readonly, metatable: None }))
   }
 
-  fn consume_expect_token(&mut self, kind: TokenKind) -> Token {
-    let token = self.lexer.next_token();
+  fn match_readonly_modifier(&mut self) -> bool {
+    let is_modifier = self.match_token(&TokenKind::Identifier("readonly".to_string()))
+      && matches!(self.lexer.peek_second_token().kind, TokenKind::Identifier(_));
+    if is_modifier {
+      self.consume_token();
+    }
+    is_modifier
+  }
+
+  // a token that doesn't match is left for the recovery, it may start the next statement
+  fn consume_expect_token(&mut self, kind: TokenKind) -> ParseResult<Token> {
+    let token = self.lexer.peek_token();
     if token.kind != kind {
       let message = format!("expected '{}' but found '{}'", kind.to_string(), token.kind.to_string());
-      self.report_error(message, token);
+      return Err(self.create_syntax_error(message, token));
     }
-    token
+    Ok(self.consume_token())
   }
 
   fn consume_token(&mut self) -> Token {
@@ -783,12 +1322,23 @@ impl<'a> Parser<'a> {
     }
   }
 
-  fn report_unexpected_token(&self, token: Token) -> ! {
+  fn create_unexpected_token(&self, token: Token) -> Diagnostic {
     let message = format!("unexpected token '{}'", token.kind.to_string());
-    self.report_error(message, token)
+    self.create_syntax_error(message, token)
+  }
+
+  fn create_required_after_optional(&self, name: Token) -> Diagnostic {
+    let message = format!("required parameter `{}` can't come after an optional one", name.lexeme());
+    self.create_syntax_error(message, name)
+  }
+
+  // the lexer replaces bytes that aren't UTF-8, the source keeps them for the emitter
+  fn create_string_literal(&self, value: String, range: Range) -> ast::StringLiteral {
+    let source = value.contains(char::REPLACEMENT_CHARACTER).then(|| self.lexer.source(&range).to_string());
+    ast::StringLiteral { source, ..ast::StringLiteral::new(value, range) }
   }
 
-  fn report_error(&self, message: String, token: Token) -> ! {
-    report_and_exit(&message, &mut token.range.clone(), &self.raw, &self.lexer.file_name)
+  fn create_syntax_error(&self, message: String, token: Token) -> Diagnostic {
+    Diagnostic::new(DiagnosticLevel::Error, message, Some(token.range))
   }
 }
@@ -1,17 +1,21 @@
 use crate::ast::tokens::TokenKind;
 
+// from lowest to highest, following the lua 5.4 manual (§3.4.8)
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Precedence {
   Assignment,
   Or,
   And,
-  Equality,
   Comparison,
+  BitwiseOr,
+  BitwiseXor,
+  BitwiseAnd,
+  Shift,
+  Concat,
   Term,
   Factor,
   Unary,
   Primary,
-  Concat,
 }
 
 impl Precedence {
@@ -19,12 +23,22 @@ impl Precedence {
     match self {
       Precedence::Or => &[TokenKind::Or],
       Precedence::And => &[TokenKind::And],
-      Precedence::Equality => &[TokenKind::Equal, TokenKind::NotEqual],
-      Precedence::Comparison => &[TokenKind::Less, TokenKind::LessEqual, TokenKind::Greater, TokenKind::GreaterEqual],
-      Precedence::Term => &[TokenKind::Plus, TokenKind::Minus],
+      Precedence::Comparison => &[
+        TokenKind::Less,
+        TokenKind::LessEqual,
+        TokenKind::Greater,
+        TokenKind::GreaterEqual,
+        TokenKind::Equal,
+        TokenKind::NotEqual,
+      ],
+      Precedence::BitwiseOr => &[TokenKind::Pipe],
+      Precedence::BitwiseXor => &[TokenKind::Tilde],
+      Precedence::BitwiseAnd => &[TokenKind::Ampersand],
+      Precedence::Shift => &[TokenKind::ShiftLeft, TokenKind::ShiftRight],
       Precedence::Concat => &[TokenKind::DoubleDot],
-      Precedence::Factor => &[TokenKind::Star, TokenKind::Slash, TokenKind::Percent],
-      _ => &[], // no binary operators for unary and primary precedence :(
+      Precedence::Term => &[TokenKind::Plus, TokenKind::Minus],
+      Precedence::Factor => &[TokenKind::Star, TokenKind::Slash, TokenKind::DoubleSlash, TokenKind::Percent],
+      _ => &[], // no binary operators for unary and primary precedence :(, `^` is parsed with the unary operators
     }
   }
 
@@ -32,9 +46,12 @@ impl Precedence {
     match self {
       Precedence::Assignment => Precedence::Or,
       Precedence::Or => Precedence::And,
-      Precedence::And => Precedence::Equality,
-      Precedence::Equality => Precedence::Comparison,
-      Precedence::Comparison => Precedence::Concat,
+      Precedence::And => Precedence::Comparison,
+      Precedence::Comparison => Precedence::BitwiseOr,
+      Precedence::BitwiseOr => Precedence::BitwiseXor,
+      Precedence::BitwiseXor => Precedence::BitwiseAnd,
+      Precedence::BitwiseAnd => Precedence::Shift,
+      Precedence::Shift => Precedence::Concat,
       Precedence::Concat => Precedence::Term,
       Precedence::Term => Precedence::Factor,
       Precedence::Factor => Precedence::Unary,
@@ -42,4 +59,8 @@ impl Precedence {
       Precedence::Primary => Precedence::Primary,
     }
   }
+
+  pub fn is_right_associative(self) -> bool {
+    matches!(self, Precedence::Concat)
+  }
 }
@@ -0,0 +1,6 @@
+use crate::types::Type;
+
+// error(message, level?): never, it doesn't return
+pub fn create_error_type() -> Type {
+  Type::new_function(vec![Type::Unknown, Type::new_option(Type::Number)], Type::Never)
+}
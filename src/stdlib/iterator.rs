@@ -0,0 +1,23 @@
+use crate::types::Type;
+
+// next(table, index?): (key, value)
+pub fn create_next_type() -> Type {
+  let params = vec![Type::new_table(None, None), Type::new_option(Type::Any)];
+  Type::new_function(params, Type::new_group(vec![Type::Any, Type::Any]))
+}
+
+// pairs(table): (next, table, nil)
+pub fn create_pairs_type() -> Type {
+  let table = Type::new_table(None, None);
+  let return_type = Type::new_group(vec![create_next_type(), table.clone(), Type::Nil]);
+  Type::new_function(vec![table], return_type)
+}
+
+// ipairs(table): (iterator, table, 0)
+pub fn create_ipairs_type() -> Type {
+  let table = Type::new_table(None, None);
+  let iterator_params = vec![table.clone(), Type::Number];
+  let iterator = Type::new_function(iterator_params, Type::new_group(vec![Type::Number, Type::Any]));
+  let return_type = Type::new_group(vec![iterator, table.clone(), Type::Number]);
+  Type::new_function(vec![table], return_type)
+}
@@ -15,5 +15,8 @@ pub fn create_math_type() -> Type {
   math_table.insert("deg".to_string(), Type::new_function(vec![Type::Number], Type::Number));
   math_table.insert("exp".to_string(), Type::new_function(vec![Type::Number], Type::Number));
   math_table.insert("floor".to_string(), Type::new_function(vec![Type::Number], Type::Number));
+  // math.random(m?, n?)
+  let random_params = vec![Type::new_option(Type::Number), Type::new_option(Type::Number)];
+  math_table.insert("random".to_string(), Type::new_function(random_params, Type::Number));
   Type::new_table(None, Some(math_table))
 }
@@ -0,0 +1,7 @@
+use crate::types::Type;
+
+// setmetatable(table, metatable): table, the checker attaches the metatable to the result
+pub fn create_setmetatable_type() -> Type {
+  let table = Type::new_table(None, None);
+  Type::new_function(vec![table.clone(), Type::new_option(table.clone())], table)
+}
@@ -2,12 +2,17 @@
 use std::collections::BTreeMap;
 
 use crate::types::Type;
+mod error;
 mod io_type;
+mod iterator;
 mod math;
+pub mod metatable;
 mod number;
 mod print;
-mod string;
+pub mod select;
+pub mod string;
 mod table;
+mod type_function;
 
 pub fn create_stdlib() -> BTreeMap<String, Type> {
   let mut stdlib_variables = BTreeMap::new();
@@ -15,8 +20,16 @@ pub fn create_stdlib() -> BTreeMap<String, Type> {
   stdlib_variables.insert("print".to_string(), print::create_print_type());
   stdlib_variables.insert("io".to_string(), io_type::create_io_type());
   stdlib_variables.insert("math".to_string(), math::create_math_type());
-  // stdlib_variables.insert("number".to_string(), number::create_number_type());
-  // stdlib_variables.insert("string".to_string(), string::create_string_type());
+  stdlib_variables.insert("string".to_string(), string::create_string_type());
+  stdlib_variables.insert("tonumber".to_string(), number::create_tonumber_type());
+  stdlib_variables.insert("tostring".to_string(), number::create_tostring_type());
   stdlib_variables.insert("table".to_string(), table::create_table_type());
+  stdlib_variables.insert("next".to_string(), iterator::create_next_type());
+  stdlib_variables.insert("pairs".to_string(), iterator::create_pairs_type());
+  stdlib_variables.insert("ipairs".to_string(), iterator::create_ipairs_type());
+  stdlib_variables.insert("select".to_string(), select::create_select_type());
+  stdlib_variables.insert("type".to_string(), type_function::create_type_function_type());
+  stdlib_variables.insert("setmetatable".to_string(), metatable::create_setmetatable_type());
+  stdlib_variables.insert("error".to_string(), error::create_error_type());
   return stdlib_variables;
 }
@@ -1,7 +1,17 @@
-#![allow(dead_code)]
+use crate::types::{FunctionType, Type};
 
-use crate::types::Type;
+// tonumber(e: number): number, tonumber(e: string, base?: number): number?
+pub fn create_tonumber_type() -> Type {
+  let from_number = FunctionType { generics: vec![], params: vec![Type::Number], return_type: Box::new(Type::Number) };
+  let from_string = FunctionType {
+    generics: vec![],
+    params: vec![Type::String, Type::new_option(Type::Number)],
+    return_type: Box::new(Type::new_option(Type::Number)),
+  };
+  Type::new_overload(vec![from_number, from_string])
+}
 
-pub fn create_number_type() -> Vec<Type> {
-  todo!()
+// tostring(v): string
+pub fn create_tostring_type() -> Type {
+  Type::new_function(vec![Type::Unknown], Type::String)
 }
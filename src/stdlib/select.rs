@@ -0,0 +1,7 @@
+use crate::types::Type;
+
+// select(index, ...): ..., select('#', ...): number
+pub fn create_select_type() -> Type {
+  let params = vec![Type::Any, Type::new_variadic(Type::Any)];
+  Type::new_function(params, Type::new_variadic(Type::Any))
+}
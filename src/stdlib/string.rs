@@ -1,6 +1,18 @@
-#![allow(dead_code)]
+use std::collections::BTreeMap;
 
 use crate::types::Type;
-pub fn create_string_type() -> Vec<Type> {
-  todo!()
+
+pub fn create_string_type() -> Type {
+  let mut string_table = BTreeMap::new();
+  string_table.insert("len".to_string(), Type::new_function(vec![Type::String], Type::Number));
+  string_table.insert("lower".to_string(), Type::new_function(vec![Type::String], Type::String));
+  string_table.insert("upper".to_string(), Type::new_function(vec![Type::String], Type::String));
+  string_table.insert("reverse".to_string(), Type::new_function(vec![Type::String], Type::String));
+  // string.sub(s, i, j?)
+  let sub_params = vec![Type::String, Type::Number, Type::new_option(Type::Number)];
+  string_table.insert("sub".to_string(), Type::new_function(sub_params, Type::String));
+  // string.rep(s, n, sep?)
+  let rep_params = vec![Type::String, Type::Number, Type::new_option(Type::String)];
+  string_table.insert("rep".to_string(), Type::new_function(rep_params, Type::String));
+  Type::new_table(None, Some(string_table))
 }
@@ -1,14 +1,19 @@
 #![allow(dead_code)]
 use std::collections::BTreeMap;
 
-use crate::types::Type;
+use crate::types::{FunctionType, Type};
 
 pub fn create_table_type() -> Type {
   let mut table_type = BTreeMap::new();
   // todo: impove and add more functions
   // let table_tt_unkown =Type::new_table(None, None) ;
-  let insert_param_table = vec![Type::Unknown, Type::Unknown];
-  table_type.insert("insert".to_string(), Type::new_function(insert_param_table.clone(), Type::Nil));
-  table_type.insert("remove".to_string(), Type::new_function(insert_param_table, Type::Nil));
+  // table.insert(t, value), table.insert(t, pos, value)
+  let append = FunctionType { generics: vec![], params: vec![Type::Any, Type::Any], return_type: Box::new(Type::Nil) };
+  let insert_params = vec![Type::Any, Type::Number, Type::Any];
+  let insert = FunctionType { generics: vec![], params: insert_params, return_type: Box::new(Type::Nil) };
+  table_type.insert("insert".to_string(), Type::new_overload(vec![append, insert]));
+  // table.remove(t, pos?)
+  let remove_params = vec![Type::Any, Type::new_option(Type::Number)];
+  table_type.insert("remove".to_string(), Type::new_function(remove_params, Type::Any));
   Type::new_table(None, Some(table_type))
 }
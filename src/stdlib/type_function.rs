@@ -0,0 +1,6 @@
+use crate::types::Type;
+
+// type(value): string, the name of the value type, e.g. "number"
+pub fn create_type_function_type() -> Type {
+  Type::new_function(vec![Type::Unknown], Type::String)
+}
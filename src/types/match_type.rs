@@ -1,21 +1,82 @@
-use super::{FunctionType, GenericType, GroupType, OptionType, TableType, Type, UnionType, VariadicType};
+use std::collections::BTreeMap;
+
+use super::{
+  FunctionType, GenericType, GroupType, IndexType, LiteralType, OptionType, OverloadType, TableType, Type, UnionType,
+  VariadicType,
+};
 
 pub fn check_match_table(left: &TableType, right: &TableType) -> bool {
+  // an instance of a subclass has every field of its base class
+  if right.inherits(left) {
+    let mut fields = left.map.iter().flatten();
+    return fields.all(|(key, value)| right.get_type(key).is_some_and(|found| value.check_match(found)));
+  }
   match (left, right) {
-    (TableType { array: Some(left_array), map: None }, TableType { array: Some(right_array), map: None }) => {
-      left_array.len() == right_array.len() && left_array.iter().zip(right_array).all(|(l, r)| l.check_match(r))
-    }
-    (TableType { array: None, map: Some(left_map) }, TableType { array: None, map: Some(right_map) }) => {
-      left_map.len() == right_map.len()
-        && left_map.iter().all(|(k, v)| right_map.get(k).map_or(false, |rv| v.check_match(rv)))
+    (
+      TableType { array: Some(left_array), tuple: None, map: None, index: None, .. },
+      TableType { array: Some(right_array), tuple: None, map: None, index: None, .. },
+    ) => left_array.check_match(right_array),
+    (
+      TableType { array: None, tuple: Some(left_tuple), map: None, index: None, .. },
+      TableType { array: None, tuple: Some(right_tuple), map: None, index: None, .. },
+    ) => left_tuple.len() == right_tuple.len() && left_tuple.iter().zip(right_tuple).all(|(l, r)| l.check_match(r)),
+    // a tuple is a list when every value has the list type
+    (
+      TableType { array: Some(array), tuple: None, map: None, index: None, .. },
+      TableType { array: None, tuple: Some(tuple), map: None, index: None, .. },
+    )
+    | (
+      TableType { array: None, tuple: Some(tuple), map: None, index: None, .. },
+      TableType { array: Some(array), tuple: None, map: None, index: None, .. },
+    ) => tuple.iter().all(|element| array.check_match(element)),
+    // only the fields are compared when the left side declares nothing else, `{1, 2, name = "x"}` has a `name`
+    (
+      TableType { array: None, tuple: None, map: Some(left_map), index: None, .. },
+      TableType { map: Some(right_map), .. },
+    ) => check_match_record(left, left_map, right, right_map),
+    (TableType { array: None, tuple: None, map: None, index: Some(index), .. }, right) => {
+      check_match_index(index, right)
     }
-    (TableType { array: None, map: None }, TableType { array: None, map: None }) => true,
-    (TableType { array: None, map: None }, TableType { .. }) => true,
-    (TableType { .. }, TableType { array: None, map: None }) => true,
+    (TableType { array: None, tuple: None, map: None, index: None, .. }, TableType { .. }) => true,
+    (TableType { .. }, TableType { array: None, tuple: None, map: None, index: None, .. }) => true,
     _ => false,
   }
 }
 
+// every key of the right side must fit the key type and every value the value type,
+// list and tuple entries have number keys
+fn check_match_index(index: &IndexType, right: &TableType) -> bool {
+  let fits = |key: &Type, value: &Type| index.key.check_match(key) && index.value.check_match(value);
+  let fields = right.map.iter().flatten();
+  fields
+    .map(|(key, value)| (Type::Literal(LiteralType::String(key.clone())), value))
+    .all(|(key, value)| fits(&key, value))
+    && right.array.iter().all(|element| fits(&Type::Number, element))
+    && right.tuple.iter().flatten().all(|element| fits(&Type::Number, element))
+    && right.index.iter().all(|right_index| fits(&right_index.key, &right_index.value))
+}
+
+// fields on both sides must match, a field missing on the right must be optional,
+// the right side can have more fields than the left one
+fn check_match_record(
+  left: &TableType,
+  left_map: &BTreeMap<String, Type>,
+  right: &TableType,
+  right_map: &BTreeMap<String, Type>,
+) -> bool {
+  left_map.iter().all(|(key, left_value)| match right_map.get(key) {
+    Some(right_value) => check_match_field(left, right, key, left_value, right_value),
+    None => left.is_optional(key),
+  })
+}
+
+fn check_match_field(left: &TableType, right: &TableType, key: &str, left_value: &Type, right_value: &Type) -> bool {
+  if left.is_optional(key) && right_value.is_nil() || right.is_optional(key) && left_value.is_nil() {
+    return true;
+  }
+  left_value.check_match(right_value)
+}
+
 pub fn check_match_function(left: &FunctionType, right: &FunctionType) -> bool {
   if left.params.len() != right.params.len() {
     return false;
@@ -27,6 +88,16 @@ pub fn check_match_function(left: &FunctionType, right: &FunctionType) -> bool {
   left.return_type.check_match(&right.return_type)
 }
 
+pub fn check_match_overload(left: &OverloadType, right: &OverloadType) -> bool {
+  left.functions.len() == right.functions.len()
+    && left.functions.iter().zip(&right.functions).all(|(l, r)| check_match_function(l, r))
+}
+
+// a single function stands for an overload set when it fits every signature
+pub fn check_match_overload_with_function(left: &OverloadType, right: &FunctionType) -> bool {
+  left.functions.iter().all(|l| check_match_function(l, right))
+}
+
 pub fn check_match_generic(left: &GenericType, right: &GenericType) -> bool {
   left.name == right.name
     && left.variables.len() == right.variables.len()
@@ -47,17 +118,29 @@ pub fn check_match_option(left: &OptionType, right: &OptionType) -> bool {
 }
 
 pub fn check_match_option_right(left: &OptionType, right: &Type) -> bool {
+  if let Type::Union(union) = right {
+    return union.types.iter().all(|member| match member {
+      Type::Option(option) => check_match_option(left, option),
+      member => check_match_option_right(left, member),
+    });
+  }
   return if right.is_nil() { true } else { left.inner_type.check_match(right) };
 }
 
-pub fn check_match_union(left: &Vec<Type>, right: &Vec<Type>) -> bool {
-  left.len() == right.len() && left.iter().zip(right).all(|(l, r)| l.check_match(r))
+// every member on the right must fit some member on the left, in any order
+pub fn check_match_union(left: &[Type], right: &[Type]) -> bool {
+  right.iter().all(|r| left.iter().any(|l| l.check_match(r)))
 }
 
 pub fn check_match_union_with_single_type(left: &UnionType, right: &Type) -> bool {
   left.types.iter().any(|t| t.check_match(right))
 }
 
+// a union only fits a single type when every member does
+pub fn check_match_single_type_with_union(left: &Type, right: &UnionType) -> bool {
+  right.types.iter().all(|t| left.check_match(t))
+}
+
 pub fn check_match_variadic(left: &VariadicType, right: &VariadicType) -> bool {
   left.inner_type.check_match(&right.inner_type)
 }
@@ -1,10 +1,8 @@
-use crate::{
-  ast::ast::{BinaryOperator, UnaryOperator},
-  utils::range::Range,
-};
+use crate::utils::range::Range;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet};
 mod match_type;
+mod operators;
 use match_type::*;
 use std::hash::{Hash, Hasher};
 
@@ -16,14 +14,22 @@ pub enum Type {
   Alias(AliasType),
   Table(TableType),
   Function(FunctionType),
+  Overload(OverloadType),
   Generic(GenericType),
   GenericCall(GenericCallType),
   Union(UnionType),
+  Intersection(IntersectionType),
   Option(OptionType),
+  // anything, but only usable once narrowed
   Unknown,
+  // anything, unchecked
+  Any,
+  // no value, e.g. the result of `error()`
+  Never,
   Nil,
   Group(GroupType),
   Variadic(VariadicType),
+  Literal(LiteralType),
 }
 
 impl Hash for Type {
@@ -34,32 +40,66 @@ impl Hash for Type {
       Type::Boolean => state.write_u8(2),
       Type::Unknown => state.write_u8(3),
       Type::Nil => state.write_u8(4),
+      Type::Any => state.write_u8(5),
+      Type::Never => state.write_u8(6),
       Type::Table(table) => table.hash(state),
       Type::Function(function) => function.hash(state),
+      Type::Overload(overload) => overload.hash(state),
       Type::Generic(generic) => generic.hash(state),
       Type::GenericCall(generic_call) => generic_call.hash(state),
       Type::Union(union) => union.hash(state),
+      Type::Intersection(intersection) => intersection.hash(state),
       Type::Option(option) => option.hash(state),
       Type::Alias(identifier) => identifier.hash(state),
       Type::Group(group) => group.hash(state),
       Type::Variadic(variadic) => variadic.hash(state),
+      Type::Literal(literal) => literal.hash(state),
     }
   }
 }
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TableType {
-  pub array: Option<HashSet<Type>>,
+  // `{T}`, any number of values of one type
+  pub array: Option<Box<Type>>,
+  // `{A, B}`, one value of each type, in order
+  pub tuple: Option<Vec<Type>>,
   // todo: hash map or btree map?
   pub map: Option<BTreeMap<String, Type>>,
+  // `{[K]: V}`, for keys only known at runtime
+  pub index: Option<IndexType>,
+  // `age?: number`, map fields that may be absent
+  #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+  pub optional: BTreeSet<String>,
+  // `readonly id: number`, map fields that can't be assigned
+  #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+  pub readonly: BTreeSet<String>,
+  // set by `setmetatable(t, mt)`, it gives `__index` members and operator metamethods
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub metatable: Option<Box<TableType>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexType {
+  pub key: Box<Type>,
+  pub value: Box<Type>,
+}
+
+impl Hash for IndexType {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.key.hash(state);
+    self.value.hash(state);
+  }
 }
 
 impl Hash for TableType {
   fn hash<H: Hasher>(&self, state: &mut H) {
     if let Some(array) = &self.array {
       state.write_u8(10);
-      for element in array {
-        element.hash(state);
-      }
+      array.hash(state);
+    }
+    if let Some(tuple) = &self.tuple {
+      state.write_u8(15);
+      tuple.hash(state);
     }
     if let Some(map) = &self.map {
       state.write_u8(20);
@@ -68,6 +108,34 @@ impl Hash for TableType {
         value.hash(state);
       }
     }
+    if let Some(index) = &self.index {
+      state.write_u8(30);
+      index.hash(state);
+    }
+    self.optional.hash(state);
+    self.readonly.hash(state);
+    if let Some(metatable) = &self.metatable {
+      state.write_u8(40);
+      metatable.hash(state);
+    }
+  }
+}
+
+// a single value of a primitive type, `"up"`, `1` or `true`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LiteralType {
+  String(String),
+  Number(String),
+  Boolean(bool),
+}
+
+impl LiteralType {
+  pub fn base_type(&self) -> Type {
+    match self {
+      LiteralType::String(_) => Type::String,
+      LiteralType::Number(_) => Type::Number,
+      LiteralType::Boolean(_) => Type::Boolean,
+    }
   }
 }
 
@@ -91,9 +159,20 @@ impl Type {
       "string" => Type::String,
       "nil" => Type::Nil,
       "unknown" => Type::Unknown,
+      "any" => Type::Any,
+      "never" => Type::Never,
       _ => Type::Alias(AliasType { name: name.to_string(), range }),
     }
   }
+  // a parameter that takes nil can be left out at the end of a call
+  pub fn accepts_nil(&self) -> bool {
+    match self {
+      Type::Nil | Type::Option(_) => true,
+      Type::Union(union) => union.types.iter().any(Type::accepts_nil),
+      _ => false,
+    }
+  }
+
   pub fn is_nil(&self) -> bool {
     match self {
       Type::Nil => true,
@@ -102,6 +181,24 @@ impl Type {
     }
   }
 
+  // `local x = "up"` declares a `string`, literal types are only kept when written down
+  pub fn widen(&self) -> Type {
+    match self {
+      Type::Literal(literal) => literal.base_type(),
+      Type::Group(group) => Type::new_group(group.types.iter().map(Type::widen).collect()),
+      // `{1, 2}` is a list of numbers, `{1, "a"}` stays a tuple
+      Type::Table(table) => match &table.tuple {
+        Some(tuple) if tuple.windows(2).all(|pair| pair[0] == pair[1]) => {
+          let mut table = table.clone();
+          table.array = table.tuple.take().map(|mut tuple| Box::new(tuple.remove(0)));
+          Type::Table(table)
+        }
+        _ => self.clone(),
+      },
+      _ => self.clone(),
+    }
+  }
+
   pub fn is_group(&self) -> bool {
     matches!(self, Type::Group(_))
   }
@@ -115,15 +212,24 @@ impl Type {
   pub fn new_group(types: Vec<Type>) -> Self {
     Type::Group(GroupType { types })
   }
-  pub fn new_table(array: Option<HashSet<Type>>, map: Option<BTreeMap<String, Type>>) -> Self {
-    Type::Table(TableType { array, map })
+  pub fn new_table(array: Option<Type>, map: Option<BTreeMap<String, Type>>) -> Self {
+    Type::Table(TableType { array: array.map(Box::new), map, ..Default::default() })
   }
   pub fn new_function(params: Vec<Type>, return_type: Type) -> Self {
-    Type::Function(FunctionType { params, return_type: Box::new(return_type) })
+    Type::Function(FunctionType { generics: vec![], params, return_type: Box::new(return_type) })
+  }
+  pub fn new_generic_function(generics: Vec<String>, params: Vec<Type>, return_type: Type) -> Self {
+    Type::Function(FunctionType { generics, params, return_type: Box::new(return_type) })
+  }
+  pub fn new_overload(functions: Vec<FunctionType>) -> Self {
+    Type::Overload(OverloadType { functions })
   }
   pub fn new_union(types: Vec<Type>) -> Self {
     Type::Union(UnionType { types })
   }
+  pub fn new_intersection(types: Vec<Type>, range: Range) -> Self {
+    Type::Intersection(IntersectionType { types, range })
+  }
   pub fn new_option(inner_type: Type) -> Self {
     Type::Option(OptionType { inner_type: Box::new(inner_type) })
   }
@@ -144,15 +250,35 @@ impl Type {
       | (Type::String, Type::String)
       | (Type::Boolean, Type::Boolean)
       | (Type::Nil, Type::Nil)
-      | (Type::Unknown, Type::Unknown)
-      | (Type::Unknown, _)
-      | (_, Type::Unknown) => true,
+      | (Type::Any, _)
+      | (_, Type::Any) => true,
+      // never fits anywhere, nothing fits never
+      (_, Type::Never) => true,
+      (Type::Never, _) => false,
+      // anything fits unknown, unknown fits nothing else
+      (Type::Unknown, _) => true,
+      (_, Type::Unknown) => false,
+      // literals match the same literal, or their primitive type
+      (Type::Literal(left), Type::Literal(right)) => left == right,
+      (Type::Literal(literal), primitive @ (Type::String | Type::Number | Type::Boolean))
+      | (primitive @ (Type::String | Type::Number | Type::Boolean), Type::Literal(literal)) => {
+        literal.base_type() == *primitive
+      }
+
+      // type parameters only match themselves
+      (Type::Alias(left), Type::Alias(right)) => left.name == right.name,
+
       // table
       (Type::Table(left), Type::Table(right)) => check_match_table(left, right),
 
       // function
       (Type::Function(left), Type::Function(right)) => check_match_function(left, right),
 
+      // overload
+      (Type::Overload(left), Type::Overload(right)) => check_match_overload(left, right),
+      (Type::Overload(left), Type::Function(right)) => check_match_overload_with_function(left, right),
+      (Type::Function(left), Type::Overload(right)) => right.functions.iter().any(|r| check_match_function(left, r)),
+
       // generic
       (Type::Generic(left), Type::Generic(right)) => check_match_generic(left, right),
 
@@ -169,7 +295,7 @@ impl Type {
       // union
       (Type::Union(left), Type::Union(right)) => check_match_union(&left.types, &right.types),
       (Type::Union(left), right) => check_match_union_with_single_type(left, right),
-      (left, Type::Union(right)) => check_match_union_with_single_type(right, left),
+      (left, Type::Union(right)) => check_match_single_type_with_union(left, right),
 
       // variadic
       (Type::Variadic(left), Type::Variadic(right)) => check_match_variadic(left, right),
@@ -179,103 +305,8 @@ impl Type {
     }
   }
 
-  pub fn supports_operator(&self, operator: &BinaryOperator) -> bool {
-    if matches!(self, Type::Number) {
-      return operator.support_number();
-    }
-    if matches!(self, Type::String) {
-      return operator.support_string();
-    }
-    if matches!(self, Type::Boolean) {
-      return operator.support_boolean();
-    }
-    if matches!(self, Type::Nil) {
-      return operator.support_nil();
-    }
-    return matches!(self, Type::Unknown) || supports_stdlib_operator(self, operator);
-  }
-
-  pub fn suport_unary_operator(&self, operator: &UnaryOperator) -> bool {
-    if matches!(self, Type::Number) {
-      return operator.support_number();
-    }
-    if matches!(self, Type::String) {
-      return operator.support_string();
-    }
-    if matches!(self, Type::Boolean) {
-      return operator.support_boolean();
-    }
-    if matches!(self, Type::Nil) {
-      return operator.support_nil();
-    }
-    return matches!(self, Type::Unknown) || supports_stdlib_unary_operator(self, operator);
-  }
-
-  pub fn get_unary_operator_result_type(&self, operator: &UnaryOperator) -> Type {
-    use UnaryOperator::*;
-    match (self, operator) {
-      (Type::Number, Negate) => Type::Number,
-      (Type::Table(_) | Type::String, Hash) => Type::Number,
-      (_, Not) => Type::Boolean,
-      (Type::Unknown, _) => Type::Unknown,
-      _ => unreachable!("{} right: {:#?}", operator, self),
-    }
-  }
-
-  pub fn get_operator_result_type(&self, other: &Type, operator: &BinaryOperator) -> Type {
-    use BinaryOperator::*;
-    let result = get_operator_stdlib_result_type(self, operator);
-    if result.is_some() {
-      return result.unwrap();
-    }
-    // format!("operator: {:#?}", operator).as_str();
-
-    match operator {
-      // string concat
-      DoubleDot => match (self, other) {
-        (Type::String, Type::String)
-        | (Type::Number, Type::String)
-        | (Type::String, Type::Number)
-        | (Type::Unknown, Type::String)
-        | (Type::String, Type::Unknown)
-        | (Type::Unknown, Type::Unknown) => Type::String,
-        _ => unreachable!("left: {:#?} {} right: {:#?}", self, operator, other),
-      },
-      // math operators
-      Add | Subtract | Multiply | Divide | Modulus | DoubleSlash => match (self, other) {
-        (Type::Number, Type::Number)
-        | (Type::Number, Type::Unknown)
-        | (Type::Unknown, Type::Number)
-        | (Type::Unknown, Type::Unknown) => Type::Number,
-        _ => unreachable!("left: {:#?} {} right: {:#?}", self, operator, other),
-      },
-      // comparison operators
-      // println!("comparison operators");
-      Equal | NotEqual | LessThan | GreaterThan | LessThanOrEqual | GreaterThanOrEqual => match (self, other) {
-        (Type::Number, Type::Number)
-        | (Type::String, Type::String)
-        | (Type::Boolean, Type::Boolean)
-        | (Type::Nil, _)
-        | (_, Type::Nil)
-        | (_, Type::Unknown)
-        | (Type::Unknown, _) => Type::Boolean,
-        _ => unreachable!("left: {:#?} {} right: {:#?}", self, operator, other),
-      },
-      //  logical operators
-      And | Or => match (self, other) {
-        (Type::Boolean, Type::Boolean)
-        | (_, Type::Nil)
-        | (Type::Nil, _)
-        | (Type::Unknown, Type::Boolean)
-        | (Type::Boolean, Type::Unknown)
-        | (Type::Unknown, Type::Unknown) => Type::Boolean,
-        _ => unreachable!("left: {:#?} {} right: {:#?}", self, operator, other),
-      },
-    }
-  }
-
   pub fn can_replace(&self, replaced: &Type) -> bool {
-    matches!(self, Type::Unknown) || !matches!(replaced, Type::Unknown)
+    matches!(self, Type::Any) || !matches!(replaced, Type::Any)
   }
 
   pub fn same_group_length(&self, other: &Type) -> bool {
@@ -287,59 +318,71 @@ impl Type {
   }
 }
 
-fn supports_stdlib_operator(left: &Type, operator: &BinaryOperator) -> bool {
-  matches!(left, Type::Option(_) | Type::Union(_))
-    && matches!(operator, BinaryOperator::Equal | BinaryOperator::NotEqual | BinaryOperator::DoubleDot)
-}
+impl TableType {
+  pub fn get_type(&self, key: &str) -> Option<&Type> {
+    self.map.as_ref()?.get(key)
+  }
 
-fn supports_stdlib_unary_operator(left: &Type, operator: &UnaryOperator) -> bool {
-  // tables
-  if matches!(left, Type::Table(_)) {
-    return matches!(operator, UnaryOperator::Not | UnaryOperator::Hash);
+  // the types of the values at positions 1..n
+  pub fn element_types(&self) -> Vec<Type> {
+    let mut types: Vec<Type> = self.array.iter().map(|array| *array.clone()).collect();
+    for element in self.tuple.iter().flatten() {
+      if !types.contains(element) {
+        types.push(element.clone());
+      }
+    }
+    types
   }
 
-  if matches!(left, Type::Option(_)) {
-    return matches!(operator, UnaryOperator::Not);
+  // reading an optional field may give nil
+  pub fn get_field_type(&self, key: &str) -> Option<Type> {
+    let field_type = self.get_type(key)?;
+    if self.is_optional(key) && !matches!(field_type, Type::Option(_)) {
+      return Some(Type::new_option(field_type.clone()));
+    }
+    Some(field_type.clone())
   }
 
-  return false;
-}
+  pub fn is_optional(&self, key: &str) -> bool {
+    self.optional.contains(key)
+  }
 
-fn get_operator_stdlib_result_type(left: &Type, operator: &BinaryOperator) -> Option<Type> {
-  if matches!(left, Type::Union(_)) {
-    if matches!(operator, BinaryOperator::Equal | BinaryOperator::NotEqual) {
-      return Some(Type::Boolean);
-    }
-    if matches!(operator, BinaryOperator::DoubleDot) {
-      return Some(Type::String);
-    }
+  pub fn is_readonly(&self, key: &str) -> bool {
+    self.readonly.contains(key)
   }
 
-  if matches!(left, Type::Union(_)) {
-    if matches!(operator, BinaryOperator::Equal | BinaryOperator::NotEqual) {
-      return Some(Type::Boolean);
-    }
-    if matches!(operator, BinaryOperator::DoubleDot) {
-      return Some(Type::String);
-    }
+  // `__add`, `__index` and the other fields of the metatable
+  pub fn get_metamethod(&self, name: &str) -> Option<&Type> {
+    self.metatable.as_ref()?.get_type(name)
   }
 
-  if matches!(left, Type::Table(_)) {
-    if matches!(operator, BinaryOperator::Equal | BinaryOperator::NotEqual) {
-      return Some(Type::Boolean);
+  // the table `__index` looks members up in, for an instance the table of its class
+  pub fn get_index_table(&self) -> Option<&TableType> {
+    match self.get_metamethod("__index")? {
+      Type::Table(index_table) => Some(index_table),
+      _ => None,
     }
+  }
 
-    if matches!(operator, BinaryOperator::DoubleDot) {
-      return Some(Type::String);
+  // an instance of `Dog` inherits from `Animal` when `Dog extends Animal`, directly or not
+  pub fn inherits(&self, base: &TableType) -> bool {
+    let Some(base_class) = base.get_index_table() else {
+      return false;
+    };
+    let mut class = self.get_index_table().and_then(TableType::get_index_table);
+    while let Some(current) = class {
+      if current == base_class {
+        return true;
+      }
+      class = current.get_index_table();
     }
+    false
   }
-
-  return None;
 }
 
-impl TableType {
-  pub fn get_type(&self, key: &str) -> Option<&Type> {
-    self.map.as_ref()?.get(key)
+impl IndexType {
+  pub fn new(key: Type, value: Type) -> Self {
+    IndexType { key: Box::new(key), value: Box::new(value) }
   }
 }
 
@@ -416,6 +459,22 @@ impl Hash for UnionType {
   }
 }
 
+// `A & B`, a table with the fields of both
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntersectionType {
+  pub types: Vec<Type>,
+  pub range: Range,
+}
+
+impl Hash for IntersectionType {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    state.write_u8(55);
+    for type_ in &self.types {
+      type_.hash(state);
+    }
+  }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OptionType {
   pub inner_type: Box<Type>,
@@ -429,6 +488,9 @@ impl Hash for OptionType {
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FunctionType {
+  // type parameters, inferred from the arguments at each call
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub generics: Vec<String>,
   pub params: Vec<Type>,
   pub return_type: Box<Type>,
 }
@@ -436,6 +498,7 @@ pub struct FunctionType {
 impl Hash for FunctionType {
   fn hash<H: Hasher>(&self, state: &mut H) {
     state.write_u8(70);
+    self.generics.hash(state);
     for type_ in &self.params {
       type_.hash(state);
     }
@@ -443,6 +506,21 @@ impl Hash for FunctionType {
   }
 }
 
+// `function(x: number): number & function(x: string): string`, a call picks the first signature that fits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverloadType {
+  pub functions: Vec<FunctionType>,
+}
+
+impl Hash for OverloadType {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    state.write_u8(75);
+    for function in &self.functions {
+      function.hash(state);
+    }
+  }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct GenericType {
   pub name: String,
@@ -0,0 +1,124 @@
+use super::{LiteralType, TableType, Type};
+use crate::ast::ast::{BinaryOperator, UnaryOperator};
+
+// what an operand has to be, every member of a union or an option has to be it
+#[derive(Debug, Clone, Copy)]
+enum Operand {
+  Number,
+  String,
+  // lua turns a number into a string on `..`
+  Text,
+  Table,
+  Value,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Output {
+  Number,
+  String,
+  Boolean,
+  // `a and b` and `a or b` give one of their operands
+  Operand,
+}
+
+struct BinarySignature {
+  left: Operand,
+  right: Operand,
+  output: Output,
+}
+
+struct UnarySignature {
+  operand: Operand,
+  output: Output,
+}
+
+const fn binary(left: Operand, right: Operand, output: Output) -> BinarySignature {
+  BinarySignature { left, right, output }
+}
+
+const fn unary(operand: Operand, output: Output) -> UnarySignature {
+  UnarySignature { operand, output }
+}
+
+const ARITHMETIC: [BinarySignature; 1] = [binary(Operand::Number, Operand::Number, Output::Number)];
+const CONCAT: [BinarySignature; 1] = [binary(Operand::Text, Operand::Text, Output::String)];
+const ORDER: [BinarySignature; 2] = [
+  binary(Operand::Number, Operand::Number, Output::Boolean),
+  binary(Operand::String, Operand::String, Output::Boolean),
+];
+const EQUALITY: [BinarySignature; 1] = [binary(Operand::Value, Operand::Value, Output::Boolean)];
+const LOGICAL: [BinarySignature; 1] = [binary(Operand::Value, Operand::Value, Output::Operand)];
+
+const NUMERIC: [UnarySignature; 1] = [unary(Operand::Number, Output::Number)];
+const LENGTH: [UnarySignature; 2] = [unary(Operand::String, Output::Number), unary(Operand::Table, Output::Number)];
+const NEGATION: [UnarySignature; 1] = [unary(Operand::Value, Output::Boolean)];
+
+fn binary_signatures(operator: &BinaryOperator) -> &'static [BinarySignature] {
+  use BinaryOperator::*;
+  match operator {
+    Add | Subtract | Multiply | Divide | Modulus | DoubleSlash | Power => &ARITHMETIC,
+    // lua converts both operands to integers
+    BitwiseAnd | BitwiseOr | BitwiseXor | ShiftLeft | ShiftRight => &ARITHMETIC,
+    DoubleDot => &CONCAT,
+    LessThan | GreaterThan | LessThanOrEqual | GreaterThanOrEqual => &ORDER,
+    Equal | NotEqual => &EQUALITY,
+    And | Or => &LOGICAL,
+  }
+}
+
+fn unary_signatures(operator: &UnaryOperator) -> &'static [UnarySignature] {
+  match operator {
+    UnaryOperator::Negate | UnaryOperator::BitwiseNot => &NUMERIC,
+    UnaryOperator::Hash => &LENGTH,
+    UnaryOperator::Not => &NEGATION,
+  }
+}
+
+fn accepts(operand: Operand, value: &Type) -> bool {
+  match value {
+    Type::Any | Type::Never => true,
+    // an unknown value has to be narrowed first, it can only be compared
+    Type::Unknown => matches!(operand, Operand::Value),
+    Type::Literal(literal) => accepts(operand, &literal.base_type()),
+    Type::Option(option) => accepts(operand, &Type::Nil) && accepts(operand, &option.inner_type),
+    Type::Union(union) => union.types.iter().all(|member| accepts(operand, member)),
+    _ => match operand {
+      Operand::Number => matches!(value, Type::Number),
+      Operand::String => matches!(value, Type::String),
+      Operand::Text => matches!(value, Type::Number | Type::String),
+      Operand::Table => matches!(value, Type::Table(_)),
+      Operand::Value => true,
+    },
+  }
+}
+
+fn output_type(output: Output, left: &Type, right: &Type) -> Type {
+  match output {
+    Output::Number => Type::Number,
+    Output::String => Type::String,
+    Output::Boolean => Type::Boolean,
+    Output::Operand if left == right => left.clone(),
+    Output::Operand => Type::new_union(vec![left.clone(), right.clone()]),
+  }
+}
+
+impl Type {
+  // `None` when no signature of the operator takes both operands
+  pub fn get_operator_result_type(&self, other: &Type, operator: &BinaryOperator) -> Option<Type> {
+    binary_signatures(operator)
+      .iter()
+      .find(|signature| accepts(signature.left, self) && accepts(signature.right, other))
+      .map(|signature| output_type(signature.output, self, other))
+  }
+
+  pub fn get_unary_operator_result_type(&self, operator: &UnaryOperator) -> Option<Type> {
+    // the length of a tuple is known
+    if let (Type::Table(TableType { tuple: Some(tuple), .. }), UnaryOperator::Hash) = (self, operator) {
+      return Some(Type::Literal(LiteralType::Number(tuple.len().to_string())));
+    }
+    unary_signatures(operator)
+      .iter()
+      .find(|signature| accepts(signature.operand, self))
+      .map(|signature| output_type(signature.output, self, self))
+  }
+}
@@ -6,9 +6,6 @@ use std::{
 };
 pub mod range;
 
-pub fn match_number(character: char) -> bool {
-  "1234567890.".contains(character)
-}
 pub fn highlight_text_with_red(text: &str) -> String {
   format!("\x1b[31m{}\x1b[0m", text)
 }
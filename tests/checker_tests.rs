@@ -0,0 +1,372 @@
+use stella_checker::checker::Checker;
+use stella_checker::diagnostics::DiagnosticLevel;
+use stella_checker::parser::parser::Parser;
+
+// the messages of the errors the checker reports for `source_code`
+fn check_errors(source_code: &str) -> Vec<String> {
+  check_errors_with(source_code, false)
+}
+
+fn check_errors_with(source_code: &str, unchecked_index: bool) -> Vec<String> {
+  let mut parser = Parser::new(source_code, "test.lua");
+  let (program, syntax_errors) = parser.parse_program();
+  assert!(syntax_errors.is_empty(), "{:?}", syntax_errors);
+  let mut checker = Checker::new("test.lua", source_code);
+  checker.unchecked_index = unchecked_index;
+  checker.check_program(&program);
+  let errors =
+    checker.diagnostics.diagnostics.into_iter().filter(|diagnostic| diagnostic.level == DiagnosticLevel::Error);
+  errors.map(|diagnostic| diagnostic.message).collect()
+}
+
+fn assert_no_errors(source_code: &str) {
+  let errors = check_errors(source_code);
+  assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+}
+
+fn assert_error(source_code: &str, message: &str) {
+  let errors = check_errors(source_code);
+  assert!(errors.iter().any(|error| error == message), "expected `{}`, found {:?}", message, errors);
+}
+
+#[test]
+fn test_records_accept_tables_with_more_fields() {
+  assert_no_errors(
+    r#"
+    local function greet(person: { name: string }): string
+      return person.name
+    end
+    local ana = { name = "ana", age = 3 }
+    local nested = { inner = { x = 1, y = 2 } }
+    local inner: { inner: { x: number } } = nested
+    local mixed = { 1, 2, name = "mixed" }
+    print(greet(ana), inner.inner.x, greet(mixed), greet({ 1, 2, name = "x" }))
+    "#,
+  );
+}
+
+#[test]
+fn test_table_literals_cannot_have_unknown_fields() {
+  let unknown_argument = r#"
+    local function greet(person: { name: string }): string
+      return person.name
+    end
+    print(greet({ name = "ana", age = 3 }))
+  "#;
+  assert_error(unknown_argument, "unknown field `age`, not declared in `table<name: string>`");
+  let unknown_nested = r#"local p: { inner: { x: number } } = { inner = { x = 1, y = 2 } }"#;
+  assert_error(unknown_nested, "unknown field `y`, not declared in `table<x: number>`");
+}
+
+#[test]
+fn test_method_receiver_is_the_enclosing_table() {
+  let source_code = r#"
+    local Account = { balance = 0 }
+    function Account:deposit(amount: number)
+      self.balance = self.balance + amount
+    end
+    function Account:withdraw(amount: number)
+      self.balance = self.balance - amount
+    end
+    Account:deposit(10)
+    Account.withdraw(Account, 5)
+  "#;
+  assert_no_errors(source_code);
+  let wrong_receiver = format!("{}\nAccount.deposit(5, 1)", source_code);
+  assert_error(&wrong_receiver, "expected `table<balance: number>`, found `5`");
+}
+
+#[test]
+fn test_string_methods_come_from_the_string_library() {
+  assert_no_errors(
+    r#"
+    local name = "stella"
+    local upper: string = name:upper()
+    local size: number = ("lua"):len()
+    print(upper, size, name:sub(1, 3))
+    "#,
+  );
+  assert_error(
+    r#"local name = "stella"
+print(name:upper(1))"#,
+    "expected 0 args, found 1",
+  );
+}
+
+#[test]
+fn test_unions_match_by_membership() {
+  assert_no_errors(
+    r#"
+    local function pick(flag: boolean): "a" | "b"
+      if flag then return "a" end
+      return "b"
+    end
+    local reordered: "b" | "a" = pick(true)
+    local wider: "a" | "b" | "c" = pick(false)
+    local maybe: option<string> = pick(true)
+    print(reordered, wider, maybe)
+    "#,
+  );
+  let narrower = r#"
+    local function pick(flag: boolean): "a" | "b" | "c"
+      if flag then return "a" end
+      return "c"
+    end
+    local narrow: "a" | "b" = pick(true)
+  "#;
+  assert!(!check_errors(narrower).is_empty());
+}
+
+#[test]
+fn test_union_fits_single_type_only_when_every_member_does() {
+  let source_code = r#"
+    local function pick(flag: boolean): number | string
+      if flag then return 1 end
+      return "one"
+    end
+    local n: number = pick(true)
+  "#;
+  assert!(!check_errors(source_code).is_empty());
+  assert_no_errors(
+    r#"
+    local function pick(flag: boolean): number | string
+      if flag then return 1 end
+      return "one"
+    end
+    local value: any = pick(true)
+    print(value)
+    "#,
+  );
+}
+
+#[test]
+fn test_compound_assignment_reads_index_signature_values_as_absent() {
+  let source_code = r#"
+    local scores: { [string]: number } = {}
+    scores["ana"] += 5
+    scores.bob += 5
+  "#;
+  let errors = check_errors(source_code);
+  let absent = "cannot add `option<number>` and `number`";
+  assert_eq!(errors.iter().filter(|error| *error == absent).count(), 2, "{:?}", errors);
+  let unchecked = check_errors_with(source_code, true);
+  assert!(unchecked.is_empty(), "unexpected errors: {:?}", unchecked);
+}
+
+#[test]
+fn test_assignment_keeps_the_declared_type() {
+  assert_no_errors(
+    r#"
+    local x: option<number> = 1
+    x = nil
+    x = 5
+    local function reset(n: number)
+      local y: option<number> = n
+      if n > 1 then
+        y = nil
+      end
+      y = 5
+      print(y)
+    end
+    reset(2)
+    print(x)
+    "#,
+  );
+  assert_error(
+    r#"
+    local x: option<number> = 1
+    x = nil
+    x = "five"
+    "#,
+    "expected `option<number>`, found `\"five\"`",
+  );
+}
+
+#[test]
+fn test_assignment_narrows_the_declared_type() {
+  assert_error(
+    r#"
+    local x: option<number> = 1
+    x = nil
+    local y: number = x
+    "#,
+    "expected `number`, found `nil`",
+  );
+}
+
+#[test]
+fn test_loop_assignments_invalidate_narrowings() {
+  let source_code = r#"
+    local function drain(x: option<number>, n: number)
+      if x ~= nil then
+        while n > 0 do
+          local a: number = x
+          x = nil
+          n = n - 1
+          print(a)
+        end
+      end
+    end
+  "#;
+  assert_error(source_code, "expected `number`, found `option<number>`");
+}
+
+#[test]
+fn test_metamethods_can_be_assigned_to_an_empty_table() {
+  assert_no_errors(
+    r#"
+    local Vec = {}
+    Vec.__index = Vec
+    Vec.__add = function(a: { x: number }, b: { x: number }): { x: number }
+      return { x = a.x + b.x }
+    end
+    local sum = setmetatable({ x = 1 }, Vec) + setmetatable({ x = 2 }, Vec)
+    print(sum.x)
+    "#,
+  );
+  assert_error(
+    r#"
+    local Vec = {}
+    Vec.size = 1
+    "#,
+    "field `size` not found in `table`",
+  );
+}
+
+#[test]
+fn test_class_method_named_init_is_not_the_constructor() {
+  assert_no_errors(
+    r#"
+    class Door
+      label: string
+
+      function new(label: string)
+        self.label = label
+      end
+
+      function init(): string
+        return "reset"
+      end
+    end
+    local door = Door.new("front")
+    local state: string = door:init()
+    print(state)
+    "#,
+  );
+}
+
+#[test]
+fn test_never_function_that_can_return() {
+  assert_error(
+    r#"
+    function maybe(b: boolean): never
+      if b then
+        error("x")
+      end
+    end
+    "#,
+    "function declared `never` can return",
+  );
+  assert_no_errors(
+    r#"
+    function fail(message: string): never
+      error(message)
+    end
+    "#,
+  );
+}
+
+#[test]
+fn test_member_of_a_call_without_values_is_not_a_table() {
+  assert_error(r#"print("x").y = 1"#, "expected table, found `nil`");
+  assert_error(r#"local y = print("x").y"#, "expected table, found `nil`");
+}
+
+#[test]
+fn test_option_members_of_table_types_are_resolved() {
+  assert_no_errors(
+    r#"
+    local x: option<number> = nil
+    local p: { a: option<number> } = { a = 1 }
+    local q: { a: option<number> } = { a = nil }
+    local r: { a: option<number> } = { a = x }
+    local l: { option<number> } = { 1, 2 }
+    local d: { [string]: option<number> } = {}
+    d.k = 1
+    d.k = nil
+    type Node = { value: number, next: option<Node> }
+    local n: Node = { value = 1, next = nil }
+    class Point
+      x: option<number>
+
+      function new()
+        self.x = nil
+      end
+    end
+    print(p, q, r, l, n, Point.new())
+    "#,
+  );
+}
+
+#[test]
+fn test_compound_assignment_to_readonly_fields() {
+  let source_code = r#"
+    local t: { readonly x: number } = { x = 1 }
+    t.x += 1
+  "#;
+  assert_error(source_code, "cannot assign to `x`, it is a readonly field");
+  assert_error(
+    r#"
+    local t: { readonly x: number } = { x = 1 }
+    t["x"] += 1
+    "#,
+    "cannot assign to `x`, it is a readonly field",
+  );
+}
+
+#[test]
+fn test_compound_assignment_narrows_the_declared_type() {
+  assert_no_errors(
+    r#"
+    local function label(x: number | string): string
+      if type(x) == "number" then
+        x ..= "a"
+        local text: string = x
+        return text
+      end
+      return "b"
+    end
+    print(label(1))
+    "#,
+  );
+  assert_error(
+    r#"
+    local count: number = 1
+    count ..= "a"
+    "#,
+    "expected `number`, found `string`",
+  );
+}
+
+#[test]
+fn test_logical_operators_split_booleans() {
+  assert_no_errors(
+    r#"
+    local function pick(b: boolean): number
+      local flag: boolean = b and true or false
+      local both: boolean = b and b
+      print(flag, both)
+      return b and 1 or 2
+    end
+    print(pick(true))
+    "#,
+  );
+  assert_error(
+    r#"
+    local function pick(b: boolean)
+      local n: number = b or 1
+    end
+    "#,
+    "expected `number`, found `union<true, 1>`",
+  );
+}
@@ -0,0 +1,86 @@
+use rlua::Lua;
+use stella_checker::parser::parser::Parser;
+
+// the lines printed by `source_code` once it's lowered to lua
+fn run_emitted(source_code: &str) -> Vec<String> {
+  let mut parser = Parser::new(source_code, "test.lua");
+  let (program, syntax_errors) = parser.parse_program();
+  assert!(syntax_errors.is_empty(), "{:?}", syntax_errors);
+  let lua = Lua::new();
+  lua.load("output = {} print = function(...) output[#output + 1] = table.concat({...}, ' ') end").exec().unwrap();
+  let raw = program.emit();
+  if let Err(error) = lua.load(&raw).exec() {
+    panic!("{}\n{}", error, raw);
+  }
+  let output: Vec<String> = lua.globals().get("output").unwrap();
+  output
+}
+
+#[test]
+fn test_repeat_with_continue_keeps_the_locals_of_the_condition() {
+  let output = run_emitted(
+    r#"
+    local n = 0
+    repeat
+      n = n + 1
+      local next = n * 2
+      if next == 4 then
+        continue
+      end
+      print(next)
+    until next >= 8
+    "#,
+  );
+  assert_eq!(output, vec!["2", "6", "8"]);
+}
+
+#[test]
+fn test_repeat_local_initializer_reads_the_outer_local() {
+  let output = run_emitted(
+    r#"
+    local i = 0
+    repeat
+      local i = i + 1
+      if i == 1 then
+        print("skipped")
+        continue
+      end
+      print(i)
+    until i > 0
+    print(i)
+    "#,
+  );
+  assert_eq!(output, vec!["skipped", "0"]);
+}
+
+#[test]
+fn test_class_method_named_init_keeps_the_constructor() {
+  let output = run_emitted(
+    r#"
+    class Door
+      label: string
+
+      function new(label: string)
+        self.label = label
+      end
+
+      function init(): string
+        return "reset"
+      end
+    end
+    local door = Door.new("front")
+    print(door.label, door:init())
+    "#,
+  );
+  assert_eq!(output, vec!["front reset"]);
+}
+
+#[test]
+fn test_byte_escapes_that_are_not_utf8_keep_their_bytes() {
+  let output = run_emitted(
+    r#"
+    print(#"\xFF", #"\200", #"a\xC3b", #"\u{48}i")
+    "#,
+  );
+  assert_eq!(output, vec!["1 1 3 2"]);
+}
@@ -0,0 +1,41 @@
+class Animal
+  name: string
+  readonly legs: number
+  nickname?: string
+
+  function new(name: string, legs: number)
+    self.name = name
+    self.legs = legs
+  end
+
+  function speak(): string
+    return self.name .. " makes a sound"
+  end
+
+  function describe(): string
+    return self.name .. " has " .. self.legs .. " legs"
+  end
+end
+
+class Dog extends Animal
+  breed: string
+
+  function new(name: string, breed: string)
+    super(name, 4)
+    self.breed = breed
+  end
+
+  function speak(): string
+    return self.name .. " barks"
+  end
+end
+
+function introduce(animal: Animal): string
+  return animal:speak()
+end
+
+local cat = Animal.new("Tom", 4)
+local rex = Dog.new("Rex", "collie")
+local pet: Animal = rex
+rex.nickname = "R"
+print(introduce(cat), introduce(rex), rex:describe(), rex.breed, pet.name, rex.nickname)
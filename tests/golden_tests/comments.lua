@@ -0,0 +1,12 @@
+-- line comment
+--[[ block comment ]]
+--[[
+block comment on
+multiple lines
+--]]
+--[==[
+long comment with ]] and ]=] inside
+]==]
+--[= not a long comment, just a line comment
+local value = 1 -- trailing comment
+--[[ inline ]] local other = 2
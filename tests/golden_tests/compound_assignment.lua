@@ -0,0 +1,11 @@
+local count = 0
+count += 1
+count -= 2
+count *= 3 + 4
+count /= 2
+local name = "stella"
+name ..= "!"
+local scores = {10, 20}
+scores[1] += 5
+local player = {stats = {level = 1}}
+player.stats.level += 1
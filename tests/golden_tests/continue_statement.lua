@@ -0,0 +1,28 @@
+local total = 0
+for i = 1, 10 do
+  if i % 2 == 0 then
+    continue
+  end
+  total += i
+end
+for _, value in ipairs({1, 2, 3}) do
+  if value == 2 then
+    continue
+  end
+  total += value
+end
+local n = 0
+repeat
+  n += 1
+  if n < 3 then
+    continue
+  end
+  local done = n >= 5
+until done
+local count = 0
+repeat
+  local count = count + 1
+  if count == 1 then
+    continue
+  end
+until count > 0
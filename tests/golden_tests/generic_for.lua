@@ -0,0 +1,36 @@
+local names = {"stella", "lua"}
+
+for index, name in ipairs(names) do
+  print(index, name)
+end
+
+for key, value in pairs(names) do
+  print(key, value)
+end
+
+for key: number, value in next, names do
+  print(key, value)
+end
+
+local function range(n: number): function(): option<number>
+  local i = 0
+  return function(): option<number>
+    i = i + 1
+    if i <= n then
+      return i
+    end
+    return nil
+  end
+end
+
+for value in range(3) do
+  print(value + 1)
+end
+
+local function fail(): never
+  error("no iterator")
+end
+
+for value in fail() do
+  print(value)
+end
@@ -0,0 +1,23 @@
+function map<T, U>(xs: {T}, f: function(x: T): U): {U}
+  local result = {}
+  for i, x in ipairs(xs) do
+    result[i] = f(x)
+  end
+  return result
+end
+
+function identity<T>(x: T): T
+  local y: T = x
+  return y
+end
+
+local n: number = identity(1)
+local names: {string} = map({1, 2, 3}, function(x: number): string
+  return "n" .. x
+end)
+
+local first = function<T>(a: T, b: T): T
+  return a
+end
+
+print(n, names[1], first("a", "b"))
@@ -0,0 +1,23 @@
+for i = 1, 3 do
+  for j = 1, 3 do
+    if j == 2 then
+      goto next_j
+    end
+    print(i, j)
+    ::next_j::
+  end
+end
+
+local n = 0
+::top::
+n = n + 1
+if n < 3 then
+  goto top
+end
+
+while true do
+  goto done
+  local unreachable = 1
+  print(unreachable)
+  ::done::
+end
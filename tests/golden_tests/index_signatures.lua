@@ -0,0 +1,21 @@
+type Scores = {[string]: number}
+
+local scores: Scores = {}
+local name = "ana"
+scores[name] = 10
+scores["bob"] = 7
+scores.carl = 3
+scores.carl = nil
+
+function score_of(table: Scores, key: string): option<number>
+  return table[key]
+end
+
+local found = scores["ana"]
+print(score_of(scores, "ana"), score_of(scores, "carl"), found, scores.bob)
+
+local ages: {[string]: number} = { ana = 3, ["bob"] = 7 }
+local names: {[number]: string} = { "ana", "bob" }
+local labels: {[number]: string} = { [1] = "one", [2] = "two" }
+local wrong: {[string]: number} = { ana = "three" }
+print(ages.ana, names[1], labels[2])
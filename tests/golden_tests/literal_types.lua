@@ -0,0 +1,26 @@
+type Dir = "up" | "down" | "left"
+
+enum Color { Red, Green, Blue }
+enum Mode { Read = "r", Write = "w" }
+
+local d: Dir = "up"
+local m: Mode = Mode.Read
+local c: Color = Color.Green
+
+function move(dir: Dir): number
+  if dir == "up" then
+    local only: "up" = dir
+    return 1
+  elseif dir == "down" then
+    return 2
+  else
+    local rest: "left" = dir
+    return 3
+  end
+end
+
+local s = "x"
+if s == "x" then
+  s = "y"
+end
+print(move(d), m, c, Color.Blue, s)
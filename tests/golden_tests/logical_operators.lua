@@ -0,0 +1,20 @@
+function pick(opt: option<number>): number
+  local x = opt or 0
+  return x
+end
+
+function name(n: option<string>): string
+  return n or "anonymous"
+end
+
+local a = 5 or "x"
+local b: number = a
+local t = { size = 3 }
+local c = t and t.size
+local d: option<number> = nil
+local e = d and d + 1
+local f: option<number> = e
+local g = true and 1
+local h = false or "s"
+local i = (d or "none")
+print(pick(nil), name(nil), b, c, f, g, h, i)
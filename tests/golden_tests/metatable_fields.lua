@@ -0,0 +1,14 @@
+local Vec = {}
+Vec.__index = Vec
+Vec.__add = function(a: { x: number, y: number }, b: { x: number, y: number }): { x: number, y: number }
+  return { x = a.x + b.x, y = a.y + b.y }
+end
+
+function Vec.__eq(a: { x: number, y: number }, b: { x: number, y: number }): boolean
+  return a.x == b.x and a.y == b.y
+end
+
+local a = setmetatable({ x = 1, y = 2 }, Vec)
+local b = setmetatable({ x = 3, y = 4 }, Vec)
+local c = a + b
+print(c.x, c.y, a == b)
@@ -0,0 +1,49 @@
+type Vector = { x: number, y: number }
+
+local methods = {
+  dot = function(a: Vector, b: Vector): number
+    return a.x * b.x + a.y * b.y
+  end,
+}
+
+local VectorMeta = {
+  __index = methods,
+  __add = function(a: Vector, b: Vector): Vector
+    return { x = a.x + b.x, y = a.y + b.y }
+  end,
+  __eq = function(a: Vector, b: Vector): boolean
+    return a.x == b.x and a.y == b.y
+  end,
+  __lt = function(a: Vector, b: Vector): boolean
+    return a.x < b.x
+  end,
+  __unm = function(a: Vector): Vector
+    return { x = -a.x, y = -a.y }
+  end,
+  __len = function(a: Vector): number
+    return a.x + a.y
+  end,
+  __concat = function(a: Vector, b: string): string
+    return a.x .. "," .. a.y .. b
+  end,
+  __call = function(a: Vector, scale: number): Vector
+    return { x = a.x * scale, y = a.y * scale }
+  end,
+}
+
+local v = setmetatable({ x = 1, y = 2 }, VectorMeta)
+local w: Vector = v + v
+local n: number = v:dot(w)
+local same: boolean = v == v
+local smaller: boolean = v > v
+local flipped: Vector = -v
+local size: number = #v
+local text: string = v .. "!"
+local scaled: Vector = v(3)
+local defaults = setmetatable({}, {
+  __index = function(t: {}, key: string): number
+    return #t + #key
+  end,
+})
+local missing: number = defaults.anything
+print(w.x, n, same, smaller, flipped.x, size, text, scaled.y, missing)
@@ -0,0 +1,19 @@
+local Account = {}
+
+function Account.new(): number
+  return 0
+end
+
+function Account:deposit(amount: number): number
+  return amount + 1
+end
+
+function app.config.load(path: string): string
+  return path
+end
+
+local total = Account:deposit(10)
+print(Account.new(), app.config:load("stella"))
+
+local name = "stella"
+print(name:upper(), name:sub(1, 3), ("lua"):len())
@@ -0,0 +1,23 @@
+function pair(): (number, string)
+  return 1, "one"
+end
+
+function four(): (number, string, number, string)
+  return 1, "two", pair()
+end
+
+local a, b = pair()
+local c, d, e = pair(), true
+local f = pair()
+local g, h, i = 1
+
+x, y = pair()
+local t = {1, 2}
+t[1], t[2] = t[2], t[1]
+
+function take(n: number, s: string, ok: boolean)
+  print(n, s, ok)
+end
+
+take(1, "one", true)
+take(pair(), "two", false)
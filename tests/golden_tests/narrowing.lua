@@ -0,0 +1,60 @@
+type Shape = "circle" | "square" | "triangle"
+
+function describe(value: option<number>): number
+  if value then
+    return value + 1
+  end
+  return 0
+end
+
+function not_nil(value: option<string>): string
+  if value ~= nil then
+    return value .. "!"
+  else
+    return "none"
+  end
+end
+
+function early(value: option<number>): number
+  if not value then
+    return 0
+  end
+  return value * 2
+end
+
+function kind(value: string | number): string
+  if type(value) == "string" then
+    return value .. "?"
+  elseif type(value) == "number" then
+    return "n" .. (value + 1)
+  end
+  return "never"
+end
+
+function both(a: option<number>, b: option<number>): number
+  if a ~= nil and b ~= nil then
+    return a + b
+  end
+  if a == nil or b == nil then
+    return 0
+  end
+  return a - b
+end
+
+function sides(shape: Shape): number
+  if shape == "circle" then
+    return 0
+  elseif shape == "square" then
+    return 4
+  else
+    local last: "triangle" = shape
+    return #last
+  end
+end
+
+function guarded(value: option<number>): boolean
+  return value ~= nil and value > 10
+end
+
+print(describe(1), describe(nil), not_nil("a"), not_nil(nil), early(4), kind("x"), kind(2))
+print(both(1, 2), both(nil, 2), sides("square"), guarded(11), guarded(nil))
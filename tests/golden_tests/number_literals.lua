@@ -0,0 +1,13 @@
+local integer = 42
+local float = 3.14
+local leading_dot = .5
+local trailing_dot = 5.
+local exponent = 1e10
+local signed_exponent = 2.5E-3
+local positive_exponent = 6e+2
+local hex = 0xFF
+local hex_upper = 0XaBc
+local hex_float = 0x1.8p3
+local hex_exponent = 0xAp-2
+local hex_fraction = 0x.1
+local concat = 1 .. 2
@@ -0,0 +1,18 @@
+local a, b, c = 2, 3, 4
+
+local power = a ^ b ^ c
+local negative_power = -a ^ 2
+local power_of_negative = a ^ -b
+local floor_division = a // b
+local bitwise_and = a & b
+local bitwise_or = a | b
+local bitwise_xor = a ~ b
+local bitwise_not = ~a
+local shift_left = a << b
+local shift_right = a >> b
+local mixed_bitwise = a | b ~ c & a << b
+local shift_term = a << b + c
+local arithmetic = a + b * c // a % b ^ c
+local concat = "a" .. "b" .. "c"
+local comparison = a < b == true
+local logic = a == b or b ~= c and not a
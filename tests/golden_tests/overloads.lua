@@ -0,0 +1,42 @@
+function greet(name: string, greeting?: string): string
+  if greeting then
+    return greeting .. ", " .. name
+  end
+  return "hello, " .. name
+end
+
+function repeat_text(text: string, times: number = 2, separator = " "): string
+  return string.rep(text, times, separator)
+end
+
+print(greet("ana"), greet("ana", "hi"))
+print(repeat_text("ab"), repeat_text("ab", 3), repeat_text("ab", 3, "-"))
+
+type Parse = function(value: number): number & function(value: string, base?: number): option<number>
+
+local parse: Parse = tonumber
+local a: number = parse(10)
+local b = parse("ff", 16)
+print(a, b, tonumber("12"), tostring(a))
+
+local list = { 1, 2 }
+table.insert(list, 3)
+table.insert(list, 1, 0)
+print(#list, string.sub("hello", 2), string.sub("hello", 2, 3), math.random(1, 6) <= 6)
+
+class Counter
+  count: number
+
+  function new(start: number = 0)
+    self.count = start
+  end
+
+  function add(step?: number): number
+    self.count = self.count + (step or 1)
+    return self.count
+  end
+end
+
+local counter = Counter.new()
+counter:add()
+print(counter:add(5), Counter.new(10).count)
@@ -0,0 +1,17 @@
+type User = { name: string, age?: number, readonly id: number }
+
+local ana: User = { name = "ana", id = 1 }
+local bob: User = { name = "bob", age = 30, id = 2 }
+
+function describe(user: User): string
+  return user.name
+end
+
+function age_of(user: User): option<number>
+  return user.age
+end
+
+bob.age = 31
+bob.name = "robert"
+print(describe(ana), describe(bob), describe({ name = "carl", id = 3 }))
+print(age_of(ana), age_of(bob), bob.id)
@@ -0,0 +1,19 @@
+local double = "double \"quoted\""
+local single = 'single \'quoted\''
+local mixed = 'has "double" quotes inside'
+local empty = ''
+local escapes = "\a\b\f\n\r\t\v\\\"\'"
+local hex_escape = "\x41\x6a\x7A"
+local decimal_escape = "\65\066\0677"
+local unicode_escape = "\u{48}\u{E9}\u{1F600}"
+local skip_whitespace = "hello \z
+                         world"
+local escaped_newline = "line one\
+line two"
+local long = [[
+the first line break is skipped
+"quotes" and \escapes are raw]]
+local leveled = [==[contains ]] and ]=] but ends here]==]
+local empty_long = [[]]
+local list = {1, 2}
+local indexed = list[1]
@@ -0,0 +1,6 @@
+local function f() end
+f() = 1
+"a" = 1
+local x = 1
+x, f() = 1, 2
+x = 3
@@ -0,0 +1,5 @@
+local name = "unfinished
+local price = 3x
+local symbol = @
+local escape = "bad \q escape"
+local ok = 1
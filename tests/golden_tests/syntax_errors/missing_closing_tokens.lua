@@ -0,0 +1,4 @@
+local t = {1, 2
+local y = 2
+print(y
+local z = 3
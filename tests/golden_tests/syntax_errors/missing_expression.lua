@@ -0,0 +1,8 @@
+local a = 
+local b: number = 10
+
+function add(x: number, y: number): number
+  return x +
+end
+
+local c = add(1, 2)
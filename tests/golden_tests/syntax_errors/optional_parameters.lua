@@ -0,0 +1,7 @@
+function bad(a?: number, b: number)
+end
+function defaults(a: number = 1, b: number)
+end
+type Bad = function(a?: number, b: number): nil
+function good(a: number, b?: number, c: number = 2, ...)
+end
@@ -0,0 +1,10 @@
+local total = 0
+
+if total > 1 then
+  total = total + 1
+
+while total < 10 do
+  total = total + )
+end
+
+return total
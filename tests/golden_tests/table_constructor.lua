@@ -0,0 +1,6 @@
+local key = 3
+local names = {[1] = "one", [2] = "two"; [key] = "three"}
+local point = {x = 1, y = 2;}
+local mixed = {10, 20; label = "mixed", ["quoted"] = true, [key * 2] = false}
+local typed: {[number]: string} = names
+local nested = {inner = {[key] = {1, 2}}, "tail"}
@@ -0,0 +1,39 @@
+local function describe(value: unknown): string
+  if type(value) == "number" then
+    return "number " .. value
+  elseif type(value) == "string" then
+    return "string " .. value
+  end
+  return "something else"
+end
+
+local loose: any = { name = "x" }
+local name: string = loose.name
+local count: number = loose.count or 0
+
+local function fail(message: string): never
+  error("failed: " .. message)
+end
+
+local function check_positive(n: number): number
+  if n < 0 then
+    fail("negative")
+  end
+  return n
+end
+
+local function parse(text: string)
+  error("cannot parse " .. text)
+end
+
+local function kind(value: number | string): string
+  if type(value) == "number" then
+    return "number"
+  elseif type(value) == "string" then
+    return "string"
+  end
+  local impossible: never = value
+  return impossible
+end
+
+print(describe(1), describe("a"), describe(true), name, count, check_positive(2), kind(1), parse)
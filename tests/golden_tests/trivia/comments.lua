@@ -0,0 +1,9 @@
+-- leading comment on its own line
+local value = 1 -- trailing comment
+
+--[[ block comment ]] local other = 2
+--[==[
+long comment with ]] inside
+]==]
+local last = value + other --[[ trailing block ]]
+-- comment at the end of the file
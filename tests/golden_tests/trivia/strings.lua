@@ -0,0 +1,6 @@
+local long = [[
+first line
+second line]]
+local nested = [==[ with ]] inside ]==]
+local escaped = "tab\there \"quoted\"" -- comment after a string
+local single = 'single quoted'
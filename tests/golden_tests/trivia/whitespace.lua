@@ -0,0 +1,10 @@
+local  spaced   =   1
+
+
+	local tabbed = 2 ;
+local a = 1; local b = 2;;
+
+if spaced then
+    print( tabbed )
+end
+   
\ No newline at end of file
@@ -0,0 +1,20 @@
+type Pair = {string, number}
+
+local entry: Pair = {"ana", 10}
+local name: string = entry[1]
+local score: number = entry[2]
+local size = #entry
+
+local list: {number} = {1, 2, 3}
+local numbers = {4, 5, 6}
+local mixed = {7, "eight"}
+
+function total(values: {number}): number
+  local sum = 0
+  for i = 1, #values do
+    sum = sum + values[i]
+  end
+  return sum
+end
+
+print(name, score, size, total(list), total(numbers), mixed[2], #mixed)
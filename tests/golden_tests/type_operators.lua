@@ -0,0 +1,31 @@
+type User = { id: number, name: string }
+type Timestamps = { created: number, updated?: number }
+
+type Record = User & Timestamps
+local record: Record = { id = 1, name = "ana", created = 10 }
+local created: number = record.created
+
+type UserKey = keyof User
+local key: UserKey = "name"
+
+type Draft = Partial<User>
+local draft: Draft = { name = "ana" }
+
+type Frozen = Readonly<User>
+local frozen: Frozen = { id = 1, name = "ana" }
+
+type Summary = Pick<User, "id">
+local summary: Summary = { id = 1 }
+
+type Parse = function(s: string, base: number): number
+type Parsed = ReturnType<Parse>
+type ParseArgs = Parameters<Parse>
+local parsed: Parsed = 10
+local args: ParseArgs = { "10", 16 }
+
+type WithId<T> = T & { id: number }
+local post: WithId<{ title: string }> = { id = 1, title = "hello" }
+local title: string = post.title
+
+type Keys<T> = keyof T
+local post_key: Keys<{ title: string, body: string }> = "body"
@@ -0,0 +1,22 @@
+function sum(...: number): number
+  local total = 0
+  for _, value in ipairs({...}) do
+    total = total + value
+  end
+  return total
+end
+
+function count(...: string): number
+  return select('#', ...)
+end
+
+function rest(first: number, ...: number): number
+  local second, third = ...
+  return first + sum(...)
+end
+
+local forward = function(...)
+  print(...)
+end
+
+print(sum(1, 2, 3), count("a", "b"), rest(1, 2, 3), select(2, "a", "b"))
@@ -0,0 +1,156 @@
+use stella_checker::ast::ast::{BinaryOperator, UnaryOperator};
+use stella_checker::types::{LiteralType, Type};
+
+const BINARY_OPERATORS: [BinaryOperator; 21] = [
+  BinaryOperator::Add,
+  BinaryOperator::Subtract,
+  BinaryOperator::Multiply,
+  BinaryOperator::Divide,
+  BinaryOperator::Modulus,
+  BinaryOperator::And,
+  BinaryOperator::Or,
+  BinaryOperator::Equal,
+  BinaryOperator::NotEqual,
+  BinaryOperator::LessThan,
+  BinaryOperator::GreaterThan,
+  BinaryOperator::LessThanOrEqual,
+  BinaryOperator::GreaterThanOrEqual,
+  BinaryOperator::DoubleDot,
+  BinaryOperator::DoubleSlash,
+  BinaryOperator::Power,
+  BinaryOperator::BitwiseAnd,
+  BinaryOperator::BitwiseOr,
+  BinaryOperator::BitwiseXor,
+  BinaryOperator::ShiftLeft,
+  BinaryOperator::ShiftRight,
+];
+
+const UNARY_OPERATORS: [UnaryOperator; 4] =
+  [UnaryOperator::Negate, UnaryOperator::Not, UnaryOperator::Hash, UnaryOperator::BitwiseNot];
+
+fn operand_types() -> Vec<Type> {
+  vec![
+    Type::Number,
+    Type::String,
+    Type::Boolean,
+    Type::Nil,
+    Type::Unknown,
+    Type::Any,
+    Type::Never,
+    Type::Literal(LiteralType::Number("1".to_string())),
+    Type::Literal(LiteralType::String("a".to_string())),
+    Type::Literal(LiteralType::Boolean(true)),
+    Type::new_option(Type::Number),
+    Type::new_option(Type::String),
+    Type::new_union(vec![Type::Number, Type::String]),
+    Type::new_union(vec![Type::Number, Type::Boolean]),
+    Type::new_table(Some(Type::Number), None),
+    Type::new_function(vec![], Type::Nil),
+  ]
+}
+
+// `any` and `never` take every operator, `unknown` only comparisons
+fn is_unchecked(operand: &Type) -> bool {
+  matches!(operand, Type::Any | Type::Never)
+}
+
+fn is_number_operand(operand: &Type) -> bool {
+  matches!(operand.widen(), Type::Number) || is_unchecked(operand)
+}
+
+fn is_text_operand(operand: &Type) -> bool {
+  match operand {
+    Type::Union(union) => union.types.iter().all(is_text_operand),
+    _ => matches!(operand.widen(), Type::Number | Type::String) || is_unchecked(operand),
+  }
+}
+
+fn is_operand_of(operand: &Type, expected: &Type) -> bool {
+  operand.widen() == *expected || is_unchecked(operand)
+}
+
+fn expected_binary_result(left: &Type, operator: &BinaryOperator, right: &Type) -> Option<Type> {
+  use BinaryOperator::*;
+  match operator {
+    Add | Subtract | Multiply | Divide | Modulus | DoubleSlash | Power | BitwiseAnd | BitwiseOr | BitwiseXor
+    | ShiftLeft | ShiftRight => (is_number_operand(left) && is_number_operand(right)).then_some(Type::Number),
+    DoubleDot => (is_text_operand(left) && is_text_operand(right)).then_some(Type::String),
+    LessThan | GreaterThan | LessThanOrEqual | GreaterThanOrEqual => {
+      let numbers = is_operand_of(left, &Type::Number) && is_operand_of(right, &Type::Number);
+      let strings = is_operand_of(left, &Type::String) && is_operand_of(right, &Type::String);
+      (numbers || strings).then_some(Type::Boolean)
+    }
+    Equal | NotEqual => Some(Type::Boolean),
+    And | Or if left == right => Some(left.clone()),
+    And | Or => Some(Type::new_union(vec![left.clone(), right.clone()])),
+  }
+}
+
+fn expected_unary_result(operator: &UnaryOperator, operand: &Type) -> Option<Type> {
+  match operator {
+    UnaryOperator::Negate | UnaryOperator::BitwiseNot => is_number_operand(operand).then_some(Type::Number),
+    UnaryOperator::Hash => {
+      (matches!(operand.widen(), Type::String | Type::Table(_)) || is_unchecked(operand)).then_some(Type::Number)
+    }
+    UnaryOperator::Not => Some(Type::Boolean),
+  }
+}
+
+#[test]
+fn test_every_binary_operator_on_every_operand_pair() {
+  let operands = operand_types();
+  for operator in BINARY_OPERATORS.iter() {
+    for left in operands.iter() {
+      for right in operands.iter() {
+        assert_eq!(
+          left.get_operator_result_type(right, operator),
+          expected_binary_result(left, operator, right),
+          "`{}` {} `{}`",
+          left,
+          operator,
+          right
+        );
+      }
+    }
+  }
+}
+
+#[test]
+fn test_every_unary_operator_on_every_operand() {
+  for operator in UNARY_OPERATORS.iter() {
+    for operand in operand_types().iter() {
+      assert_eq!(
+        operand.get_unary_operator_result_type(operator),
+        expected_unary_result(operator, operand),
+        "{} `{}`",
+        operator,
+        operand
+      );
+    }
+  }
+}
+
+#[test]
+fn test_option_operands_are_unsupported_until_narrowed() {
+  let option = Type::new_option(Type::String);
+  assert_eq!(option.get_operator_result_type(&Type::String, &BinaryOperator::DoubleDot), None);
+  assert_eq!(option.get_operator_result_type(&Type::Nil, &BinaryOperator::Equal), Some(Type::Boolean));
+  assert_eq!(option.get_unary_operator_result_type(&UnaryOperator::Hash), None);
+}
+
+#[test]
+fn test_unknown_operands_have_to_be_narrowed() {
+  assert_eq!(Type::Unknown.get_operator_result_type(&Type::Number, &BinaryOperator::Add), None);
+  assert_eq!(Type::Unknown.get_operator_result_type(&Type::Nil, &BinaryOperator::Equal), Some(Type::Boolean));
+  assert_eq!(Type::Any.get_operator_result_type(&Type::Number, &BinaryOperator::Add), Some(Type::Number));
+}
+
+#[test]
+fn test_length_of_a_tuple_is_known() {
+  let mut tuple = Type::new_table(None, None);
+  if let Type::Table(table) = &mut tuple {
+    table.tuple = Some(vec![Type::Number, Type::String]);
+  }
+  let length = tuple.get_unary_operator_result_type(&UnaryOperator::Hash);
+  assert_eq!(length, Some(Type::Literal(LiteralType::Number("2".to_string()))));
+}
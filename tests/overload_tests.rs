@@ -0,0 +1,36 @@
+use stella_checker::types::{FunctionType, Type};
+
+fn function(params: Vec<Type>, return_type: Type) -> FunctionType {
+  FunctionType { generics: vec![], params, return_type: Box::new(return_type) }
+}
+
+fn number_or_string() -> Type {
+  let from_number = function(vec![Type::Number], Type::Number);
+  let from_string = function(vec![Type::String], Type::String);
+  Type::new_overload(vec![from_number, from_string])
+}
+
+#[test]
+fn test_overload_is_usable_as_one_of_its_signatures() {
+  let signature = Type::new_function(vec![Type::String], Type::String);
+  assert!(signature.check_match(&number_or_string()));
+  let other = Type::new_function(vec![Type::Boolean], Type::String);
+  assert!(!other.check_match(&number_or_string()));
+}
+
+#[test]
+fn test_function_stands_for_an_overload_only_if_it_fits_every_signature() {
+  let number_only = Type::new_function(vec![Type::Number], Type::Number);
+  assert!(!number_or_string().check_match(&number_only));
+  let untyped = Type::new_function(vec![Type::Any], Type::Any);
+  assert!(number_or_string().check_match(&untyped));
+}
+
+#[test]
+fn test_parameters_that_take_nil_can_be_left_out() {
+  assert!(Type::new_option(Type::Number).accepts_nil());
+  assert!(Type::new_union(vec![Type::String, Type::Nil]).accepts_nil());
+  assert!(Type::Nil.accepts_nil());
+  assert!(!Type::Number.accepts_nil());
+  assert!(!Type::Any.accepts_nil());
+}
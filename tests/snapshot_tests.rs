@@ -6,6 +6,9 @@ use std::ffi::OsString;
 use std::fs;
 use std::path::Path;
 use stella_checker::ast::tokens::{Token, TokenKind};
+use stella_checker::checker::Checker;
+use stella_checker::cst::SyntaxTree;
+use stella_checker::diagnostics::Diagnostic;
 use stella_checker::lexer::Lexer;
 use stella_checker::parser::parser::Parser;
 
@@ -71,15 +74,79 @@ fn test_parser_snapshot() {
     settings.bind(|| {
       let mut parser = create_parser(source_code, file_name);
       let file_name = format_file_name_with_module(file_name, "parser");
-      let program = parser.parse_program();
+      let (program, _) = parser.parse_program();
       assert_ron_snapshot!(file_name.clone(), program);
     });
   }
 }
 
-// #[test]
-// fn test_type_checker_snapshot() {
-//   let test_files = read_test_files_with_pattern("tests/golden_tests/*.lua");
-//   println!("test_type_checker_snapshot: {:?}", test_files);
-//   // hei, please :) implement me
-// }
+// the full tree is large, so it's only snapshotted for the fixtures about trivia
+#[test]
+fn test_syntax_tree_snapshot() {
+  let test_files = read_test_files_with_pattern("tests/golden_tests/trivia/*.lua");
+  let settings = setings_snapshot();
+  for (file_name, source_code) in test_files.iter() {
+    settings.bind(|| {
+      let (tree, _) = SyntaxTree::parse(source_code, file_name);
+      let file_name = format_file_name_with_module(file_name, "cst");
+      assert_ron_snapshot!(file_name.clone(), tree);
+    });
+  }
+}
+
+#[test]
+fn test_syntax_tree_round_trip() {
+  let test_files = read_test_files_with_pattern("tests/golden_tests/**/*.lua");
+  for (file_name, source_code) in test_files.iter() {
+    let (tree, _) = SyntaxTree::parse(source_code, file_name);
+    assert_eq!(&tree.to_source(), source_code, "{} doesn't round-trip", file_name);
+  }
+}
+
+#[test]
+fn test_syntax_errors_snapshot() {
+  let test_files = read_test_files_with_pattern("tests/golden_tests/syntax_errors/*.lua");
+  let settings = setings_snapshot();
+  for (file_name, source_code) in test_files.iter() {
+    settings.bind(|| {
+      let mut parser = create_parser(source_code, file_name);
+      let file_name = format_file_name_with_module(file_name, "syntax_errors");
+      let (program, diagnostics) = parser.parse_program();
+      let errors: Vec<_> = diagnostics.into_iter().map(|diagnostic| (diagnostic.message, diagnostic.range)).collect();
+      assert_ron_snapshot!(file_name.clone(), (program, errors));
+    });
+  }
+}
+
+// the source under a diagnostic, so the snapshot reads without counting offsets
+fn diagnostic_source(source_code: &str, diagnostic: &Diagnostic) -> String {
+  match &diagnostic.range {
+    Some(range) => source_code.chars().skip(range.start).take(range.end.saturating_sub(range.start)).collect(),
+    None => String::new(),
+  }
+}
+
+#[test]
+fn test_type_checker_snapshot() {
+  let test_files = read_test_files_with_pattern("tests/golden_tests/*.lua");
+  let settings = setings_snapshot();
+  for (file_name, source_code) in test_files.iter() {
+    settings.bind(|| {
+      let mut parser = create_parser(source_code, file_name);
+      let (program, _) = parser.parse_program();
+      let path_name = format!("tests/golden_tests/{}", file_name);
+      let mut checker = Checker::new(&path_name, source_code);
+      checker.check_program(&program);
+      let diagnostics: Vec<_> = checker
+        .diagnostics
+        .diagnostics
+        .iter()
+        .map(|diagnostic| {
+          (format!("{:?}", diagnostic.level), diagnostic.message.clone(), diagnostic_source(source_code, diagnostic))
+        })
+        .collect();
+      let file_name = format_file_name_with_module(file_name, "checker");
+      assert_ron_snapshot!(file_name.clone(), diagnostics);
+    });
+  }
+}